@@ -1 +1,5 @@
 pub mod one_d;
+pub mod multi_d;
+pub mod monte_carlo;
+pub mod ode;
+pub mod sde;
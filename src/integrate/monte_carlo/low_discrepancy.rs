@@ -0,0 +1,151 @@
+
+// Low-discrepancy sequences fill the unit hypercube far more evenly than
+// random points do. Each is an iterator over points in [0, 1)^d.
+
+// The Halton sequence, where coordinate k is the radical inverse of the point's
+// index in the k-th prime base. It works in any dimension but degrades when
+// there are many dimensions.
+#[derive(Debug, Clone)]
+pub struct Halton {
+    bases: Vec<u64>,
+    index: u64,
+}
+
+impl Halton {
+    pub fn new(dim: usize) -> Self {
+        let mut bases = Vec::with_capacity(dim);
+        let mut candidate = 2;
+        while bases.len() < dim {
+            if bases.iter().all(|&p| candidate % p != 0) {
+                bases.push(candidate);
+            }
+            candidate += 1;
+        }
+        Halton { bases, index: 0 }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.bases.len()
+    }
+}
+
+impl Iterator for Halton {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let point = self.bases.iter().map(|&base| radical_inverse(self.index, base)).collect();
+        self.index += 1;
+        Some(point)
+    }
+}
+
+// Reflects the digits of n in the given base about the radix point
+fn radical_inverse(mut n: u64, base: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut scale = inv_base;
+    let mut digest = 0.0;
+    while n > 0 {
+        digest += (n % base) as f64 * scale;
+        n /= base;
+        scale *= inv_base;
+    }
+    digest
+}
+
+// The number of bits in each coordinate of a Sobol point
+const SOBOL_BITS: usize = 32;
+
+// Primitive polynomials and initial direction numbers from Joe and Kuo
+// (new-joe-kuo-6.21201) for dimensions 2 and up. Each entry is the degree s,
+// the polynomial's interior coefficients a, and m_1..m_s. The first dimension
+// is the van der Corput sequence.
+const SOBOL_TABLE: [(usize, u32, &[u32]); 20] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+// The (unscrambled) Sobol sequence, generated in Gray code order. Its
+// balance properties are best when a power of two number of points is used.
+#[derive(Debug, Clone)]
+pub struct Sobol {
+    // directions[d][k] is the k-th direction number of dimension d
+    directions: Vec<[u32; SOBOL_BITS]>,
+    current: Vec<u32>,
+    index: u64,
+}
+
+impl Sobol {
+    // The largest dimension with direction numbers available
+    pub const MAX_DIMENSION: usize = SOBOL_TABLE.len() + 1;
+
+    pub fn new(dim: usize) -> Result<Self, &'static str> {
+        if dim == 0 || dim > Self::MAX_DIMENSION {
+            return Err("The Sobol sequence is only available in dimensions 1 through 21.");
+        }
+        let mut directions = Vec::with_capacity(dim);
+        let mut first = [0; SOBOL_BITS];
+        for (k, v) in first.iter_mut().enumerate() {
+            *v = 1 << (SOBOL_BITS - 1 - k);
+        }
+        directions.push(first);
+        for &(s, a, m) in SOBOL_TABLE.iter().take(dim - 1) {
+            let mut v = [0; SOBOL_BITS];
+            for k in 0..s {
+                v[k] = m[k] << (SOBOL_BITS - 1 - k);
+            }
+            for k in s..SOBOL_BITS {
+                v[k] = v[k - s] ^ (v[k - s] >> s);
+                for j in 1..s {
+                    if (a >> (s - 1 - j)) & 1 == 1 {
+                        v[k] ^= v[k - j];
+                    }
+                }
+            }
+            directions.push(v);
+        }
+        Ok(Sobol { directions, current: vec![0; dim], index: 0 })
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.directions.len()
+    }
+}
+
+impl Iterator for Sobol {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index >= 1 << SOBOL_BITS {
+            return None;
+        }
+        let scale = 1.0 / (1u64 << SOBOL_BITS) as f64;
+        let point = self.current.iter().map(|&x| x as f64 * scale).collect();
+        // Each point differs from the last in the direction of the lowest zero bit of the index
+        let bit = self.index.trailing_ones() as usize;
+        if bit < SOBOL_BITS {
+            for (x, v) in self.current.iter_mut().zip(self.directions.iter()) {
+                *x ^= v[bit];
+            }
+        }
+        self.index += 1;
+        Some(point)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod monte_carlo_integrator;
+pub mod low_discrepancy;
+pub mod quasi_monte_carlo_integrator;
@@ -0,0 +1,193 @@
+
+pub use crate::math::random::Rng;
+pub use crate::math::scalar::{Real, Scalar};
+pub use crate::spatial::vector_3d::Vector3D;
+
+// The estimate of a stochastic integration along with its standard error,
+// i.e. the standard deviation of the estimate itself
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonteCarloResult<S>
+where S: Scalar {
+    pub value: S,
+    pub std_error: S,
+    // The number of times the integrand was evaluated
+    pub samples: usize,
+}
+
+// Running mean and variance, using Welford's algorithm to avoid cancellation
+pub(crate) struct Moments<S> {
+    pub count: usize,
+    pub mean: S,
+    // The sum of squared deviations from the mean
+    pub squares: S,
+}
+
+impl<S> Moments<S>
+where S: Real
+{
+    pub fn new() -> Self {
+        Moments { count: 0, mean: S::zero(), squares: S::zero() }
+    }
+
+    pub fn push(&mut self, x: S) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / S::from(self.count as f64);
+        self.squares += delta * (x - self.mean);
+    }
+
+    // The unbiased sample variance
+    pub fn variance(&self) -> S {
+        if self.count < 2 {
+            S::zero()
+        } else {
+            self.squares / S::from((self.count - 1) as f64)
+        }
+    }
+
+    // The standard error of the mean
+    pub fn std_error(&self) -> S {
+        if self.count == 0 {
+            S::zero()
+        } else {
+            (self.variance() / S::from(self.count as f64)).sqrt()
+        }
+    }
+}
+
+// Integrates over hyper-rectangles by averaging the integrand at random
+// points. The random stream is fully determined by the seed.
+pub struct MonteCarloIntegrator {
+    // The number of integrand evaluations to use
+    pub samples: usize,
+    pub seed: u64,
+}
+
+impl MonteCarloIntegrator {
+    pub fn new(samples: usize, seed: u64) -> Self {
+        MonteCarloIntegrator { samples, seed }
+    }
+
+    // Integrates f over the box with the given lower and upper corners,
+    // sampling it uniformly
+    pub fn integrate<S, F>(&self, mut f: F, lower: &[S], upper: &[S]) -> Result<MonteCarloResult<S>, &'static str>
+    where
+        S: Real,
+        F: FnMut(&[S]) -> S,
+    {
+        check_corners(lower, upper)?;
+        let mut rng = Rng::new(self.seed);
+        let volume = volume(lower, upper);
+        let mut point = lower.to_vec();
+        let mut moments = Moments::new();
+        for _ in 0..self.samples {
+            for (p, (&lo, &hi)) in point.iter_mut().zip(lower.iter().zip(upper.iter())) {
+                *p = lo + (hi - lo) * S::from(rng.uniform());
+            }
+            moments.push(f(&point));
+        }
+        Ok(MonteCarloResult {
+            value: moments.mean * volume,
+            std_error: moments.std_error() * volume.abs(),
+            samples: self.samples,
+        })
+    }
+
+    pub fn integrate_vector_3d<S, F>(&self, mut f: F, lower: Vector3D<S>, upper: Vector3D<S>) -> Result<MonteCarloResult<S>, &'static str>
+    where
+        S: Real,
+        F: FnMut(Vector3D<S>) -> S,
+    {
+        self.integrate(
+            |p| f(Vector3D::new(p[0], p[1], p[2])),
+            &[lower.x, lower.y, lower.z],
+            &[upper.x, upper.y, upper.z],
+        )
+    }
+
+    // Splits every side of the box into the given number of strata and
+    // samples each of the resulting cells separately. This removes the
+    // variance caused by points clumping together. The samples are spread
+    // evenly over the cells, with at least two in each.
+    pub fn integrate_stratified<S, F>(&self, mut f: F, lower: &[S], upper: &[S], strata: usize) -> Result<MonteCarloResult<S>, &'static str>
+    where
+        S: Real,
+        F: FnMut(&[S]) -> S,
+    {
+        check_corners(lower, upper)?;
+        let mut rng = Rng::new(self.seed);
+        let dim = lower.len();
+        let strata = strata.max(1);
+        let cells = u32::try_from(dim)
+            .ok()
+            .and_then(|d| strata.checked_pow(d))
+            .ok_or("There are too many strata to count in this many dimensions.")?;
+        let per_cell = (self.samples / cells).max(2);
+        let cell_volume = volume(lower, upper) / S::from(cells as f64);
+        let widths: Vec<S> = lower.iter().zip(upper.iter()).map(|(&lo, &hi)| (hi - lo) / S::from(strata as f64)).collect();
+        let mut index = vec![0; dim];
+        let mut point = lower.to_vec();
+        let mut value = S::zero();
+        let mut variance = S::zero();
+        for _ in 0..cells {
+            let mut moments = Moments::new();
+            for _ in 0..per_cell {
+                for d in 0..dim {
+                    let offset = S::from(index[d] as f64) + S::from(rng.uniform());
+                    point[d] = lower[d] + widths[d] * offset;
+                }
+                moments.push(f(&point));
+            }
+            value += moments.mean * cell_volume;
+            variance += moments.variance() / S::from(per_cell as f64) * cell_volume * cell_volume;
+            // Move on to the next cell, like an odometer
+            for i in index.iter_mut() {
+                *i += 1;
+                if *i < strata {
+                    break;
+                }
+                *i = 0;
+            }
+        }
+        Ok(MonteCarloResult { value, std_error: variance.sqrt(), samples: cells * per_cell })
+    }
+
+    // Integrates f over the support of a probability density by averaging
+    // f / density at points drawn from that density. Sampling more often
+    // where |f| is large reduces the variance. sample must draw points
+    // distributed according to density.
+    pub fn integrate_importance<S, F, D, Q>(&self, mut f: F, mut sample: Q, density: D) -> MonteCarloResult<S>
+    where
+        S: Real,
+        F: FnMut(&[S]) -> S,
+        D: Fn(&[S]) -> S,
+        Q: FnMut(&mut Rng) -> Vec<S>,
+    {
+        let mut rng = Rng::new(self.seed);
+        let mut moments = Moments::new();
+        for _ in 0..self.samples {
+            let point = sample(&mut rng);
+            moments.push(f(&point) / density(&point));
+        }
+        MonteCarloResult { value: moments.mean, std_error: moments.std_error(), samples: self.samples }
+    }
+}
+
+// Checks that both corners of a box have a coordinate for every dimension
+pub(crate) fn check_corners<S>(lower: &[S], upper: &[S]) -> Result<(), &'static str>
+where S: Real {
+    if lower.len() == upper.len() {
+        Ok(())
+    } else {
+        Err("The lower and upper corners must have the same number of coordinates.")
+    }
+}
+
+pub(crate) fn volume<S>(lower: &[S], upper: &[S]) -> S
+where S: Real {
+    let mut digest = S::from(1.0);
+    for (&lo, &hi) in lower.iter().zip(upper.iter()) {
+        digest *= hi - lo;
+    }
+    digest
+}
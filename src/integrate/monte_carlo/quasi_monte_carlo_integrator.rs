@@ -0,0 +1,85 @@
+
+pub use super::low_discrepancy::{Halton, Sobol};
+pub use super::monte_carlo_integrator::{MonteCarloResult, Real, Rng, Scalar, Vector3D};
+
+use super::monte_carlo_integrator::{check_corners, volume, Moments};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceKind {
+    Sobol,
+    Halton,
+}
+
+// Integrates over hyper-rectangles by averaging the integrand over a
+// low-discrepancy sequence, which typically converges much faster than random
+// sampling. A single deterministic sequence says nothing about its own error,
+// so the samples are split between several randomly shifted copies of the
+// sequence (a Cranley-Patterson rotation). The spread of their estimates gives
+// the standard error.
+pub struct QuasiMonteCarloIntegrator {
+    pub sequence: SequenceKind,
+    // The total number of integrand evaluations, split evenly between the replicates
+    pub samples: usize,
+    // The number of randomly shifted copies of the sequence
+    pub replicates: usize,
+    // Seeds the random shifts
+    pub seed: u64,
+}
+
+impl QuasiMonteCarloIntegrator {
+    pub fn new(sequence: SequenceKind, samples: usize, seed: u64) -> Self {
+        QuasiMonteCarloIntegrator { sequence, samples, replicates: 8, seed }
+    }
+
+    // Integrates f over the box with the given lower and upper corners
+    pub fn integrate<S, F>(&self, mut f: F, lower: &[S], upper: &[S]) -> Result<MonteCarloResult<S>, &'static str>
+    where
+        S: Real,
+        F: FnMut(&[S]) -> S,
+    {
+        check_corners(lower, upper)?;
+        let dim = lower.len();
+        let replicates = self.replicates.max(1);
+        let per_replicate = self.samples / replicates;
+        if per_replicate == 0 {
+            return Err("There must be at least one sample for each replicate.");
+        }
+        let units: Vec<Vec<f64>> = match self.sequence {
+            SequenceKind::Sobol => Sobol::new(dim)?.take(per_replicate).collect(),
+            SequenceKind::Halton => Halton::new(dim).take(per_replicate).collect(),
+        };
+        let mut rng = Rng::new(self.seed);
+        let mut point = lower.to_vec();
+        let mut estimates = Moments::new();
+        for _ in 0..replicates {
+            let shift: Vec<f64> = (0..dim).map(|_| rng.uniform()).collect();
+            let mut moments = Moments::new();
+            for unit in units.iter() {
+                for d in 0..dim {
+                    let u = (unit[d] + shift[d]).fract();
+                    point[d] = lower[d] + (upper[d] - lower[d]) * S::from(u);
+                }
+                moments.push(f(&point));
+            }
+            estimates.push(moments.mean);
+        }
+        let volume = volume(lower, upper);
+        Ok(MonteCarloResult {
+            value: estimates.mean * volume,
+            std_error: estimates.std_error() * volume.abs(),
+            samples: replicates * per_replicate,
+        })
+    }
+
+    pub fn integrate_vector_3d<S, F>(&self, mut f: F, lower: Vector3D<S>, upper: Vector3D<S>) -> Result<MonteCarloResult<S>, &'static str>
+    where
+        S: Real,
+        F: FnMut(Vector3D<S>) -> S,
+    {
+        self.integrate(
+            |p| f(Vector3D::new(p[0], p[1], p[2])),
+            &[lower.x, lower.y, lower.z],
+            &[upper.x, upper.y, upper.z],
+        )
+    }
+}
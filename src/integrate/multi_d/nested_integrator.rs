@@ -0,0 +1,128 @@
+
+pub use crate::integrate::one_d::gauss_kronrod_integrator::GaussKronrodIntegrator;
+pub use crate::integrate::one_d::integrator::{AdaptiveIntegrator, QuadratureResult, Real, Scalar};
+pub use crate::spatial::vector_3d::Vector3D;
+
+use crate::integrate::one_d::trapezoid_integrator::interval_area;
+
+use std::marker::PhantomData;
+
+// The bounds of one variable given the values of the variables outside of it
+pub type Limits<'a, S> = &'a dyn Fn(&[S]) -> (S, S);
+
+// Integrates over multi-dimensional regions by nesting one-dimensional
+// integrals, like SciPy's dblquad, tplquad and nquad. The bounds of each
+// variable may depend on the variables outside of it, so any region that can
+// be swept out one coordinate at a time can be integrated over.
+//
+// The error estimate is the outer integral's estimate plus the integral of
+// the inner estimates, taken with the trapezoid rule through the points the
+// outer integral sampled, so it stays finite over infinite ranges. The result
+// has only converged if every one-dimensional integral did.
+pub struct NestedIntegrator<S, I>
+where
+    S: Real,
+    I: AdaptiveIntegrator<S>,
+{
+    pub integrator: I,
+    _s: PhantomData<S>,
+}
+
+impl<S> Default for NestedIntegrator<S, GaussKronrodIntegrator<S>>
+where S: Real
+{
+    fn default() -> Self {
+        Self::new(GaussKronrodIntegrator::new())
+    }
+}
+
+impl<S, I> NestedIntegrator<S, I>
+where
+    S: Real,
+    I: AdaptiveIntegrator<S>,
+{
+    // Every nested integral is computed with the given integrator
+    pub fn new(integrator: I) -> Self {
+        NestedIntegrator {
+            integrator,
+            _s: PhantomData,
+        }
+    }
+
+    // Integrates f(x, y) over a <= x <= b and y_lo(x) <= y <= y_hi(x)
+    pub fn dblquad<F, G, H>(&self, mut f: F, a: S, b: S, y_lo: G, y_hi: H) -> QuadratureResult<S>
+    where
+        F: FnMut(S, S) -> S,
+        G: Fn(S) -> S,
+        H: Fn(S) -> S,
+    {
+        let x_limits = |_: &[S]| (a, b);
+        let y_limits = |p: &[S]| (y_lo(p[0]), y_hi(p[0]));
+        self.nquad(|p| f(p[0], p[1]), &[&x_limits, &y_limits])
+    }
+
+    // Integrates f over a <= x <= b, y_lo(x) <= y <= y_hi(x) and z_lo(x, y) <= z <= z_hi(x, y)
+    #[allow(clippy::too_many_arguments)]
+    pub fn tplquad<F, G, H, P, Q>(&self, mut f: F, a: S, b: S, y_lo: G, y_hi: H, z_lo: P, z_hi: Q) -> QuadratureResult<S>
+    where
+        F: FnMut(Vector3D<S>) -> S,
+        G: Fn(S) -> S,
+        H: Fn(S) -> S,
+        P: Fn(S, S) -> S,
+        Q: Fn(S, S) -> S,
+    {
+        let x_limits = |_: &[S]| (a, b);
+        let y_limits = |p: &[S]| (y_lo(p[0]), y_hi(p[0]));
+        let z_limits = |p: &[S]| (z_lo(p[0], p[1]), z_hi(p[0], p[1]));
+        self.nquad(|p| f(Vector3D::new(p[0], p[1], p[2])), &[&x_limits, &y_limits, &z_limits])
+    }
+
+    // Integrates f over as many variables as there are limits. The first
+    // limits are for the outermost variable and are given an empty slice; the
+    // limits for each later variable are given the values of all variables
+    // before it.
+    pub fn nquad<F>(&self, mut f: F, limits: &[Limits<S>]) -> QuadratureResult<S>
+    where F: FnMut(&[S]) -> S
+    {
+        let mut point = Vec::with_capacity(limits.len());
+        self.nest(&mut f, limits, &mut point)
+    }
+
+    fn nest<F>(&self, f: &mut F, limits: &[Limits<S>], point: &mut Vec<S>) -> QuadratureResult<S>
+    where F: FnMut(&[S]) -> S
+    {
+        let depth = point.len();
+        if depth == limits.len() {
+            return QuadratureResult { value: f(point), abs_error: S::zero(), evaluations: 1, converged: true };
+        }
+        let (lo, hi) = limits[depth](point);
+        // The error of the inner integral at each point sampled
+        let mut inner_errors = Vec::new();
+        let mut evaluations = 0;
+        let mut converged = true;
+        let outer = self.integrator.integrate(
+            |x| {
+                point.push(x);
+                let inner = self.nest(f, limits, point);
+                point.pop();
+                inner_errors.push((x, inner.abs_error));
+                evaluations += inner.evaluations;
+                converged &= inner.converged;
+                inner.value
+            },
+            lo,
+            hi,
+        );
+        inner_errors.sort_by(|l, r| l.0.to_f64().total_cmp(&r.0.to_f64()));
+        let mut abs_error = outer.abs_error;
+        for w in inner_errors.windows(2) {
+            abs_error += interval_area(w[0].0, w[0].1, w[1].0, w[1].1);
+        }
+        QuadratureResult {
+            value: outer.value,
+            abs_error,
+            evaluations,
+            converged: converged && outer.converged,
+        }
+    }
+}
@@ -0,0 +1,349 @@
+
+pub use super::ode_solver::{DenseMatrix, Event, OdeError, OdeSolution, OdeState, Real, Scalar};
+pub use crate::math::lin_alg::dense_matrix::LuDecomposition;
+
+use super::ode_event::EventMonitor;
+use super::ode_solver::{initial_step, is_finite, jacobian, min_step, newton_tolerance, scaled_norm, solve_flattened, FlatJacobian, FlatRhs};
+
+const MAX_ORDER: usize = 5;
+const NEWTON_MAXITER: usize = 4;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+
+// The NDF modifications to the BDF formulas of each order (Shampine and Reichelt)
+const KAPPA: [f64; MAX_ORDER + 1] = [0.0, -0.1850, -1.0 / 9.0, -0.0823, -0.0415, 0.0];
+
+// A variable order (1 to 5), variable step backward differentiation formula
+// solver for stiff systems, following SciPy's BDF. It uses the numerical
+// differentiation formulas of Shampine and Reichelt, which are a little more
+// efficient than the classic BDFs, and stores the solution's history as
+// backward differences so that the step size is cheap to change.
+//
+// Each step solves a nonlinear system with a simplified Newton iteration,
+// which needs the Jacobian df/dy. This is either given or found by finite
+// differences, and is only recomputed when the iteration fails to converge.
+pub struct BdfSolver<S>
+where S: Real {
+    pub rtol: S,
+    pub atol: S,
+    // The size of the first step; estimated from f if None
+    pub first_step: Option<S>,
+    pub max_step: S,
+    // The number of steps (accepted or rejected) after which to give up
+    pub max_steps: usize,
+}
+
+impl<S> Default for BdfSolver<S>
+where S: Real
+{
+    fn default() -> Self {
+        BdfSolver {
+            rtol: S::from(1e-3),
+            atol: S::from(1e-6),
+            first_step: None,
+            max_step: S::from(f64::INFINITY),
+            max_steps: 100_000,
+        }
+    }
+}
+
+impl<S> BdfSolver<S>
+where S: Real
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Solves from y(t0) = y0 to t1, finding the Jacobian by finite differences
+    pub fn solve<Y, F>(&self, f: F, t0: S, t1: S, y0: Y) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        self.solve_with_events(f, t0, t1, y0, &mut [])
+    }
+
+    // Solves from y(t0) = y0 to t1, recording the crossings of the events and
+    // stopping early at the first terminal one
+    pub fn solve_with_events<Y, F>(&self, f: F, t0: S, t1: S, y0: Y, events: &mut [Event<S, Y>]) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        solve_flattened(f, None::<fn(S, &Y) -> DenseMatrix<S>>, y0, events, |f, jac, y0, events| self.solve_flat(f, jac, t0, t1, y0, events))
+    }
+
+    // Solves from y(t0) = y0 to t1, where jac(t, y) is the Jacobian of f.
+    // Entry (i, j) is the derivative of component i of f by component j of y.
+    pub fn solve_with_jacobian<Y, F, J>(&self, f: F, jac: J, t0: S, t1: S, y0: Y) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        J: FnMut(S, &Y) -> DenseMatrix<S>,
+    {
+        self.solve_with_jacobian_and_events(f, jac, t0, t1, y0, &mut [])
+    }
+
+    // solve_with_jacobian, watching for events like solve_with_events
+    pub fn solve_with_jacobian_and_events<Y, F, J>(&self, f: F, jac: J, t0: S, t1: S, y0: Y, events: &mut [Event<S, Y>]) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        J: FnMut(S, &Y) -> DenseMatrix<S>,
+    {
+        solve_flattened(f, Some(jac), y0, events, |f, jac, y0, events| self.solve_flat(f, jac, t0, t1, y0, events))
+    }
+
+    fn solve_flat(
+        &self,
+        f: &mut FlatRhs<S>,
+        mut jac: Option<&mut FlatJacobian<S>>,
+        t0: S,
+        t1: S,
+        y0: Vec<S>,
+        events: &mut [Event<S, Vec<S>>],
+    ) -> Result<OdeSolution<S, Vec<S>>, OdeError> {
+        let n = y0.len();
+        let direction = if t1 > t0 { S::from(1.0) } else { S::from(-1.0) };
+        let f0 = f(t0, &y0);
+        let mut solution = OdeSolution::new(t0, y0.clone(), f0.clone());
+        solution.evaluations += 1;
+        if t0 == t1 {
+            return Ok(solution);
+        }
+        let mut monitor = EventMonitor::new(events, t0, &y0);
+        let mut h_abs = match self.first_step {
+            Some(h) => h.abs(),
+            None => initial_step(&mut |t, y: &Vec<S>| f(t, y), t0, &y0, &f0, direction, 1, self.rtol, self.atol, &mut solution.evaluations),
+        };
+        h_abs = h_abs.min(self.max_step).min((t1 - t0).abs());
+        let mut jac_matrix = jacobian(f, &mut jac, t0, &y0, Some(&f0), self.atol, &mut solution);
+        let mut lu: Option<LuDecomposition<S>> = None;
+        let newton_tol = newton_tolerance(self.rtol);
+
+        // gamma[k] is the sum of 1/j for j = 1..k
+        let mut gamma = [0.0; MAX_ORDER + 1];
+        for k in 1..=MAX_ORDER {
+            gamma[k] = gamma[k - 1] + 1.0 / k as f64;
+        }
+        let alpha: Vec<f64> = (0..=MAX_ORDER).map(|k| (1.0 - KAPPA[k]) * gamma[k]).collect();
+        let error_const: Vec<f64> = (0..=MAX_ORDER).map(|k| KAPPA[k] * gamma[k] + 1.0 / (k as f64 + 1.0)).collect();
+
+        // differences[k] is the k-th backward difference of the solution,
+        // scaled by the step size
+        let mut differences = vec![vec![S::zero(); n]; MAX_ORDER + 3];
+        differences[0] = y0;
+        differences[1] = f0.iter().map(|&v| v * h_abs * direction).collect();
+        let mut order = 1;
+        let mut equal_steps = 0;
+        let mut attempts = 0;
+        let mut t = t0;
+        while t != t1 {
+            if h_abs > self.max_step {
+                change_differences(&mut differences, order, (self.max_step / h_abs).to_f64());
+                h_abs = self.max_step;
+                equal_steps = 0;
+                lu = None;
+            }
+            let mut current_jac = false;
+            let (t_new, y_new, d, psi, c, safety, error_norm, scale) = loop {
+                if attempts == self.max_steps {
+                    return Err(OdeError::TooManySteps { t: t.to_f64() });
+                }
+                attempts += 1;
+                if h_abs < min_step(t, t0, t1) {
+                    return Err(OdeError::StepSizeTooSmall { t: t.to_f64() });
+                }
+                // Land exactly on t1 rather than stepping past it
+                let mut t_new = t + direction * h_abs;
+                if direction * (t_new - t1) > S::zero() {
+                    t_new = t1;
+                    change_differences(&mut differences, order, ((t_new - t).abs() / h_abs).to_f64());
+                    equal_steps = 0;
+                    lu = None;
+                }
+                let h = t_new - t;
+                h_abs = h.abs();
+
+                let mut y_predict = vec![S::zero(); n];
+                for row in differences.iter().take(order + 1) {
+                    for (p, &v) in y_predict.iter_mut().zip(row.iter()) {
+                        *p += v;
+                    }
+                }
+                let scale: Vec<S> = y_predict.iter().map(|&v| self.atol + self.rtol * v.abs()).collect();
+                let mut psi = vec![S::zero(); n];
+                for k in 1..=order {
+                    let weight = S::from(gamma[k] / alpha[order]);
+                    for (p, &v) in psi.iter_mut().zip(differences[k].iter()) {
+                        *p += weight * v;
+                    }
+                }
+                let c = h / S::from(alpha[order]);
+
+                let mut newton = None;
+                loop {
+                    if lu.is_none() {
+                        lu = (DenseMatrix::identity(n) - jac_matrix.clone() * c).lu().ok();
+                        solution.lu_decompositions += 1;
+                    }
+                    if let Some(lu) = lu.as_ref() {
+                        newton = solve_bdf_system(f, t_new, &y_predict, c, &psi, lu, &scale, newton_tol, &mut solution.evaluations);
+                    }
+                    if newton.is_some() || current_jac {
+                        break;
+                    }
+                    jac_matrix = jacobian(f, &mut jac, t_new, &y_predict, None, self.atol, &mut solution);
+                    lu = None;
+                    current_jac = true;
+                }
+                let (iterations, y_new, d) = match newton {
+                    Some(result) => result,
+                    None => {
+                        h_abs *= S::from(0.5);
+                        change_differences(&mut differences, order, 0.5);
+                        equal_steps = 0;
+                        lu = None;
+                        solution.rejected_steps += 1;
+                        continue;
+                    }
+                };
+
+                let safety = 0.9 * (2 * NEWTON_MAXITER + 1) as f64 / (2 * NEWTON_MAXITER + iterations) as f64;
+                let scale: Vec<S> = y_new.iter().map(|&v| self.atol + self.rtol * v.abs()).collect();
+                let error: Vec<S> = d.iter().map(|&v| S::from(error_const[order]) * v).collect();
+                let error_norm = scaled_norm(&error, &scale);
+                if error_norm > 1.0 {
+                    let factor = MIN_FACTOR.max(safety * error_norm.powf(-1.0 / (order as f64 + 1.0)));
+                    h_abs *= S::from(factor);
+                    change_differences(&mut differences, order, factor);
+                    equal_steps = 0;
+                    solution.rejected_steps += 1;
+                    continue;
+                }
+                break (t_new, y_new, d, psi, c, safety, error_norm, scale);
+            };
+
+            equal_steps += 1;
+            t = t_new;
+            // The Newton iteration converged on c f(t, y) = psi + d
+            let dy = psi.iter().zip(d.iter()).map(|(&p, &v)| (p + v) / c).collect();
+            solution.push(t, y_new, dy);
+            if monitor.check(&mut solution) {
+                break;
+            }
+
+            // d is the (order + 1)-th difference at the new point, which
+            // updates every lower difference in turn
+            differences[order + 2] = d.iter().zip(differences[order + 1].iter()).map(|(&a, &b)| a - b).collect();
+            differences[order + 1] = d;
+            for k in (0..=order).rev() {
+                let (lower, upper) = differences.split_at_mut(k + 1);
+                for (a, &b) in lower[k].iter_mut().zip(upper[0].iter()) {
+                    *a += b;
+                }
+            }
+
+            // Only consider a new order and step size once the current ones
+            // have been used for long enough to be trusted
+            if equal_steps < order + 1 {
+                continue;
+            }
+            let norm_of = |k: usize, row: &[S]| {
+                let error: Vec<S> = row.iter().map(|&v| S::from(error_const[k]) * v).collect();
+                scaled_norm(&error, &scale)
+            };
+            let error_m_norm = if order > 1 { norm_of(order - 1, &differences[order]) } else { f64::INFINITY };
+            let error_p_norm = if order < MAX_ORDER { norm_of(order + 1, &differences[order + 2]) } else { f64::INFINITY };
+            let factors = [
+                error_m_norm.powf(-1.0 / order as f64),
+                error_norm.powf(-1.0 / (order as f64 + 1.0)),
+                error_p_norm.powf(-1.0 / (order as f64 + 2.0)),
+            ];
+            let mut best = 0;
+            for k in 1..3 {
+                if factors[k] > factors[best] {
+                    best = k;
+                }
+            }
+            order = order + best - 1;
+            let factor = MAX_FACTOR.min(safety * factors[best]);
+            h_abs *= S::from(factor);
+            change_differences(&mut differences, order, factor);
+            equal_steps = 0;
+            lu = None;
+        }
+        Ok(solution)
+    }
+}
+
+// The matrix that rescales backward differences of the given order when the
+// step size is multiplied by factor
+fn rescaling_matrix(order: usize, factor: f64) -> Vec<Vec<f64>> {
+    let mut digest = vec![vec![1.0; order + 1]; order + 1];
+    for i in 1..=order {
+        let (above, below) = digest.split_at_mut(i);
+        below[0][0] = 0.0;
+        for (j, (value, &prev)) in below[0].iter_mut().zip(above[i - 1].iter()).enumerate().skip(1) {
+            *value = prev * (i as f64 - 1.0 - factor * j as f64) / i as f64;
+        }
+    }
+    digest
+}
+
+// Rewrites the differences as if the last steps had been factor times as long
+fn change_differences<S>(differences: &mut [Vec<S>], order: usize, factor: f64)
+where S: Real {
+    let r = rescaling_matrix(order, factor);
+    let u = rescaling_matrix(order, 1.0);
+    let old: Vec<Vec<S>> = differences[..=order].to_vec();
+    for (i, row) in differences.iter_mut().take(order + 1).enumerate() {
+        // Row i of (R U)^T
+        let weights: Vec<S> = (0..=order).map(|j| S::from((0..=order).map(|k| r[j][k] * u[k][i]).sum())).collect();
+        for (m, value) in row.iter_mut().enumerate() {
+            let mut digest = S::zero();
+            for (&w, old_row) in weights.iter().zip(old.iter()) {
+                digest += w * old_row[m];
+            }
+            *value = digest;
+        }
+    }
+}
+
+// Solves the implicit equation of a step by simplified Newton iteration,
+// starting from the predicted value. Returns the number of iterations, the
+// new state and its difference from the prediction, or None if the iteration
+// doesn't converge quickly enough.
+#[allow(clippy::too_many_arguments)]
+fn solve_bdf_system<S>(f: &mut FlatRhs<S>, t_new: S, y_predict: &[S], c: S, psi: &[S], lu: &LuDecomposition<S>, scale: &[S], tol: f64, evaluations: &mut usize) -> Option<(usize, Vec<S>, Vec<S>)>
+where S: Real {
+    let n = y_predict.len();
+    let mut y = y_predict.to_vec();
+    let mut d = vec![S::zero(); n];
+    let mut dy_norm_old: Option<f64> = None;
+    for k in 0..NEWTON_MAXITER {
+        let fy = f(t_new, &y);
+        *evaluations += 1;
+        if !is_finite(&fy) {
+            return None;
+        }
+        let rhs: Vec<S> = (0..n).map(|i| c * fy[i] - psi[i] - d[i]).collect();
+        let dy = lu.solve(&rhs);
+        let dy_norm = scaled_norm(&dy, scale);
+        let rate = dy_norm_old.map(|old| dy_norm / old);
+        if let Some(rate) = rate {
+            if rate >= 1.0 || rate.powi((NEWTON_MAXITER - k) as i32) / (1.0 - rate) * dy_norm > tol {
+                return None;
+            }
+        }
+        for i in 0..n {
+            y[i] += dy[i];
+            d[i] += dy[i];
+        }
+        if dy_norm == 0.0 || rate.is_some_and(|rate| rate / (1.0 - rate) * dy_norm < tol) {
+            return Some((k + 1, y, d));
+        }
+        dy_norm_old = Some(dy_norm);
+    }
+    None
+}
@@ -0,0 +1,592 @@
+
+pub use super::ode_solver::{DenseMatrix, OdeError, OdeState, Real, Scalar};
+pub use crate::math::lin_alg::dense_matrix::LuDecomposition;
+
+use super::ode_solver::{hermite, FlatRhs};
+use super::ode_state::{components, from_components};
+
+use std::error;
+use std::fmt;
+
+// The damped Newton iteration of solve_bvp: a step is accepted once it
+// shrinks the next Newton step enough, and otherwise it is cut back by TAU
+// up to BACKTRACKS times
+const SIGMA: f64 = 0.2;
+const TAU: f64 = 0.5;
+const BACKTRACKS: usize = 4;
+const NEWTON_MAXITER: usize = 8;
+const MAX_JACOBIANS: usize = 4;
+
+// The ways in which solving a boundary value problem can fail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BvpError {
+    // The mesh needs at least two strictly increasing points, with a guess
+    // of the same dimension at each
+    InvalidMesh,
+    // The Newton iteration met a singular Jacobian
+    SingularJacobian,
+    // Meeting the tolerance needs more than the maximum number of mesh nodes
+    TooManyNodes { nodes: usize },
+    // The tolerances weren't met after this many iterations
+    NotConverged { iterations: usize },
+    // The boundary conditions gave a different number of residuals than the
+    // dimension of the system
+    BoundaryConditionCount { expected: usize, found: usize },
+    // An integration failed while shooting
+    Ode(OdeError),
+}
+
+impl fmt::Display for BvpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BvpError::InvalidMesh => write!(f, "The mesh must have at least two increasing points, with a guess at each."),
+            BvpError::SingularJacobian => write!(f, "The Jacobian of the collocation system is singular."),
+            BvpError::TooManyNodes { nodes } => write!(
+                f,
+                "Meeting the tolerance needs more than {} mesh nodes.",
+                nodes
+            ),
+            BvpError::NotConverged { iterations } => write!(
+                f,
+                "The tolerances weren't met after {} iterations.",
+                iterations
+            ),
+            BvpError::BoundaryConditionCount { expected, found } => write!(
+                f,
+                "A system of dimension {} needs {} boundary conditions, but {} were given.",
+                expected, expected, found
+            ),
+            BvpError::Ode(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for BvpError {}
+
+impl From<OdeError> for BvpError {
+    fn from(e: OdeError) -> Self {
+        BvpError::Ode(e)
+    }
+}
+
+// The solution of a boundary value problem on the final mesh x. y[i] is the
+// solution at x[i] and derivatives[i] is f there, and residuals[i] is the
+// relative RMS residual of the interpolant over the interval from x[i].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BvpSolution<S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub x: Vec<S>,
+    pub y: Vec<Y>,
+    pub derivatives: Vec<Y>,
+    pub residuals: Vec<S>,
+    // The number of times the mesh was solved for
+    pub iterations: usize,
+    // The number of times the right hand side was evaluated
+    pub evaluations: usize,
+}
+
+impl<S, Y> BvpSolution<S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    // The solution anywhere on the mesh by cubic Hermite interpolation, the
+    // same interpolant the residuals were measured on. None outside the mesh.
+    pub fn interpolate(&self, x: S) -> Option<Y> {
+        let last = self.x.len() - 1;
+        if x < self.x[0] || x > self.x[last] {
+            return None;
+        }
+        let end = self.x.partition_point(|&node| node < x).clamp(1, last);
+        let start = end - 1;
+        let (y, _) = hermite(
+            self.x[start],
+            &self.y[start],
+            &self.derivatives[start],
+            self.x[end],
+            &self.y[end],
+            &self.derivatives[end],
+            x,
+        );
+        Some(y)
+    }
+}
+
+pub(crate) type FlatBoundary<'a, S> = dyn FnMut(&[S], &[S]) -> Vec<S> + 'a;
+
+// A two point boundary value problem solver in the manner of SciPy's
+// solve_bvp. It solves y' = f(x, y) on [a, b] with the n conditions
+// bc(y(a), y(b)) = 0, where the n components of bc are driven to zero.
+//
+// The solution is a cubic on each interval of the mesh that satisfies the ODE
+// at both ends and the middle (fourth order Lobatto IIIA collocation). The
+// collocation equations for every node are solved together by a damped
+// Newton iteration, with a Jacobian assembled from finite differences. It is
+// factorised block by block, in O(m n^3) operations for m nodes and n
+// components rather than the O((m n)^3) of a dense matrix.
+// Intervals where the residual of the ODE is too large are then split, and
+// the process repeats until the residuals are below tol everywhere.
+pub struct BvpSolver<S>
+where S: Real {
+    // The tolerance on the relative RMS residual of the ODE on each interval
+    pub tol: S,
+    // The tolerance on the boundary condition residuals
+    pub bc_tol: S,
+    pub max_nodes: usize,
+    // The number of times the mesh may be solved for (and refined)
+    pub max_iterations: usize,
+}
+
+impl<S> Default for BvpSolver<S>
+where S: Real
+{
+    fn default() -> Self {
+        BvpSolver {
+            tol: S::from(1e-3),
+            bc_tol: S::from(1e-3),
+            max_nodes: 1000,
+            max_iterations: 100,
+        }
+    }
+}
+
+impl<S> BvpSolver<S>
+where S: Real
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Solves from the initial mesh x, with the guess y[i] for the solution at
+    // each x[i]. Any guess satisfying the boundary conditions will do for
+    // linear problems; nonlinear ones may have several solutions, and which
+    // is found depends on the guess.
+    pub fn solve<Y, F, B>(&self, mut f: F, mut bc: B, x: Vec<S>, y: Vec<Y>) -> Result<BvpSolution<S, Y>, BvpError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        B: FnMut(&Y, &Y) -> Y,
+    {
+        if x.len() < 2 || y.len() != x.len() || x.windows(2).any(|w| w[1] <= w[0]) || y.iter().any(|v| v.dimension() != y[0].dimension()) {
+            return Err(BvpError::InvalidMesh);
+        }
+        let template = y[0].clone();
+        let mut flat_f = |x: S, y: &[S]| components(&f(x, &from_components(&template, y)));
+        let mut flat_bc = |ya: &[S], yb: &[S]| components(&bc(&from_components(&template, ya), &from_components(&template, yb)));
+        let flat = self.solve_flat(&mut flat_f, &mut flat_bc, x, y.iter().map(components).collect())?;
+        let unflatten = |values: &Vec<Vec<S>>| values.iter().map(|y| from_components(&template, y)).collect();
+        Ok(BvpSolution {
+            y: unflatten(&flat.y),
+            derivatives: unflatten(&flat.derivatives),
+            x: flat.x,
+            residuals: flat.residuals,
+            iterations: flat.iterations,
+            evaluations: flat.evaluations,
+        })
+    }
+
+    fn solve_flat(&self, f: &mut FlatRhs<S>, bc: &mut FlatBoundary<S>, mut x: Vec<S>, mut y: Vec<Vec<S>>) -> Result<BvpSolution<S, Vec<S>>, BvpError> {
+        let mut evaluations = 0;
+        let mut iterations = 0;
+        loop {
+            self.solve_newton(f, bc, &x, &mut y, &mut evaluations)?;
+            iterations += 1;
+            let collocation = collocate(f, bc, &x, &y, &mut evaluations)?;
+            let residuals = rms_residuals(f, &x, &y, &collocation, &mut evaluations);
+            let n = y[0].len();
+            let max_bc = collocation.residuals[(x.len() - 1) * n..].iter().fold(S::zero(), |acc, r| acc.max(r.abs()));
+
+            // Split the intervals where the residual is too large, into thirds
+            // where it is far too large
+            let tol = self.tol;
+            let hundred = S::from(100.0);
+            let added: usize = residuals.iter().map(|&r| if r >= hundred * tol { 2 } else if r > tol { 1 } else { 0 }).sum();
+            if x.len() + added > self.max_nodes {
+                return Err(BvpError::TooManyNodes { nodes: self.max_nodes });
+            }
+            if added == 0 && max_bc <= self.bc_tol {
+                return Ok(BvpSolution { x, y, derivatives: collocation.f, residuals, iterations, evaluations });
+            }
+            if iterations >= self.max_iterations {
+                return Err(BvpError::NotConverged { iterations });
+            }
+            if added > 0 {
+                let mut new_x = Vec::with_capacity(x.len() + added);
+                let mut new_y = Vec::with_capacity(x.len() + added);
+                for (i, &r) in residuals.iter().enumerate() {
+                    let h = x[i + 1] - x[i];
+                    let fractions: &[f64] = if r >= hundred * tol { &[1.0 / 3.0, 2.0 / 3.0] } else if r > tol { &[0.5] } else { &[] };
+                    new_x.push(x[i]);
+                    new_y.push(y[i].clone());
+                    for &fraction in fractions {
+                        let point = x[i] + S::from(fraction) * h;
+                        let (value, _) = hermite(x[i], &y[i], &collocation.f[i], x[i + 1], &y[i + 1], &collocation.f[i + 1], point);
+                        new_x.push(point);
+                        new_y.push(value);
+                    }
+                }
+                new_x.push(*x.last().unwrap());
+                new_y.push(y.last().unwrap().clone());
+                x = new_x;
+                y = new_y;
+            }
+        }
+    }
+
+    // Solves the collocation equations on the mesh x by damped Newton
+    // iteration from y, like solve_bvp's solve_newton
+    fn solve_newton(&self, f: &mut FlatRhs<S>, bc: &mut FlatBoundary<S>, x: &[S], y: &mut Vec<Vec<S>>, evaluations: &mut usize) -> Result<(), BvpError> {
+        let n = y[0].len();
+        let m = x.len();
+        // The collocation residuals are a step's worth of the ODE residual
+        let tol_r: Vec<S> = x.windows(2).map(|w| S::from(2.0 / 3.0 * 5e-2) * (w[1] - w[0]) * self.tol).collect();
+        let mut collocation = collocate(f, bc, x, y, evaluations)?;
+        let mut lu: Option<BlockLu<S>> = None;
+        let mut jacobians = 0;
+        let mut next: Option<(Vec<S>, f64)> = None;
+        for _ in 0..NEWTON_MAXITER {
+            let (step, cost) = match next.take() {
+                Some(next) => next,
+                None => {
+                    let jac = global_jacobian(f, bc, x, y, &collocation, evaluations);
+                    jacobians += 1;
+                    let factors = BlockLu::new(&jac).ok_or(BvpError::SingularJacobian)?;
+                    let step = factors.solve(&collocation.residuals);
+                    lu = Some(factors);
+                    let cost = squared_norm(&step);
+                    (step, cost)
+                }
+            };
+            let factors = lu.as_ref().unwrap();
+            let mut alpha = 1.0;
+            let mut trial = 0;
+            let (y_new, collocation_new, step_new, cost_new) = loop {
+                let y_new: Vec<Vec<S>> = (0..m).map(|i| (0..n).map(|k| y[i][k] - S::from(alpha) * step[i * n + k]).collect()).collect();
+                let collocation_new = collocate(f, bc, x, &y_new, evaluations)?;
+                let step_new = factors.solve(&collocation_new.residuals);
+                let cost_new = squared_norm(&step_new);
+                if cost_new < (1.0 - 2.0 * alpha * SIGMA) * cost || trial == BACKTRACKS {
+                    break (y_new, collocation_new, step_new, cost_new);
+                }
+                alpha *= TAU;
+                trial += 1;
+            };
+            *y = y_new;
+            collocation = collocation_new;
+            if jacobians == MAX_JACOBIANS {
+                break;
+            }
+            let converged = (0..m - 1).all(|i| {
+                (0..n).all(|k| collocation.residuals[i * n + k].abs() < tol_r[i] * (S::from(1.0) + collocation.f_middle[i][k].abs()))
+            }) && collocation.residuals[(m - 1) * n..].iter().all(|r| r.abs() < self.bc_tol);
+            if converged {
+                break;
+            }
+            // A full step means the Jacobian is still good, so the next step
+            // is already known
+            if alpha == 1.0 {
+                next = Some((step_new, cost_new));
+            }
+        }
+        Ok(())
+    }
+}
+
+// The residuals of the collocation equations on a mesh: for each interval,
+// y[i + 1] - y[i] - h (f[i] + 4 f_middle[i] + f[i + 1]) / 6, followed by
+// the boundary conditions
+struct Collocation<S>
+where S: Real {
+    residuals: Vec<S>,
+    f: Vec<Vec<S>>,
+    y_middle: Vec<Vec<S>>,
+    f_middle: Vec<Vec<S>>,
+}
+
+fn collocate<S>(f: &mut FlatRhs<S>, bc: &mut FlatBoundary<S>, x: &[S], y: &[Vec<S>], evaluations: &mut usize) -> Result<Collocation<S>, BvpError>
+where S: Real {
+    let m = x.len();
+    let n = y[0].len();
+    let (two, six, eight) = (S::from(2.0), S::from(6.0), S::from(8.0));
+    let f_nodes: Vec<Vec<S>> = x.iter().zip(y.iter()).map(|(&x, y)| f(x, y)).collect();
+    let mut residuals = Vec::with_capacity(m * n);
+    let mut y_middle = Vec::with_capacity(m - 1);
+    let mut f_middle = Vec::with_capacity(m - 1);
+    for i in 0..m - 1 {
+        let h = x[i + 1] - x[i];
+        // The cubic through the ends of the interval, at its middle
+        let y_mid: Vec<S> = (0..n).map(|k| (y[i][k] + y[i + 1][k]) / two - h / eight * (f_nodes[i + 1][k] - f_nodes[i][k])).collect();
+        let f_mid = f(x[i] + h / two, &y_mid);
+        for k in 0..n {
+            residuals.push(y[i + 1][k] - y[i][k] - h / six * (f_nodes[i][k] + S::from(4.0) * f_mid[k] + f_nodes[i + 1][k]));
+        }
+        y_middle.push(y_mid);
+        f_middle.push(f_mid);
+    }
+    let bc_value = bc(&y[0], &y[m - 1]);
+    if bc_value.len() != n {
+        return Err(BvpError::BoundaryConditionCount { expected: n, found: bc_value.len() });
+    }
+    residuals.extend(bc_value);
+    *evaluations += 2 * m - 1;
+    Ok(Collocation { residuals, f: f_nodes, y_middle, f_middle })
+}
+
+// The derivative of the collocation residuals by every component of y, in
+// blocks. Each interval's residuals depend only on the nodes at its ends, so
+// row block i is zero apart from left[i] in column block i and right[i] in
+// column block i + 1. The boundary conditions depend only on the first and
+// last nodes, through bc_a and bc_b.
+struct CollocationJacobian<S>
+where S: Real {
+    left: Vec<DenseMatrix<S>>,
+    right: Vec<DenseMatrix<S>>,
+    bc_a: DenseMatrix<S>,
+    bc_b: DenseMatrix<S>,
+}
+
+fn global_jacobian<S>(f: &mut FlatRhs<S>, bc: &mut FlatBoundary<S>, x: &[S], y: &[Vec<S>], collocation: &Collocation<S>, evaluations: &mut usize) -> CollocationJacobian<S>
+where S: Real {
+    let m = x.len();
+    let n = y[0].len();
+    let (two, three, six, twelve) = (S::from(2.0), S::from(3.0), S::from(6.0), S::from(12.0));
+    let df_dy: Vec<DenseMatrix<S>> = (0..m)
+        .map(|i| difference_jacobian(&mut |y: &[S]| f(x[i], y), &y[i], &collocation.f[i], evaluations))
+        .collect();
+    let mut left = Vec::with_capacity(m - 1);
+    let mut right = Vec::with_capacity(m - 1);
+    for i in 0..m - 1 {
+        let h = x[i + 1] - x[i];
+        let x_mid = x[i] + h / two;
+        let df_dy_middle = difference_jacobian(&mut |y: &[S]| f(x_mid, y), &collocation.y_middle[i], &collocation.f_middle[i], evaluations);
+        // The middle value depends on the ends through f, so its Jacobian
+        // enters both blocks once directly and once through df/dy there
+        let through_left = df_dy_middle.clone() * df_dy[i].clone();
+        let through_right = df_dy_middle.clone() * df_dy[i + 1].clone();
+        let mut left_block = DenseMatrix::zeros(n, n);
+        let mut right_block = DenseMatrix::zeros(n, n);
+        for p in 0..n {
+            for q in 0..n {
+                let identity = if p == q { S::from(1.0) } else { S::zero() };
+                left_block[(p, q)] = S::zero() - identity - h / six * df_dy[i][(p, q)] - h / three * df_dy_middle[(p, q)] - h * h / twelve * through_left[(p, q)];
+                right_block[(p, q)] = identity - h / six * df_dy[i + 1][(p, q)] - h / three * df_dy_middle[(p, q)] + h * h / twelve * through_right[(p, q)];
+            }
+        }
+        left.push(left_block);
+        right.push(right_block);
+    }
+    let (ya, yb) = (&y[0], &y[m - 1]);
+    let bc_value = &collocation.residuals[(m - 1) * n..];
+    let bc_a = difference_jacobian(&mut |ya: &[S]| bc(ya, yb), ya, bc_value, &mut 0);
+    let bc_b = difference_jacobian(&mut |yb: &[S]| bc(ya, yb), yb, bc_value, &mut 0);
+    CollocationJacobian { left, right, bc_a, bc_b }
+}
+
+// Gaussian elimination with partial pivoting on the collocation Jacobian,
+// kept to its blocks. The boundary condition rows are carried down the mesh:
+// at each interval, its rows and the carried rows are pivoted on the columns
+// of its left node, which leaves n rows in its right node and the last node
+// to carry on to the next. No other rows have entries in those columns, so
+// the pivots are the ones dense partial pivoting would choose from.
+struct BlockLu<S>
+where S: Real {
+    n: usize,
+    // For each interval, the 2n by 3n matrix of its rows over the carried
+    // rows, in the columns of its left node, its right node and the last
+    // node, eliminated with the multipliers stored below the diagonal, and
+    // the row swapped into each pivot position
+    steps: Vec<(DenseMatrix<S>, Vec<usize>)>,
+    // The rows carried past the last interval, in the last node alone
+    last: LuDecomposition<S>,
+}
+
+impl<S> BlockLu<S>
+where S: Real
+{
+    // None if the Jacobian is singular
+    fn new(jac: &CollocationJacobian<S>) -> Option<Self> {
+        let n = jac.bc_a.size().0;
+        let intervals = jac.left.len();
+        // The carried rows, in the columns of the next left node and then of
+        // the last node
+        let mut carried = DenseMatrix::zeros(n, 2 * n);
+        for p in 0..n {
+            for q in 0..n {
+                carried[(p, q)] = jac.bc_a[(p, q)];
+                carried[(p, n + q)] = jac.bc_b[(p, q)];
+            }
+        }
+        let mut steps = Vec::with_capacity(intervals);
+        for i in 0..intervals {
+            let mut block = DenseMatrix::zeros(2 * n, 3 * n);
+            for p in 0..n {
+                for q in 0..n {
+                    block[(p, q)] = jac.left[i][(p, q)];
+                    block[(p, n + q)] = jac.right[i][(p, q)];
+                    block[(n + p, q)] = carried[(p, q)];
+                    block[(n + p, 2 * n + q)] = carried[(p, n + q)];
+                }
+            }
+            // The right node of the last interval is the last node
+            if i == intervals - 1 {
+                for p in 0..2 * n {
+                    for q in 0..n {
+                        let last = block[(p, 2 * n + q)];
+                        block[(p, n + q)] += last;
+                        block[(p, 2 * n + q)] = S::zero();
+                    }
+                }
+            }
+            let mut pivots = Vec::with_capacity(n);
+            for k in 0..n {
+                let mut pivot = k;
+                for r in k + 1..2 * n {
+                    if block[(r, k)].magnitude() > block[(pivot, k)].magnitude() {
+                        pivot = r;
+                    }
+                }
+                if block[(pivot, k)] == S::zero() {
+                    return None;
+                }
+                if pivot != k {
+                    for c in 0..3 * n {
+                        let value = block[(k, c)];
+                        block[(k, c)] = block[(pivot, c)];
+                        block[(pivot, c)] = value;
+                    }
+                }
+                pivots.push(pivot);
+                let inv = block[(k, k)].inv();
+                for r in k + 1..2 * n {
+                    let factor = block[(r, k)] * inv;
+                    block[(r, k)] = factor;
+                    if factor != S::zero() {
+                        for c in k + 1..3 * n {
+                            let u = block[(k, c)];
+                            block[(r, c)] -= factor * u;
+                        }
+                    }
+                }
+            }
+            for p in 0..n {
+                for q in 0..2 * n {
+                    carried[(p, q)] = block[(n + p, n + q)];
+                }
+            }
+            steps.push((block, pivots));
+        }
+        // The last elimination was in the last node's columns already
+        let mut last = DenseMatrix::zeros(n, n);
+        for p in 0..n {
+            for q in 0..n {
+                last[(p, q)] = carried[(p, q)];
+            }
+        }
+        let last = last.lu().ok()?;
+        Some(BlockLu { n, steps, last })
+    }
+
+    // Solves for the Newton step from the collocation residuals
+    fn solve(&self, residuals: &[S]) -> Vec<S> {
+        let n = self.n;
+        let m = self.steps.len() + 1;
+        // Forward elimination leaves n equations in each interval's nodes and
+        // the last node, and n in the last node alone
+        let mut carried = residuals[(m - 1) * n..].to_vec();
+        let mut reduced = Vec::with_capacity(m - 1);
+        for (i, (block, pivots)) in self.steps.iter().enumerate() {
+            let mut z: Vec<S> = residuals[i * n..(i + 1) * n].iter().chain(carried.iter()).copied().collect();
+            for (k, &pivot) in pivots.iter().enumerate() {
+                z.swap(k, pivot);
+                for r in k + 1..2 * n {
+                    let l = block[(r, k)];
+                    let pivot_value = z[k];
+                    z[r] -= l * pivot_value;
+                }
+            }
+            carried = z.split_off(n);
+            reduced.push(z);
+        }
+        let last = self.last.solve(&carried);
+        let mut digest = vec![S::zero(); m * n];
+        digest[(m - 1) * n..].copy_from_slice(&last);
+        // Back substitution, node by node from the end
+        for i in (0..m - 1).rev() {
+            let block = &self.steps[i].0;
+            for p in (0..n).rev() {
+                let mut value = reduced[i][p];
+                for q in p + 1..n {
+                    value -= block[(p, q)] * digest[i * n + q];
+                }
+                for q in 0..n {
+                    value -= block[(p, n + q)] * digest[(i + 1) * n + q] + block[(p, 2 * n + q)] * last[q];
+                }
+                digest[i * n + p] = value / block[(p, p)];
+            }
+        }
+        digest
+    }
+}
+
+// The relative RMS residual y' - f(x, y) of the collocation solution over
+// each interval, by five point Lobatto quadrature. The residual vanishes at
+// the ends, and at the middle it follows from the collocation residual.
+fn rms_residuals<S>(f: &mut FlatRhs<S>, x: &[S], y: &[Vec<S>], collocation: &Collocation<S>, evaluations: &mut usize) -> Vec<S>
+where S: Real {
+    let n = y[0].len();
+    let one = S::from(1.0);
+    let offset = S::from(0.5 * (3.0f64 / 7.0).sqrt());
+    let mut digest = Vec::with_capacity(x.len() - 1);
+    for i in 0..x.len() - 1 {
+        let h = x[i + 1] - x[i];
+        let x_mid = x[i] + h / S::from(2.0);
+        let mut middle = S::zero();
+        for k in 0..n {
+            let r = S::from(1.5) * collocation.residuals[i * n + k] / h / (one + collocation.f_middle[i][k].abs());
+            middle += r * r;
+        }
+        let mut sides = S::zero();
+        for point in [x_mid - offset * h, x_mid + offset * h] {
+            let (value, derivative) = hermite(x[i], &y[i], &collocation.f[i], x[i + 1], &y[i + 1], &collocation.f[i + 1], point);
+            let f_point = f(point, &value);
+            *evaluations += 1;
+            for k in 0..n {
+                let r = (derivative[k] - f_point[k]) / (one + f_point[k].abs());
+                sides += r * r;
+            }
+        }
+        digest.push((S::from(0.5) * (S::from(32.0 / 45.0) * middle + S::from(49.0 / 90.0) * sides)).sqrt());
+    }
+    digest
+}
+
+// The Jacobian of g at y by forward differences, where gy = g(y)
+pub(crate) fn difference_jacobian<S, G>(g: &mut G, y: &[S], gy: &[S], evaluations: &mut usize) -> DenseMatrix<S>
+where
+    S: Real,
+    G: FnMut(&[S]) -> Vec<S>,
+{
+    let root_eps = S::epsilon().sqrt();
+    let mut digest = DenseMatrix::zeros(gy.len(), y.len());
+    let mut shifted = y.to_vec();
+    for j in 0..y.len() {
+        // Round the step so that it is exactly representable as a difference
+        let step = (y[j] + root_eps * (S::from(1.0) + y[j].abs())) - y[j];
+        shifted[j] = y[j] + step;
+        let g_shifted = g(&shifted);
+        *evaluations += 1;
+        for i in 0..gy.len() {
+            digest[(i, j)] = (g_shifted[i] - gy[i]) / step;
+        }
+        shifted[j] = y[j];
+    }
+    digest
+}
+
+fn squared_norm<S>(x: &[S]) -> f64
+where S: Real {
+    x.iter().map(|v| v.to_f64() * v.to_f64()).sum()
+}
@@ -0,0 +1,412 @@
+
+pub use super::ode_solver::{OdeError, OdeSolution, OdeState, Real, Scalar};
+pub use super::runge_kutta::{ButcherTableau, RungeKuttaMethod};
+
+use super::ode_solver::{error_norm, hermite, initial_step, min_step};
+use super::runge_kutta::{combine, ErrorEstimate};
+
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+// How many times the stages of a step may be recomputed when a lagged time
+// falls inside it
+const OVERLAP_ITERATIONS: usize = 5;
+// The change in a recomputed step, relative to the tolerances, below which
+// it's taken to have settled
+const SETTLED: f64 = 1e-3;
+
+// A solver for delay differential equations y'(t) = f(t, y(t), lagged),
+// where lagged[k] = y(t - tau_k), in the manner of MATLAB's dde23 and
+// ddesd. Before t0 the solution is given by a history function, and after
+// it the lagged values come from the dense output of the steps already
+// taken, so the returned solution can be interpolated like any other.
+//
+// The steps are those of an adaptive explicit Runge-Kutta method. The
+// solution's derivative jumps at t0, and with constant delays the jump
+// propagates to t0 + tau, t0 + 2 tau and so on, smoothing out a little each
+// time. The solver steps exactly onto these points for as many levels as the
+// method's order, and keeps its steps shorter than the smallest delay so
+// that every lagged value comes from a finished step.
+//
+// With state dependent delays neither is possible. A lagged time that falls
+// inside the current step is found by iterating on the step's own
+// interpolant, which converges when the delay isn't much smaller than the
+// step. As the lagged values come from cubic Hermite interpolation, each
+// step must also keep the interpolant's residual within the tolerances, which
+// makes the solver effectively fourth order whatever the method.
+pub struct DdeSolver<S>
+where S: Real {
+    pub method: RungeKuttaMethod,
+    pub rtol: S,
+    pub atol: S,
+    // The size of the first step; estimated from f if None
+    pub first_step: Option<S>,
+    pub max_step: S,
+    // The number of steps (accepted or rejected) after which to give up
+    pub max_steps: usize,
+}
+
+impl<S> Default for DdeSolver<S>
+where S: Real
+{
+    fn default() -> Self {
+        Self::new(RungeKuttaMethod::Rk45)
+    }
+}
+
+impl<S> DdeSolver<S>
+where S: Real
+{
+    pub fn new(method: RungeKuttaMethod) -> Self {
+        DdeSolver {
+            method,
+            rtol: S::from(1e-3),
+            atol: S::from(1e-6),
+            first_step: None,
+            max_step: S::from(f64::INFINITY),
+            max_steps: 100_000,
+        }
+    }
+
+    // Solves from t0 forwards to t1 with constant delays, where history(t)
+    // is the solution for t <= t0
+    pub fn solve<Y, F, H>(&self, f: F, delays: &[S], history: H, t0: S, t1: S) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y, &[Y]) -> Y,
+        H: FnMut(S) -> Y,
+    {
+        if let Some(&delay) = delays.iter().find(|&&d| d <= S::zero()) {
+            return Err(OdeError::InvalidDelay { delay: delay.to_f64() });
+        }
+        let delays = delays.to_vec();
+        let shortest = delays.iter().fold(S::from(f64::INFINITY), |acc, &d| acc.min(d));
+        let order = self.method.tableau().order;
+        let breakpoints = breakpoints(&delays, t0, t1, order);
+        self.solve_delayed(f, |_: S, _: &Y| delays.clone(), history, t0, t1, shortest, breakpoints)
+    }
+
+    // Solves from t0 forwards to t1, where delays(t, y) gives the delays at
+    // each point and history(t) is the solution for t <= t0
+    pub fn solve_state_dependent<Y, F, D, H>(&self, f: F, delays: D, history: H, t0: S, t1: S) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y, &[Y]) -> Y,
+        D: FnMut(S, &Y) -> Vec<S>,
+        H: FnMut(S) -> Y,
+    {
+        self.solve_delayed(f, delays, history, t0, t1, S::from(f64::INFINITY), Vec::new())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn solve_delayed<Y, F, D, H>(&self, mut f: F, mut delays: D, mut history: H, t0: S, t1: S, max_step: S, breakpoints: Vec<S>) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y, &[Y]) -> Y,
+        D: FnMut(S, &Y) -> Vec<S>,
+        H: FnMut(S) -> Y,
+    {
+        if t1 < t0 || t1.partial_cmp(&t0).is_none() {
+            return Err(OdeError::Backwards { t0: t0.to_f64(), t1: t1.to_f64() });
+        }
+        let tableau = self.method.tableau();
+        let estimate = ErrorEstimate::new(tableau)?;
+        let exponent = -1.0 / (tableau.error_order as f64 + 1.0);
+
+        let mut t = t0;
+        let mut y = history(t0);
+        let mut solution = OdeSolution::new(t, y.clone(), y.clone());
+        let lagged = lagged_values(&mut delays, &mut history, &solution, t, &y)?.0;
+        let mut dy = f(t, &y, &lagged);
+        solution.derivatives[0] = dy.clone();
+        solution.evaluations += 1;
+        if t0 == t1 {
+            return Ok(solution);
+        }
+
+        let max_step = self.max_step.min(max_step);
+        let mut h = match self.first_step {
+            Some(h) => h.abs(),
+            None => {
+                let mut failure = None;
+                let mut evaluations = 0;
+                let mut undelayed = |t: S, y: &Y| match lagged_values(&mut delays, &mut history, &solution, t, y) {
+                    Ok((lagged, _)) => f(t, y, &lagged),
+                    Err(e) => {
+                        failure = Some(e);
+                        y.clone()
+                    }
+                };
+                let h = initial_step(&mut undelayed, t, &y, &dy, S::from(1.0), tableau.error_order, self.rtol, self.atol, &mut evaluations);
+                if let Some(e) = failure {
+                    return Err(e);
+                }
+                solution.evaluations += evaluations;
+                h
+            }
+        };
+        h = h.min(max_step).min(t1 - t0);
+        let mut breakpoints = breakpoints.into_iter().peekable();
+        let mut attempts = 0;
+        while t != t1 {
+            let mut rejected = false;
+            loop {
+                if attempts == self.max_steps {
+                    return Err(OdeError::TooManySteps { t: t.to_f64() });
+                }
+                attempts += 1;
+                if h < min_step(t, t0, t1) {
+                    return Err(OdeError::StepSizeTooSmall { t: t.to_f64() });
+                }
+                // Land exactly on the next discontinuity or on t1
+                let stop = breakpoints.peek().map_or(t1, |&b| b.min(t1));
+                let t_new = if t + h >= stop { stop } else { t + h };
+                let step = t_new - t;
+
+                let (mut stages, mut y_new, mut overlaps) = attempt(&mut f, &mut delays, &mut history, &mut solution, tableau, t, &y, &dy, step)?;
+                let mut dy_new = end_derivative(&mut f, &mut delays, &mut history, &mut solution, tableau, &stages, t_new, &y_new)?;
+                // A lagged time inside the step was found by extrapolating
+                // the last one, so redo the stages with this step's own
+                // interpolant until they settle
+                let mut iterations = 0;
+                while overlaps && iterations < OVERLAP_ITERATIONS {
+                    provisional(&mut solution, t_new, &y_new, &dy_new);
+                    let retry = attempt(&mut f, &mut delays, &mut history, &mut solution, tableau, t, &y, &dy, step);
+                    retract(&mut solution);
+                    let previous = y_new;
+                    (stages, y_new, overlaps) = retry?;
+                    dy_new = end_derivative(&mut f, &mut delays, &mut history, &mut solution, tableau, &stages, t_new, &y_new)?;
+                    let change = difference(&y_new, &previous);
+                    overlaps = overlaps && error_norm(&change, &y, &y_new, self.rtol, self.atol).to_f64() > SETTLED;
+                    iterations += 1;
+                }
+                // The lagged values come from the interpolant, not the Runge
+                // Kutta stages, so its accuracy is controlled too, through
+                // its residuals a quarter of the way from each end (where,
+                // unlike in the middle, the error in its slope shows)
+                provisional(&mut solution, t_new, &y_new, &dy_new);
+                let residuals = [0.25, 0.75].map(|theta| residual(&mut f, &mut delays, &mut history, &mut solution, S::from(theta)));
+                retract(&mut solution);
+                let mut norm = estimate.norm(&y, &y_new, step, &stages, self.rtol, self.atol);
+                for residual in residuals {
+                    norm = norm.max(error_norm(&residual?, &y, &y_new, self.rtol, self.atol));
+                }
+                let norm = norm.to_f64();
+                if norm <= 1.0 {
+                    let mut factor = if norm == 0.0 { MAX_FACTOR } else { (SAFETY * norm.powf(exponent)).min(MAX_FACTOR) };
+                    // Don't grow a step that has only just been shrunk
+                    if rejected {
+                        factor = factor.min(1.0);
+                    }
+                    h = (step * S::from(factor)).min(max_step);
+                    t = t_new;
+                    y = y_new;
+                    dy = dy_new;
+                    solution.push(t, y.clone(), dy.clone());
+                    while breakpoints.peek().is_some_and(|&b| b <= t) {
+                        breakpoints.next();
+                    }
+                    break;
+                }
+                h = step * S::from((SAFETY * norm.powf(exponent)).max(MIN_FACTOR));
+                solution.rejected_steps += 1;
+                rejected = true;
+            }
+        }
+        Ok(solution)
+    }
+}
+
+// Takes the stages of one step from (t, y), returning them, the new state
+// and whether any lagged time was past the end of the solution so far
+#[allow(clippy::too_many_arguments)]
+fn attempt<S, Y, F, D, H>(f: &mut F, delays: &mut D, history: &mut H, solution: &mut OdeSolution<S, Y>, tableau: &ButcherTableau, t: S, y: &Y, dy: &Y, step: S) -> Result<(Vec<Y>, Y, bool), OdeError>
+where
+    S: Real,
+    Y: OdeState<S>,
+    F: FnMut(S, &Y, &[Y]) -> Y,
+    D: FnMut(S, &Y) -> Vec<S>,
+    H: FnMut(S) -> Y,
+{
+    let mut stages = Vec::with_capacity(tableau.c.len());
+    stages.push(dy.clone());
+    let mut overlaps = false;
+    for (&c, a) in tableau.c.iter().zip(tableau.a.iter()).skip(1) {
+        let t_stage = t + S::from(c) * step;
+        let y_stage = combine(y, step, a, &stages);
+        let (lagged, overlap) = lagged_values(delays, history, solution, t_stage, &y_stage)?;
+        overlaps |= overlap;
+        stages.push(f(t_stage, &y_stage, &lagged));
+        solution.evaluations += 1;
+    }
+    let y_new = combine(y, step, tableau.b, &stages);
+    Ok((stages, y_new, overlaps))
+}
+
+// The derivative at the end of a step, which is the last stage of an FSAL
+// method
+#[allow(clippy::too_many_arguments)]
+fn end_derivative<S, Y, F, D, H>(f: &mut F, delays: &mut D, history: &mut H, solution: &mut OdeSolution<S, Y>, tableau: &ButcherTableau, stages: &[Y], t: S, y: &Y) -> Result<Y, OdeError>
+where
+    S: Real,
+    Y: OdeState<S>,
+    F: FnMut(S, &Y, &[Y]) -> Y,
+    D: FnMut(S, &Y) -> Vec<S>,
+    H: FnMut(S) -> Y,
+{
+    if tableau.fsal {
+        return Ok(stages.last().unwrap().clone());
+    }
+    let (lagged, _) = lagged_values(delays, history, solution, t, y)?;
+    solution.evaluations += 1;
+    Ok(f(t, y, &lagged))
+}
+
+// h (p'(t) - f(t, p(t), lagged)) at the fraction theta of the last step,
+// where p is the solution's interpolant
+fn residual<S, Y, F, D, H>(f: &mut F, delays: &mut D, history: &mut H, solution: &mut OdeSolution<S, Y>, theta: S) -> Result<Y, OdeError>
+where
+    S: Real,
+    Y: OdeState<S>,
+    F: FnMut(S, &Y, &[Y]) -> Y,
+    D: FnMut(S, &Y) -> Vec<S>,
+    H: FnMut(S) -> Y,
+{
+    let n = solution.times.len();
+    let (t0, t1) = (solution.times[n - 2], solution.times[n - 1]);
+    let t = t0 + theta * (t1 - t0);
+    let (p, dp) = hermite(t0, &solution.states[n - 2], &solution.derivatives[n - 2], t1, &solution.states[n - 1], &solution.derivatives[n - 1], t);
+    let (lagged, _) = lagged_values(delays, history, solution, t, &p)?;
+    solution.evaluations += 1;
+    let mut residual = difference(&dp, &f(t, &p, &lagged));
+    for i in 0..residual.dimension() {
+        residual.set_component(i, (t1 - t0) * residual.component(i));
+    }
+    Ok(residual)
+}
+
+// Appends a step that may yet be rejected, so that lagged times inside it
+// are interpolated
+fn provisional<S, Y>(solution: &mut OdeSolution<S, Y>, t: S, y: &Y, dy: &Y)
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    solution.times.push(t);
+    solution.states.push(y.clone());
+    solution.derivatives.push(dy.clone());
+}
+
+fn retract<S, Y>(solution: &mut OdeSolution<S, Y>)
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    solution.times.pop();
+    solution.states.pop();
+    solution.derivatives.pop();
+}
+
+fn difference<S, Y>(a: &Y, b: &Y) -> Y
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    let mut digest = a.clone();
+    for i in 0..a.dimension() {
+        digest.set_component(i, a.component(i) - b.component(i));
+    }
+    digest
+}
+
+// The solution at the delayed times for the point (t, y), and whether any
+// of them had to be extrapolated
+fn lagged_values<S, Y, D, H>(delays: &mut D, history: &mut H, solution: &OdeSolution<S, Y>, t: S, y: &Y) -> Result<(Vec<Y>, bool), OdeError>
+where
+    S: Real,
+    Y: OdeState<S>,
+    D: FnMut(S, &Y) -> Vec<S>,
+    H: FnMut(S) -> Y,
+{
+    let end = *solution.times.last().unwrap();
+    let mut overlaps = false;
+    let lagged = delays(t, y)
+        .into_iter()
+        .map(|delay| {
+            if delay < S::zero() {
+                return Err(OdeError::InvalidDelay { delay: delay.to_f64() });
+            }
+            overlaps |= t - delay > end;
+            Ok(past_value(history, solution, t - delay))
+        })
+        .collect::<Result<Vec<Y>, OdeError>>()?;
+    Ok((lagged, overlaps))
+}
+
+// The solution at a time before the current step ends: from the history
+// before t0, from the dense output of a finished step, or by extrapolating
+// the last step
+fn past_value<S, Y, H>(history: &mut H, solution: &OdeSolution<S, Y>, s: S) -> Y
+where
+    S: Real,
+    Y: OdeState<S>,
+    H: FnMut(S) -> Y,
+{
+    let n = solution.times.len();
+    if s <= solution.times[0] {
+        return history(s);
+    }
+    if let Some(y) = solution.interpolate(s) {
+        return y;
+    }
+    if n == 1 {
+        let (y0, dy0) = (&solution.states[0], &solution.derivatives[0]);
+        let mut digest = y0.clone();
+        for i in 0..y0.dimension() {
+            digest.set_component(i, y0.component(i) + (s - solution.times[0]) * dy0.component(i));
+        }
+        return digest;
+    }
+    let (start, end) = (n - 2, n - 1);
+    hermite(
+        solution.times[start],
+        &solution.states[start],
+        &solution.derivatives[start],
+        solution.times[end],
+        &solution.states[end],
+        &solution.derivatives[end],
+        s,
+    )
+    .0
+}
+
+// The points after t0 where the solution may not be smooth: sums of up to
+// levels of the delays. Sums that differ only by rounding, like 0.1 + 0.2
+// and 0.3, are the same point, and are merged so that the solver isn't made
+// to step between them and their number doesn't grow with every level. So
+// are points that round to just short of t1.
+fn breakpoints<S>(delays: &[S], t0: S, t1: S, levels: usize) -> Vec<S>
+where S: Real {
+    let tolerance = S::from(100.0) * S::epsilon() * t0.abs().max(t1.abs());
+    let mut digest = Vec::new();
+    let mut level = vec![t0];
+    for _ in 0..levels {
+        let next: Vec<S> = level.iter().flat_map(|&b| delays.iter().map(move |&d| b + d)).filter(|&b| t1 - b > tolerance).collect();
+        level = merged(next, tolerance);
+        digest.extend(level.iter().cloned());
+    }
+    merged(digest, tolerance)
+}
+
+// The points in order, less any within the tolerance of the one before
+fn merged<S>(mut points: Vec<S>, tolerance: S) -> Vec<S>
+where S: Real {
+    points.sort_by(|a, b| a.to_f64().total_cmp(&b.to_f64()));
+    let mut digest: Vec<S> = Vec::with_capacity(points.len());
+    for point in points {
+        if digest.last().map_or(true, |&last| point - last > tolerance) {
+            digest.push(point);
+        }
+    }
+    digest
+}
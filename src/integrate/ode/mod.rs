@@ -0,0 +1,11 @@
+pub mod ode_state;
+pub mod ode_solver;
+pub mod ode_event;
+pub mod runge_kutta;
+pub mod bdf;
+pub mod radau;
+pub mod rosenbrock;
+pub mod symplectic;
+pub mod bvp;
+pub mod shooting;
+pub mod dde;
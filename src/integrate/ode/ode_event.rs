@@ -0,0 +1,151 @@
+
+pub use super::ode_state::{OdeState, Real, Scalar};
+
+use super::ode_solver::{hermite, OdeSolution};
+
+// Which zero crossings of an event function count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventDirection {
+    // From negative to positive
+    Rising,
+    // From positive to negative
+    Falling,
+    Either,
+}
+
+pub type EventFunction<'a, S, Y> = Box<dyn FnMut(S, &Y) -> S + 'a>;
+
+// A condition to watch for while solving, like the events of SciPy's
+// solve_ivp. The event occurs whenever function(t, y) crosses zero in the
+// given direction. A terminal event stops the solve at the crossing.
+pub struct Event<'a, S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub function: EventFunction<'a, S, Y>,
+    pub direction: EventDirection,
+    pub terminal: bool,
+}
+
+impl<'a, S, Y> Event<'a, S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub fn new<G>(function: G, direction: EventDirection, terminal: bool) -> Self
+    where G: FnMut(S, &Y) -> S + 'a
+    {
+        Event { function: Box::new(function), direction, terminal }
+    }
+}
+
+// A crossing found while solving
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRecord<S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    // The position of the event in the list given to the solver
+    pub index: usize,
+    pub t: S,
+    pub y: Y,
+}
+
+// Watches the event functions from step to step. Crossings are located on
+// the dense output of each step.
+pub(crate) struct EventMonitor<'e, 'a, S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    events: &'e mut [Event<'a, S, Y>],
+    // The value of each event function at the last accepted point
+    values: Vec<S>,
+}
+
+impl<'e, 'a, S, Y> EventMonitor<'e, 'a, S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub fn new(events: &'e mut [Event<'a, S, Y>], t0: S, y0: &Y) -> Self {
+        let values = events.iter_mut().map(|e| (e.function)(t0, y0)).collect();
+        EventMonitor { events, values }
+    }
+
+    // Checks the step that was just added to the solution for crossings and
+    // records them. If one of them is terminal, the solution is cut short at
+    // the first terminal crossing and true is returned.
+    pub fn check(&mut self, solution: &mut OdeSolution<S, Y>) -> bool {
+        let n = solution.times.len();
+        let (t0, t1) = (solution.times[n - 2], solution.times[n - 1]);
+        let (y0, y1) = (&solution.states[n - 2], &solution.states[n - 1]);
+        let (dy0, dy1) = (&solution.derivatives[n - 2], &solution.derivatives[n - 1]);
+        let mut found = Vec::new();
+        for (index, event) in self.events.iter_mut().enumerate() {
+            let before = self.values[index];
+            let after = (event.function)(t1, y1);
+            self.values[index] = after;
+            let rising = before < S::zero() && after >= S::zero();
+            let falling = before > S::zero() && after <= S::zero();
+            let crossed = match event.direction {
+                EventDirection::Rising => rising,
+                EventDirection::Falling => falling,
+                EventDirection::Either => rising || falling,
+            };
+            if crossed {
+                let mut g = |t: S| (event.function)(t, &hermite(t0, y0, dy0, t1, y1, dy1, t).0);
+                let t = find_root(&mut g, t0, before, t1, after);
+                found.push((t, index, event.terminal));
+            }
+        }
+        // Take the crossings in the order they happen
+        found.sort_by(|a, b| {
+            let (a, b) = if t1 > t0 { (a.0, b.0) } else { (b.0, a.0) };
+            a.to_f64().total_cmp(&b.to_f64())
+        });
+        for &(t, index, terminal) in found.iter() {
+            let (y, dy) = hermite(t0, y0, dy0, t1, y1, dy1, t);
+            solution.events.push(EventRecord { index, t, y: y.clone() });
+            if terminal {
+                solution.times[n - 1] = t;
+                solution.states[n - 1] = y;
+                solution.derivatives[n - 1] = dy;
+                solution.terminated = true;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// Finds a zero of g between a and b, where g(a) = ga and g(b) = gb have
+// opposite signs (or gb is zero), by the Illinois variant of the false
+// position method
+fn find_root<S, G>(g: &mut G, mut a: S, mut ga: S, mut b: S, mut gb: S) -> S
+where
+    S: Real,
+    G: FnMut(S) -> S,
+{
+    let tol = S::from(4.0) * S::epsilon() * a.abs().max(b.abs());
+    for _ in 0..100 {
+        if gb == S::zero() || (b - a).abs() <= tol {
+            break;
+        }
+        let c = b - gb * (b - a) / (gb - ga);
+        let gc = g(c);
+        if (gc > S::zero()) != (gb > S::zero()) {
+            a = b;
+            ga = gb;
+        } else {
+            // Halving the value at the end that stays put keeps it from
+            // being stuck there
+            ga *= S::from(0.5);
+        }
+        b = c;
+        gb = gc;
+    }
+    b
+}
@@ -0,0 +1,367 @@
+
+pub use super::ode_event::{Event, EventRecord};
+pub use super::ode_state::{OdeState, Real, Scalar};
+pub use crate::math::lin_alg::dense_matrix::DenseMatrix;
+
+use super::ode_state::{components, from_components};
+
+use std::error;
+use std::fmt;
+
+// The ways in which solving an initial value problem can fail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OdeError {
+    // A fixed step solve was asked to take this many steps
+    InvalidStepCount { count: u64 },
+    // The method has no error estimate, so it can only take fixed steps
+    NotAdaptive,
+    // The step size needed to meet the tolerances became negligible at time t
+    StepSizeTooSmall { t: f64 },
+    // The maximum number of steps was taken before reaching the end, at time t
+    TooManySteps { t: f64 },
+    // A delay differential equation had a negative delay, which would need
+    // the solution's future
+    InvalidDelay { delay: f64 },
+    // A delay differential equation was asked to be solved from t0 back to
+    // an earlier t1, which its history can't start
+    Backwards { t0: f64, t1: f64 },
+    // A force callback gave a different number of accelerations than there
+    // are particles
+    AccelerationCount { expected: usize, found: usize },
+    // A mechanical state with a different number of velocities than
+    // positions
+    VelocityCount { positions: usize, velocities: usize },
+}
+
+impl fmt::Display for OdeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OdeError::InvalidStepCount { count } => write!(f, "A solve can't take {} steps.", count),
+            OdeError::NotAdaptive => write!(f, "This method has no error estimate, so it can only take fixed steps."),
+            OdeError::StepSizeTooSmall { t } => write!(
+                f,
+                "The step size became too small to meet the tolerances at t = {}.",
+                t
+            ),
+            OdeError::TooManySteps { t } => write!(
+                f,
+                "The maximum number of steps was reached at t = {}.",
+                t
+            ),
+            OdeError::InvalidDelay { delay } => write!(f, "A delay of {} would need the solution's future.", delay),
+            OdeError::Backwards { t0, t1 } => write!(
+                f,
+                "A delay differential equation can only be solved forwards, not from t0 = {} to t1 = {}.",
+                t0, t1
+            ),
+            OdeError::AccelerationCount { expected, found } => write!(
+                f,
+                "The accelerations of {} particles were asked for, but {} were given.",
+                expected, found
+            ),
+            OdeError::VelocityCount { positions, velocities } => write!(
+                f,
+                "A state of {} particles can't have {} velocities.",
+                positions, velocities
+            ),
+        }
+    }
+}
+
+impl error::Error for OdeError {}
+
+// The trajectory found by a solver, like the result of SciPy's solve_ivp.
+// states[i] is the solution at times[i] and derivatives[i] is f there; the
+// first entry is the initial condition and the last is at the end of the
+// requested range, or at the terminal event that stopped the solve.
+//
+// Between the times, the solution is interpolated with the cubic Hermite
+// polynomial through the states and derivatives at the ends of each step. This
+// is fourth order, so it is less accurate than the steps of the higher order
+// methods when they are long.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OdeSolution<S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub times: Vec<S>,
+    pub states: Vec<Y>,
+    pub derivatives: Vec<Y>,
+    // Every event crossing found, in the order they happened
+    pub events: Vec<EventRecord<S, Y>>,
+    // Whether a terminal event stopped the solve early
+    pub terminated: bool,
+    // The number of times the right hand side was evaluated
+    pub evaluations: usize,
+    pub accepted_steps: usize,
+    pub rejected_steps: usize,
+    // The number of Jacobians computed (analytically or by finite differences)
+    // and LU decompositions made by the implicit solvers
+    pub jacobian_evaluations: usize,
+    pub lu_decompositions: usize,
+}
+
+impl<S, Y> OdeSolution<S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub(crate) fn new(t0: S, y0: Y, dy0: Y) -> Self {
+        OdeSolution {
+            times: vec![t0],
+            states: vec![y0],
+            derivatives: vec![dy0],
+            events: Vec::new(),
+            terminated: false,
+            evaluations: 0,
+            accepted_steps: 0,
+            rejected_steps: 0,
+            jacobian_evaluations: 0,
+            lu_decompositions: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, t: S, y: Y, dy: Y) {
+        self.times.push(t);
+        self.states.push(y);
+        self.derivatives.push(dy);
+        self.accepted_steps += 1;
+    }
+
+    pub fn final_time(&self) -> S {
+        *self.times.last().unwrap()
+    }
+
+    pub fn final_state(&self) -> &Y {
+        self.states.last().unwrap()
+    }
+
+    // The solution at any time covered by the solve, or None outside of it
+    pub fn interpolate(&self, t: S) -> Option<Y> {
+        let first = self.times[0];
+        let last = self.final_time();
+        let forwards = last >= first;
+        let inside = if forwards { first <= t && t <= last } else { last <= t && t <= first };
+        if !inside {
+            return None;
+        }
+        if self.times.len() == 1 {
+            return Some(self.states[0].clone());
+        }
+        // The first step that ends at or after t
+        let end = self.times.partition_point(|&time| if forwards { time < t } else { time > t }).max(1);
+        let start = end - 1;
+        let (y, _) = hermite(
+            self.times[start],
+            &self.states[start],
+            &self.derivatives[start],
+            self.times[end],
+            &self.states[end],
+            &self.derivatives[end],
+            t,
+        );
+        Some(y)
+    }
+}
+
+// The cubic Hermite interpolant through (t0, y0) and (t1, y1) with slopes
+// dy0 and dy1, and its derivative, at t
+pub(crate) fn hermite<S, Y>(t0: S, y0: &Y, dy0: &Y, t1: S, y1: &Y, dy1: &Y, t: S) -> (Y, Y)
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    let h = t1 - t0;
+    let s = (t - t0) / h;
+    let (one, two, three) = (S::from(1.0), S::from(2.0), S::from(3.0));
+    let s2 = s * s;
+    let s3 = s2 * s;
+    let h00 = two * s3 - three * s2 + one;
+    let h10 = s3 - two * s2 + s;
+    let h01 = three * s2 - two * s3;
+    let h11 = s3 - s2;
+    let d00 = S::from(6.0) * (s2 - s) / h;
+    let d10 = three * s2 - S::from(4.0) * s + one;
+    let d11 = three * s2 - two * s;
+    let mut y = y0.clone();
+    let mut dy = dy0.clone();
+    for i in 0..y0.dimension() {
+        let (a, da, b, db) = (y0.component(i), dy0.component(i), y1.component(i), dy1.component(i));
+        y.set_component(i, h00 * a + h10 * h * da + h01 * b + h11 * h * db);
+        dy.set_component(i, d00 * (a - b) + d10 * da + d11 * db);
+    }
+    (y, dy)
+}
+
+// The root mean square of err, with each component measured against
+// atol + rtol * max(|y|, |y_new|) as in SciPy. A step is acceptable when
+// this is at most one.
+pub(crate) fn error_norm<S, Y>(err: &Y, y: &Y, y_new: &Y, rtol: S, atol: S) -> S
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    let n = err.dimension();
+    if n == 0 {
+        return S::zero();
+    }
+    let mut digest = S::zero();
+    for i in 0..n {
+        let scale = atol + rtol * y.component(i).abs().max(y_new.component(i).abs());
+        let e = err.component(i) / scale;
+        digest += e * e;
+    }
+    (digest / S::from(n as f64)).sqrt()
+}
+
+// The smallest step worth taking from t, near which t + h rounds back to t.
+// Close to t = 0 rounding sets no such limit, so steps are also measured
+// against the whole range, which steps that small could never get across.
+pub(crate) fn min_step<S>(t: S, t0: S, t1: S) -> S
+where S: Real {
+    S::from(10.0) * S::epsilon() * t.abs().max(S::epsilon() * (t1 - t0).abs())
+}
+
+// Estimates a good size for the first step (Hairer, Norsett and Wanner,
+// section II.4), given f0 = f(t0, y0) and the order of the error estimate.
+// The f evaluation it makes is added to evaluations.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn initial_step<S, Y, F>(f: &mut F, t0: S, y0: &Y, f0: &Y, direction: S, order: usize, rtol: S, atol: S, evaluations: &mut usize) -> S
+where
+    S: Real,
+    Y: OdeState<S>,
+    F: FnMut(S, &Y) -> Y,
+{
+    let d0 = error_norm(y0, y0, y0, rtol, atol).to_f64();
+    let d1 = error_norm(f0, y0, y0, rtol, atol).to_f64();
+    let h0 = if d0 < 1e-5 || d1 < 1e-5 { 1e-6 } else { 0.01 * d0 / d1 };
+    let mut y1 = y0.clone();
+    for i in 0..y0.dimension() {
+        y1.set_component(i, y0.component(i) + S::from(h0) * direction * f0.component(i));
+    }
+    let f1 = f(t0 + S::from(h0) * direction, &y1);
+    *evaluations += 1;
+    let mut diff = f1.clone();
+    for i in 0..f1.dimension() {
+        diff.set_component(i, f1.component(i) - f0.component(i));
+    }
+    let d2 = error_norm(&diff, y0, y0, rtol, atol).to_f64() / h0;
+    let h1 = if d1 <= 1e-15 && d2 <= 1e-15 {
+        (h0 * 1e-3).max(1e-6)
+    } else {
+        (0.01 / d1.max(d2)).powf(1.0 / (order as f64 + 1.0))
+    };
+    S::from((100.0 * h0).min(h1))
+}
+
+// The implicit solvers work on the components of the state directly, so that
+// they can use dense linear algebra
+pub(crate) type FlatRhs<'a, S> = dyn FnMut(S, &[S]) -> Vec<S> + 'a;
+pub(crate) type FlatJacobian<'a, S> = dyn FnMut(S, &[S]) -> DenseMatrix<S> + 'a;
+
+// Runs solve on the flattened system and reshapes its solution into states
+// like y0. The event functions are flattened too.
+pub(crate) fn solve_flattened<S, Y, F, J, D>(mut f: F, mut jac: Option<J>, y0: Y, events: &mut [Event<S, Y>], solve: D) -> Result<OdeSolution<S, Y>, OdeError>
+where
+    S: Real,
+    Y: OdeState<S>,
+    F: FnMut(S, &Y) -> Y,
+    J: FnMut(S, &Y) -> DenseMatrix<S>,
+    D: FnOnce(&mut FlatRhs<S>, Option<&mut FlatJacobian<S>>, Vec<S>, &mut [Event<S, Vec<S>>]) -> Result<OdeSolution<S, Vec<S>>, OdeError>,
+{
+    let template = &y0;
+    let mut flat_f = |t: S, y: &[S]| components(&f(t, &from_components(template, y)));
+    let mut flat_jac = jac.as_mut().map(|j| move |t: S, y: &[S]| j(t, &from_components(template, y)));
+    let mut flat_events: Vec<Event<S, Vec<S>>> = events
+        .iter_mut()
+        .map(|event| {
+            let function = &mut event.function;
+            Event::new(move |t, y: &Vec<S>| function(t, &from_components(template, y)), event.direction, event.terminal)
+        })
+        .collect();
+    let flat = solve(&mut flat_f, flat_jac.as_mut().map(|j| j as &mut FlatJacobian<S>), components(template), &mut flat_events)?;
+    let unflatten = |values: &Vec<Vec<S>>| values.iter().map(|y| from_components(template, y)).collect();
+    Ok(OdeSolution {
+        states: unflatten(&flat.states),
+        derivatives: unflatten(&flat.derivatives),
+        events: flat
+            .events
+            .iter()
+            .map(|e| EventRecord { index: e.index, t: e.t, y: from_components(template, &e.y) })
+            .collect(),
+        times: flat.times,
+        terminated: flat.terminated,
+        evaluations: flat.evaluations,
+        accepted_steps: flat.accepted_steps,
+        rejected_steps: flat.rejected_steps,
+        jacobian_evaluations: flat.jacobian_evaluations,
+        lu_decompositions: flat.lu_decompositions,
+    })
+}
+
+// df/dy at (t, y). Without an analytic Jacobian, each column is found with a
+// forward difference from fy = f(t, y), which is evaluated if not given. The
+// difference steps are relative to the components of y, but no smaller than
+// relative to atol, so that tiny components aren't swamped. A component that
+// is zero when atol is too gets a step relative to 1 instead.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn jacobian<S>(f: &mut FlatRhs<S>, jac: &mut Option<&mut FlatJacobian<S>>, t: S, y: &[S], fy: Option<&[S]>, atol: S, solution: &mut OdeSolution<S, Vec<S>>) -> DenseMatrix<S>
+where S: Real {
+    solution.jacobian_evaluations += 1;
+    if let Some(jac) = jac {
+        return jac(t, y);
+    }
+    let fy = match fy {
+        Some(fy) => fy.to_vec(),
+        None => {
+            solution.evaluations += 1;
+            f(t, y)
+        }
+    };
+    let n = y.len();
+    let root_eps = S::epsilon().sqrt();
+    let mut digest = DenseMatrix::zeros(n, n);
+    let mut shifted = y.to_vec();
+    for j in 0..n {
+        // Round the step so that it is exactly representable as a difference
+        let scale = y[j].abs().max(atol);
+        let scale = if scale == S::zero() { S::one() } else { scale };
+        let step = (y[j] + root_eps * scale) - y[j];
+        shifted[j] = y[j] + step;
+        let f_shifted = f(t, &shifted);
+        solution.evaluations += 1;
+        for i in 0..n {
+            digest[(i, j)] = (f_shifted[i] - fy[i]) / step;
+        }
+        shifted[j] = y[j];
+    }
+    digest
+}
+
+// The root mean square of x / scale, used to measure Newton updates and
+// error estimates against the tolerances
+pub(crate) fn scaled_norm<S>(x: &[S], scale: &[S]) -> f64
+where S: Real {
+    if x.is_empty() {
+        return 0.0;
+    }
+    let mut digest = 0.0;
+    for (&a, &s) in x.iter().zip(scale.iter()) {
+        let e = (a / s).to_f64();
+        digest += e * e;
+    }
+    (digest / x.len() as f64).sqrt()
+}
+
+// How closely the Newton iterations of the implicit solvers are converged
+pub(crate) fn newton_tolerance<S>(rtol: S) -> f64
+where S: Real {
+    let rtol = rtol.to_f64();
+    (10.0 * f64::EPSILON / rtol).max(0.03f64.min(rtol.sqrt()))
+}
+
+pub(crate) fn is_finite<S>(x: &[S]) -> bool
+where S: Real {
+    x.iter().all(|v| v.to_f64().is_finite())
+}
@@ -0,0 +1,8 @@
+pub use crate::math::components::{Complex, Real, Scalar, Vector3D};
+
+pub(crate) use crate::math::components::{components, from_components};
+
+// Anything that can be the state y of a system y' = f(t, y). The solvers
+// only need to read and write the state's components, so anything with
+// components can be integrated directly.
+pub use crate::math::components::Components as OdeState;
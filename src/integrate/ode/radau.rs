@@ -0,0 +1,297 @@
+
+pub use super::ode_solver::{DenseMatrix, Event, OdeError, OdeSolution, OdeState, Real, Scalar};
+pub use crate::math::lin_alg::dense_matrix::LuDecomposition;
+
+use super::ode_event::EventMonitor;
+use super::ode_solver::{initial_step, is_finite, jacobian, min_step, newton_tolerance, scaled_norm, solve_flattened, FlatJacobian, FlatRhs};
+
+const NEWTON_MAXITER: usize = 6;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+
+const SQRT_6: f64 = 2.449489742783178;
+
+// The Radau IIA collocation nodes and coefficients
+const C: [f64; 3] = [(4.0 - SQRT_6) / 10.0, (4.0 + SQRT_6) / 10.0, 1.0];
+const A: [[f64; 3]; 3] = [
+    [(88.0 - 7.0 * SQRT_6) / 360.0, (296.0 - 169.0 * SQRT_6) / 1800.0, (-2.0 + 3.0 * SQRT_6) / 225.0],
+    [(296.0 + 169.0 * SQRT_6) / 1800.0, (88.0 + 7.0 * SQRT_6) / 360.0, (-2.0 - 3.0 * SQRT_6) / 225.0],
+    [(16.0 - SQRT_6) / 36.0, (16.0 + SQRT_6) / 36.0, 1.0 / 9.0],
+];
+
+// The embedded error estimate of Hairer and Wanner, which uses the real
+// eigenvalue of the inverse of A
+const E: [f64; 3] = [(-13.0 - 7.0 * SQRT_6) / 3.0, (-13.0 + 7.0 * SQRT_6) / 3.0, -1.0 / 3.0];
+const MU_REAL: f64 = 3.637834252744496;
+
+// The three stage, fifth order Radau IIA method, an implicit Runge-Kutta
+// method that is L-stable and so damps stiff components completely, in the
+// manner of Hairer and Wanner's RADAU5 and SciPy's Radau. The stages are
+// found together by simplified Newton iteration on the full 3n x 3n system.
+//
+// The Jacobian df/dy is either given or found by finite differences, and is
+// only recomputed when the iteration fails to converge.
+pub struct RadauSolver<S>
+where S: Real {
+    pub rtol: S,
+    pub atol: S,
+    // The size of the first step; estimated from f if None
+    pub first_step: Option<S>,
+    pub max_step: S,
+    // The number of steps (accepted or rejected) after which to give up
+    pub max_steps: usize,
+}
+
+impl<S> Default for RadauSolver<S>
+where S: Real
+{
+    fn default() -> Self {
+        RadauSolver {
+            rtol: S::from(1e-3),
+            atol: S::from(1e-6),
+            first_step: None,
+            max_step: S::from(f64::INFINITY),
+            max_steps: 100_000,
+        }
+    }
+}
+
+impl<S> RadauSolver<S>
+where S: Real
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Solves from y(t0) = y0 to t1, finding the Jacobian by finite differences
+    pub fn solve<Y, F>(&self, f: F, t0: S, t1: S, y0: Y) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        self.solve_with_events(f, t0, t1, y0, &mut [])
+    }
+
+    // Solves from y(t0) = y0 to t1, recording the crossings of the events and
+    // stopping early at the first terminal one
+    pub fn solve_with_events<Y, F>(&self, f: F, t0: S, t1: S, y0: Y, events: &mut [Event<S, Y>]) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        solve_flattened(f, None::<fn(S, &Y) -> DenseMatrix<S>>, y0, events, |f, jac, y0, events| self.solve_flat(f, jac, t0, t1, y0, events))
+    }
+
+    // Solves from y(t0) = y0 to t1, where jac(t, y) is the Jacobian of f.
+    // Entry (i, j) is the derivative of component i of f by component j of y.
+    pub fn solve_with_jacobian<Y, F, J>(&self, f: F, jac: J, t0: S, t1: S, y0: Y) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        J: FnMut(S, &Y) -> DenseMatrix<S>,
+    {
+        self.solve_with_jacobian_and_events(f, jac, t0, t1, y0, &mut [])
+    }
+
+    // solve_with_jacobian, watching for events like solve_with_events
+    pub fn solve_with_jacobian_and_events<Y, F, J>(&self, f: F, jac: J, t0: S, t1: S, y0: Y, events: &mut [Event<S, Y>]) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        J: FnMut(S, &Y) -> DenseMatrix<S>,
+    {
+        solve_flattened(f, Some(jac), y0, events, |f, jac, y0, events| self.solve_flat(f, jac, t0, t1, y0, events))
+    }
+
+    fn solve_flat(
+        &self,
+        f: &mut FlatRhs<S>,
+        mut jac: Option<&mut FlatJacobian<S>>,
+        t0: S,
+        t1: S,
+        y0: Vec<S>,
+        events: &mut [Event<S, Vec<S>>],
+    ) -> Result<OdeSolution<S, Vec<S>>, OdeError> {
+        let n = y0.len();
+        let direction = if t1 > t0 { S::from(1.0) } else { S::from(-1.0) };
+        let mut t = t0;
+        let mut y = y0;
+        let mut fy = f(t, &y);
+        let mut solution = OdeSolution::new(t, y.clone(), fy.clone());
+        solution.evaluations += 1;
+        if t0 == t1 {
+            return Ok(solution);
+        }
+        let mut monitor = EventMonitor::new(events, t, &y);
+        let mut h_abs = match self.first_step {
+            Some(h) => h.abs(),
+            None => initial_step(&mut |t, y: &Vec<S>| f(t, y), t, &y, &fy, direction, 3, self.rtol, self.atol, &mut solution.evaluations),
+        };
+        h_abs = h_abs.min(self.max_step).min((t1 - t0).abs());
+        let mut jac_matrix = jacobian(f, &mut jac, t, &y, Some(&fy), self.atol, &mut solution);
+        let newton_tol = newton_tolerance(self.rtol);
+        // The factorisations of the Newton matrix and of the error estimate's matrix
+        let mut lu: Option<(LuDecomposition<S>, LuDecomposition<S>)> = None;
+        let mut attempts = 0;
+        while t != t1 {
+            let mut current_jac = false;
+            let mut rejected = false;
+            loop {
+                if attempts == self.max_steps {
+                    return Err(OdeError::TooManySteps { t: t.to_f64() });
+                }
+                attempts += 1;
+                if h_abs < min_step(t, t0, t1) {
+                    return Err(OdeError::StepSizeTooSmall { t: t.to_f64() });
+                }
+                // Land exactly on t1 rather than stepping past it
+                let mut t_new = t + direction * h_abs;
+                if direction * (t_new - t1) > S::zero() {
+                    t_new = t1;
+                }
+                let h = t_new - t;
+                if h.abs() != h_abs {
+                    h_abs = h.abs();
+                    lu = None;
+                }
+                if lu.is_none() {
+                    lu = factorise(&jac_matrix, h);
+                    solution.lu_decompositions += 2;
+                }
+
+                let scale: Vec<S> = y.iter().map(|&v| self.atol + self.rtol * v.abs()).collect();
+                let newton = match lu.as_ref() {
+                    Some((newton_lu, _)) => solve_collocation_system(f, t, &y, h, newton_lu, &scale, newton_tol, &mut solution.evaluations),
+                    None => None,
+                };
+                let (iterations, z) = match newton {
+                    Some(result) => result,
+                    None if !current_jac => {
+                        jac_matrix = jacobian(f, &mut jac, t, &y, Some(&fy), self.atol, &mut solution);
+                        current_jac = true;
+                        lu = None;
+                        continue;
+                    }
+                    None => {
+                        h_abs *= S::from(0.5);
+                        lu = None;
+                        solution.rejected_steps += 1;
+                        continue;
+                    }
+                };
+
+                let y_new: Vec<S> = (0..n).map(|i| y[i] + z[2 * n + i]).collect();
+                let ze: Vec<S> = (0..n).map(|i| (0..3).fold(S::zero(), |acc, k| acc + S::from(E[k]) * z[k * n + i]) / h).collect();
+                let error_lu = &lu.as_ref().unwrap().1;
+                let mut error = error_lu.solve(&(0..n).map(|i| fy[i] + ze[i]).collect::<Vec<S>>());
+                let scale: Vec<S> = (0..n).map(|i| self.atol + self.rtol * y[i].abs().max(y_new[i].abs())).collect();
+                let mut error_norm = scaled_norm(&error, &scale);
+                let safety = 0.9 * (2 * NEWTON_MAXITER + 1) as f64 / (2 * NEWTON_MAXITER + iterations) as f64;
+                // After a rejection, filter the estimate through one more
+                // solve, which keeps it from being too pessimistic for stiff problems
+                if rejected && error_norm > 1.0 {
+                    let shifted: Vec<S> = (0..n).map(|i| y[i] + error[i]).collect();
+                    let f_shifted = f(t, &shifted);
+                    solution.evaluations += 1;
+                    error = error_lu.solve(&(0..n).map(|i| f_shifted[i] + ze[i]).collect::<Vec<S>>());
+                    error_norm = scaled_norm(&error, &scale);
+                }
+                if error_norm > 1.0 {
+                    h_abs *= S::from(MIN_FACTOR.max(safety * error_norm.powf(-0.25)));
+                    lu = None;
+                    solution.rejected_steps += 1;
+                    rejected = true;
+                    continue;
+                }
+
+                let mut factor = if error_norm == 0.0 { MAX_FACTOR } else { MAX_FACTOR.min(safety * error_norm.powf(-0.25)) };
+                if rejected {
+                    factor = factor.min(1.0);
+                }
+                t = t_new;
+                y = y_new;
+                fy = f(t, &y);
+                solution.evaluations += 1;
+                solution.push(t, y.clone(), fy.clone());
+                h_abs = (h_abs * S::from(factor)).min(self.max_step);
+                lu = None;
+                break;
+            }
+            if monitor.check(&mut solution) {
+                break;
+            }
+        }
+        Ok(solution)
+    }
+}
+
+// Factors the Newton matrix I - h (A x J) of the stages, and the matrix
+// MU_REAL / h I - J of the error estimate. None if either is singular.
+fn factorise<S>(jac: &DenseMatrix<S>, h: S) -> Option<(LuDecomposition<S>, LuDecomposition<S>)>
+where S: Real {
+    let n = jac.size().0;
+    let mut newton = DenseMatrix::identity(3 * n);
+    for (i, row) in A.iter().enumerate() {
+        for (j, &a) in row.iter().enumerate() {
+            let weight = h * S::from(a);
+            for p in 0..n {
+                for q in 0..n {
+                    newton[(i * n + p, j * n + q)] -= weight * jac[(p, q)];
+                }
+            }
+        }
+    }
+    let error = DenseMatrix::identity(n) * (S::from(MU_REAL) / h) - jac.clone();
+    Some((newton.lu().ok()?, error.lu().ok()?))
+}
+
+// Solves for the stage increments z (the three stage values minus y, one
+// after the other) by simplified Newton iteration from z = 0. Returns the
+// number of iterations and z, or None if the iteration doesn't converge
+// quickly enough.
+#[allow(clippy::too_many_arguments)]
+fn solve_collocation_system<S>(f: &mut FlatRhs<S>, t: S, y: &[S], h: S, lu: &LuDecomposition<S>, scale: &[S], tol: f64, evaluations: &mut usize) -> Option<(usize, Vec<S>)>
+where S: Real {
+    let n = y.len();
+    let mut z = vec![S::zero(); 3 * n];
+    let scale: Vec<S> = scale.iter().cycle().take(3 * n).cloned().collect();
+    let mut dz_norm_old: Option<f64> = None;
+    for k in 0..NEWTON_MAXITER {
+        let mut stages = Vec::with_capacity(3);
+        for (i, &c) in C.iter().enumerate() {
+            let stage_y: Vec<S> = (0..n).map(|p| y[p] + z[i * n + p]).collect();
+            let stage_f = f(t + S::from(c) * h, &stage_y);
+            *evaluations += 1;
+            if !is_finite(&stage_f) {
+                return None;
+            }
+            stages.push(stage_f);
+        }
+        let mut residual = vec![S::zero(); 3 * n];
+        for i in 0..3 {
+            for p in 0..n {
+                let mut digest = S::zero() - z[i * n + p];
+                for (j, stage) in stages.iter().enumerate() {
+                    digest += h * S::from(A[i][j]) * stage[p];
+                }
+                residual[i * n + p] = digest;
+            }
+        }
+        let dz = lu.solve(&residual);
+        let dz_norm = scaled_norm(&dz, &scale);
+        let rate = dz_norm_old.map(|old| dz_norm / old);
+        if let Some(rate) = rate {
+            if rate >= 1.0 || rate.powi((NEWTON_MAXITER - k) as i32) / (1.0 - rate) * dz_norm > tol {
+                return None;
+            }
+        }
+        for (a, &b) in z.iter_mut().zip(dz.iter()) {
+            *a += b;
+        }
+        if dz_norm == 0.0 || rate.is_some_and(|rate| rate / (1.0 - rate) * dz_norm < tol) {
+            return Some((k + 1, z));
+        }
+        dz_norm_old = Some(dz_norm);
+    }
+    None
+}
@@ -0,0 +1,198 @@
+
+pub use super::ode_solver::{DenseMatrix, Event, OdeError, OdeSolution, OdeState, Real, Scalar};
+
+use super::ode_event::EventMonitor;
+use super::ode_solver::{initial_step, jacobian, min_step, scaled_norm, solve_flattened, FlatJacobian, FlatRhs};
+
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 5.0;
+
+// The modified Rosenbrock formula of order 2(3) by Shampine and Reichelt, as
+// used by MATLAB's ode23s. Rosenbrock methods are linearly implicit: each
+// step solves linear systems with the matrix I - h d J rather than iterating
+// to convergence, which makes them cheap and robust at crude tolerances. The
+// formula is L-stable, and its error estimate is third order.
+//
+// A fresh Jacobian df/dy is needed at every step, so supplying one saves a
+// lot of work for larger systems. df/dt is always found by a finite difference.
+pub struct RosenbrockSolver<S>
+where S: Real {
+    pub rtol: S,
+    pub atol: S,
+    // The size of the first step; estimated from f if None
+    pub first_step: Option<S>,
+    pub max_step: S,
+    // The number of steps (accepted or rejected) after which to give up
+    pub max_steps: usize,
+}
+
+impl<S> Default for RosenbrockSolver<S>
+where S: Real
+{
+    fn default() -> Self {
+        RosenbrockSolver {
+            rtol: S::from(1e-3),
+            atol: S::from(1e-6),
+            first_step: None,
+            max_step: S::from(f64::INFINITY),
+            max_steps: 100_000,
+        }
+    }
+}
+
+impl<S> RosenbrockSolver<S>
+where S: Real
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Solves from y(t0) = y0 to t1, finding the Jacobian by finite differences
+    pub fn solve<Y, F>(&self, f: F, t0: S, t1: S, y0: Y) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        self.solve_with_events(f, t0, t1, y0, &mut [])
+    }
+
+    // Solves from y(t0) = y0 to t1, recording the crossings of the events and
+    // stopping early at the first terminal one
+    pub fn solve_with_events<Y, F>(&self, f: F, t0: S, t1: S, y0: Y, events: &mut [Event<S, Y>]) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        solve_flattened(f, None::<fn(S, &Y) -> DenseMatrix<S>>, y0, events, |f, jac, y0, events| self.solve_flat(f, jac, t0, t1, y0, events))
+    }
+
+    // Solves from y(t0) = y0 to t1, where jac(t, y) is the Jacobian of f.
+    // Entry (i, j) is the derivative of component i of f by component j of y.
+    pub fn solve_with_jacobian<Y, F, J>(&self, f: F, jac: J, t0: S, t1: S, y0: Y) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        J: FnMut(S, &Y) -> DenseMatrix<S>,
+    {
+        self.solve_with_jacobian_and_events(f, jac, t0, t1, y0, &mut [])
+    }
+
+    // solve_with_jacobian, watching for events like solve_with_events
+    pub fn solve_with_jacobian_and_events<Y, F, J>(&self, f: F, jac: J, t0: S, t1: S, y0: Y, events: &mut [Event<S, Y>]) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        J: FnMut(S, &Y) -> DenseMatrix<S>,
+    {
+        solve_flattened(f, Some(jac), y0, events, |f, jac, y0, events| self.solve_flat(f, jac, t0, t1, y0, events))
+    }
+
+    fn solve_flat(
+        &self,
+        f: &mut FlatRhs<S>,
+        mut jac: Option<&mut FlatJacobian<S>>,
+        t0: S,
+        t1: S,
+        y0: Vec<S>,
+        events: &mut [Event<S, Vec<S>>],
+    ) -> Result<OdeSolution<S, Vec<S>>, OdeError> {
+        let n = y0.len();
+        let d = S::from(1.0 / (2.0 + 2f64.sqrt()));
+        let e32 = S::from(6.0 + 2f64.sqrt());
+        let two = S::from(2.0);
+        let direction = if t1 > t0 { S::from(1.0) } else { S::from(-1.0) };
+        let mut t = t0;
+        let mut y = y0;
+        let mut f0 = f(t, &y);
+        let mut solution = OdeSolution::new(t, y.clone(), f0.clone());
+        solution.evaluations += 1;
+        if t0 == t1 {
+            return Ok(solution);
+        }
+        let mut monitor = EventMonitor::new(events, t, &y);
+        let mut h_abs = match self.first_step {
+            Some(h) => h.abs(),
+            None => initial_step(&mut |t, y: &Vec<S>| f(t, y), t, &y, &f0, direction, 2, self.rtol, self.atol, &mut solution.evaluations),
+        };
+        h_abs = h_abs.min(self.max_step).min((t1 - t0).abs());
+        let mut attempts = 0;
+        while t != t1 {
+            let jac_matrix = jacobian(f, &mut jac, t, &y, Some(&f0), self.atol, &mut solution);
+            // df/dt by a forward difference
+            let dt = S::epsilon().sqrt() * t.abs().max(h_abs);
+            let f_dt = f(t + dt, &y);
+            solution.evaluations += 1;
+            let dfdt: Vec<S> = (0..n).map(|i| (f_dt[i] - f0[i]) / dt).collect();
+            let mut rejected = false;
+            loop {
+                if attempts == self.max_steps {
+                    return Err(OdeError::TooManySteps { t: t.to_f64() });
+                }
+                attempts += 1;
+                if h_abs < min_step(t, t0, t1) {
+                    return Err(OdeError::StepSizeTooSmall { t: t.to_f64() });
+                }
+                // Land exactly on t1 rather than stepping past it
+                let mut t_new = t + direction * h_abs;
+                if direction * (t_new - t1) > S::zero() {
+                    t_new = t1;
+                }
+                let h = t_new - t;
+                h_abs = h.abs();
+
+                let w = DenseMatrix::identity(n) - jac_matrix.clone() * (h * d);
+                solution.lu_decompositions += 1;
+                let lu = match w.lu() {
+                    Ok(lu) => lu,
+                    Err(_) => {
+                        h_abs *= S::from(0.5);
+                        solution.rejected_steps += 1;
+                        rejected = true;
+                        continue;
+                    }
+                };
+                let k1 = lu.solve(&(0..n).map(|i| f0[i] + h * d * dfdt[i]).collect::<Vec<S>>());
+                let f1 = f(t + S::from(0.5) * h, &(0..n).map(|i| y[i] + S::from(0.5) * h * k1[i]).collect::<Vec<S>>());
+                let mut k2 = lu.solve(&(0..n).map(|i| f1[i] - k1[i]).collect::<Vec<S>>());
+                for (a, &b) in k2.iter_mut().zip(k1.iter()) {
+                    *a += b;
+                }
+                let y_new: Vec<S> = (0..n).map(|i| y[i] + h * k2[i]).collect();
+                let f2 = f(t_new, &y_new);
+                let k3 = lu.solve(
+                    &(0..n)
+                        .map(|i| f2[i] - e32 * (k2[i] - f1[i]) - two * (k1[i] - f0[i]) + h * d * dfdt[i])
+                        .collect::<Vec<S>>(),
+                );
+                solution.evaluations += 2;
+
+                let error: Vec<S> = (0..n).map(|i| h / S::from(6.0) * (k1[i] - two * k2[i] + k3[i])).collect();
+                let scale: Vec<S> = (0..n).map(|i| self.atol + self.rtol * y[i].abs().max(y_new[i].abs())).collect();
+                let error_norm = scaled_norm(&error, &scale);
+                if error_norm > 1.0 {
+                    h_abs *= S::from(MIN_FACTOR.max(SAFETY * error_norm.powf(-1.0 / 3.0)));
+                    solution.rejected_steps += 1;
+                    rejected = true;
+                    continue;
+                }
+                let mut factor = if error_norm == 0.0 { MAX_FACTOR } else { MAX_FACTOR.min(SAFETY * error_norm.powf(-1.0 / 3.0)) };
+                // Don't grow a step that has only just been shrunk
+                if rejected {
+                    factor = factor.min(1.0);
+                }
+                t = t_new;
+                y = y_new;
+                // The last stage is f at the new point, so it starts the next step
+                f0 = f2;
+                solution.push(t, y.clone(), f0.clone());
+                h_abs = (h_abs * S::from(factor)).min(self.max_step);
+                break;
+            }
+            if monitor.check(&mut solution) {
+                break;
+            }
+        }
+        Ok(solution)
+    }
+}
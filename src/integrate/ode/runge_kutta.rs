@@ -0,0 +1,475 @@
+
+pub use super::ode_solver::{Event, OdeError, OdeSolution, OdeState, Real, Scalar};
+
+use super::ode_event::EventMonitor;
+use super::ode_solver::{error_norm, initial_step, min_step};
+
+// Step size control follows SciPy: the new step is the old one times
+// SAFETY * err^(-1 / (error_order + 1)), clamped to [MIN_FACTOR, MAX_FACTOR]
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+
+// The coefficients of an explicit Runge-Kutta method. Row i of a holds the
+// i coefficients used to build stage i from the stages before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButcherTableau {
+    pub c: &'static [f64],
+    pub a: &'static [&'static [f64]],
+    pub b: &'static [f64],
+    // The weights of the embedded solution used to estimate the error, if any
+    pub b_hat: Option<&'static [f64]>,
+    // The weights of a second, lower order embedded solution, which some
+    // methods use alongside b_hat to sharpen the error estimate
+    pub b_hat_low: Option<&'static [f64]>,
+    pub order: usize,
+    // The order of the embedded solution
+    pub error_order: usize,
+    // Whether the last stage is f at the new state, so it can be reused as
+    // the first stage of the next step
+    pub fsal: bool,
+}
+
+// The classic fourth order method
+const RK4: ButcherTableau = ButcherTableau {
+    c: &[0.0, 0.5, 0.5, 1.0],
+    a: &[&[], &[0.5], &[0.0, 0.5], &[0.0, 0.0, 1.0]],
+    b: &[1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0],
+    b_hat: None,
+    b_hat_low: None,
+    order: 4,
+    error_order: 4,
+    fsal: false,
+};
+
+// Bogacki-Shampine 3(2)
+const RK23: ButcherTableau = ButcherTableau {
+    c: &[0.0, 1.0 / 2.0, 3.0 / 4.0, 1.0],
+    a: &[&[], &[1.0 / 2.0], &[0.0, 3.0 / 4.0], &[2.0 / 9.0, 1.0 / 3.0, 4.0 / 9.0]],
+    b: &[2.0 / 9.0, 1.0 / 3.0, 4.0 / 9.0, 0.0],
+    b_hat: Some(&[7.0 / 24.0, 1.0 / 4.0, 1.0 / 3.0, 1.0 / 8.0]),
+    b_hat_low: None,
+    order: 3,
+    error_order: 2,
+    fsal: true,
+};
+
+// Dormand-Prince 5(4)
+const RK45: ButcherTableau = ButcherTableau {
+    c: &[0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0],
+    a: &[
+        &[],
+        &[1.0 / 5.0],
+        &[3.0 / 40.0, 9.0 / 40.0],
+        &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+        &[19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0],
+        &[9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0],
+        &[35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+    ],
+    b: &[35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0, 0.0],
+    b_hat: Some(&[
+        5179.0 / 57600.0,
+        0.0,
+        7571.0 / 16695.0,
+        393.0 / 640.0,
+        -92097.0 / 339200.0,
+        187.0 / 2100.0,
+        1.0 / 40.0,
+    ]),
+    b_hat_low: None,
+    order: 5,
+    error_order: 4,
+    fsal: true,
+};
+
+// Dormand-Prince 8(5,3), as in Hairer's DOP853 and SciPy. It advances with
+// the eighth order solution, and estimates the error from a fifth and a
+// third order one together (see step_error_norm).
+const DOP853: ButcherTableau = ButcherTableau {
+    c: &[
+        0.0,
+        0.05260015195876773,
+        0.0789002279381516,
+        0.1183503419072274,
+        0.2816496580927726,
+        0.3333333333333333,
+        0.25,
+        0.3076923076923077,
+        0.6512820512820513,
+        0.6,
+        0.8571428571428571,
+        1.0,
+    ],
+    a: &[
+        &[],
+        &[0.05260015195876773],
+        &[0.0197250569845379, 0.0591751709536137],
+        &[0.02958758547680685, 0.0, 0.08876275643042054],
+        &[0.2413651341592667, 0.0, -0.8845494793282861, 0.924834003261792],
+        &[0.037037037037037035, 0.0, 0.0, 0.17082860872947386, 0.12546768756682242],
+        &[0.037109375, 0.0, 0.0, 0.17025221101954405, 0.06021653898045596, -0.017578125],
+        &[
+            0.03709200011850479,
+            0.0,
+            0.0,
+            0.17038392571223998,
+            0.10726203044637328,
+            -0.015319437748624402,
+            0.008273789163814023,
+        ],
+        &[
+            0.6241109587160757,
+            0.0,
+            0.0,
+            -3.3608926294469414,
+            -0.868219346841726,
+            27.59209969944671,
+            20.154067550477894,
+            -43.48988418106996,
+        ],
+        &[
+            0.47766253643826434,
+            0.0,
+            0.0,
+            -2.4881146199716677,
+            -0.590290826836843,
+            21.230051448181193,
+            15.279233632882423,
+            -33.28821096898486,
+            -0.020331201708508627,
+        ],
+        &[
+            -0.9371424300859873,
+            0.0,
+            0.0,
+            5.186372428844064,
+            1.0914373489967295,
+            -8.149787010746927,
+            -18.52006565999696,
+            22.739487099350505,
+            2.4936055526796523,
+            -3.0467644718982196,
+        ],
+        &[
+            2.273310147516538,
+            0.0,
+            0.0,
+            -10.53449546673725,
+            -2.0008720582248625,
+            -17.9589318631188,
+            27.94888452941996,
+            -2.8589982771350235,
+            -8.87285693353063,
+            12.360567175794303,
+            0.6433927460157636,
+        ],
+    ],
+    b: &[
+        0.054293734116568765,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        4.450312892752409,
+        1.8915178993145003,
+        -5.801203960010585,
+        0.3111643669578199,
+        -0.1521609496625161,
+        0.20136540080403034,
+        0.04471061572777259,
+    ],
+    b_hat: Some(&[
+        0.04117368912237388,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        5.675469339128614,
+        2.3872768489717506,
+        -7.465581142465571,
+        0.6614932157077936,
+        -0.48634006837553356,
+        0.11944219431891463,
+        0.06706592359165889,
+    ]),
+    b_hat_low: Some(&[
+        0.2440944881889764,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.7338466882816118,
+        0.0,
+        0.0,
+        0.022058823529411766,
+    ]),
+    order: 8,
+    error_order: 7,
+    fsal: false,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RungeKuttaMethod {
+    // The classic fourth order method, which can only take fixed steps
+    Rk4,
+    // Bogacki-Shampine 3(2), for loose tolerances
+    Rk23,
+    // Dormand-Prince 5(4), a good default
+    Rk45,
+    // Dormand-Prince 8(5,3), for tight tolerances
+    Dop853,
+}
+
+impl RungeKuttaMethod {
+    pub fn tableau(&self) -> &'static ButcherTableau {
+        match self {
+            RungeKuttaMethod::Rk4 => &RK4,
+            RungeKuttaMethod::Rk23 => &RK23,
+            RungeKuttaMethod::Rk45 => &RK45,
+            RungeKuttaMethod::Dop853 => &DOP853,
+        }
+    }
+}
+
+// Solves initial value problems y' = f(t, y) with explicit Runge-Kutta
+// methods, much like SciPy's solve_ivp. The adaptive methods keep the
+// estimated local error of each step below atol + rtol * |y|, component by
+// component. Integrating backwards in time (t1 < t0) is allowed.
+pub struct RungeKuttaSolver<S>
+where S: Real {
+    pub method: RungeKuttaMethod,
+    pub rtol: S,
+    pub atol: S,
+    // The size of the first step; estimated from f if None
+    pub first_step: Option<S>,
+    pub max_step: S,
+    // The number of steps (accepted or rejected) after which to give up
+    pub max_steps: usize,
+}
+
+impl<S> Default for RungeKuttaSolver<S>
+where S: Real
+{
+    fn default() -> Self {
+        Self::new(RungeKuttaMethod::Rk45)
+    }
+}
+
+impl<S> RungeKuttaSolver<S>
+where S: Real
+{
+    // The tolerances default to those of solve_ivp
+    pub fn new(method: RungeKuttaMethod) -> Self {
+        RungeKuttaSolver {
+            method,
+            rtol: S::from(1e-3),
+            atol: S::from(1e-6),
+            first_step: None,
+            max_step: S::from(f64::INFINITY),
+            max_steps: 100_000,
+        }
+    }
+
+    // Solves from y(t0) = y0 to t1, choosing the steps adaptively. The
+    // solution holds every accepted step.
+    pub fn solve<Y, F>(&self, f: F, t0: S, t1: S, y0: Y) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        self.solve_with_events(f, t0, t1, y0, &mut [])
+    }
+
+    // Solves from y(t0) = y0 to t1 like solve, recording the crossings of the
+    // events and stopping early at the first terminal one
+    pub fn solve_with_events<Y, F>(&self, mut f: F, t0: S, t1: S, y0: Y, events: &mut [Event<S, Y>]) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        let tableau = self.method.tableau();
+        let estimate = ErrorEstimate::new(tableau)?;
+        let exponent = -1.0 / (tableau.error_order as f64 + 1.0);
+        let mut t = t0;
+        let mut y = y0;
+        let mut dy = f(t, &y);
+        let mut solution = OdeSolution::new(t, y.clone(), dy.clone());
+        solution.evaluations += 1;
+        if t0 == t1 {
+            return Ok(solution);
+        }
+        let mut monitor = EventMonitor::new(events, t, &y);
+        let direction = if t1 > t0 { S::from(1.0) } else { S::from(-1.0) };
+        let mut h = match self.first_step {
+            Some(h) => h.abs(),
+            None => initial_step(&mut f, t, &y, &dy, direction, tableau.error_order, self.rtol, self.atol, &mut solution.evaluations),
+        };
+        h = h.min(self.max_step).min((t1 - t0).abs());
+        let mut attempts = 0;
+        while t != t1 {
+            let mut rejected = false;
+            loop {
+                if attempts == self.max_steps {
+                    return Err(OdeError::TooManySteps { t: t.to_f64() });
+                }
+                attempts += 1;
+                if h < min_step(t, t0, t1) {
+                    return Err(OdeError::StepSizeTooSmall { t: t.to_f64() });
+                }
+                // Land exactly on t1 rather than stepping past it
+                let mut t_new = t + direction * h;
+                if direction * (t_new - t1) > S::zero() {
+                    t_new = t1;
+                }
+                let step = t_new - t;
+                let stages = stages(tableau, &mut f, t, &y, step, dy.clone(), &mut solution.evaluations);
+                let y_new = combine(&y, step, tableau.b, &stages);
+                let norm = estimate.norm(&y, &y_new, step, &stages, self.rtol, self.atol).to_f64();
+                if norm <= 1.0 {
+                    let mut factor = if norm == 0.0 { MAX_FACTOR } else { (SAFETY * norm.powf(exponent)).min(MAX_FACTOR) };
+                    // Don't grow a step that has only just been shrunk
+                    if rejected {
+                        factor = factor.min(1.0);
+                    }
+                    h = (step.abs() * S::from(factor)).min(self.max_step);
+                    t = t_new;
+                    y = y_new;
+                    dy = if tableau.fsal {
+                        stages.last().unwrap().clone()
+                    } else {
+                        solution.evaluations += 1;
+                        f(t, &y)
+                    };
+                    solution.push(t, y.clone(), dy.clone());
+                    break;
+                }
+                h = step.abs() * S::from((SAFETY * norm.powf(exponent)).max(MIN_FACTOR));
+                solution.rejected_steps += 1;
+                rejected = true;
+            }
+            if monitor.check(&mut solution) {
+                break;
+            }
+        }
+        Ok(solution)
+    }
+
+    // Solves from y(t0) = y0 to t1 in the given number of equal steps,
+    // ignoring the tolerances. Any of the methods can be used.
+    pub fn solve_fixed<Y, F>(&self, mut f: F, t0: S, t1: S, y0: Y, steps: u64) -> Result<OdeSolution<S, Y>, OdeError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+    {
+        if steps == 0 {
+            return Err(OdeError::InvalidStepCount { count: steps });
+        }
+        let tableau = self.method.tableau();
+        let h = (t1 - t0) / S::from(steps as f64);
+        let mut t = t0;
+        let mut y = y0;
+        let mut dy = f(t, &y);
+        let mut solution = OdeSolution::new(t, y.clone(), dy.clone());
+        solution.evaluations += 1;
+        for i in 1..=steps {
+            let stages = stages(tableau, &mut f, t, &y, h, dy, &mut solution.evaluations);
+            y = combine(&y, h, tableau.b, &stages);
+            // Compute each time from t0 so that rounding errors don't build up
+            t = if i == steps { t1 } else { t0 + h * S::from(i as f64) };
+            dy = f(t, &y);
+            solution.evaluations += 1;
+            solution.push(t, y.clone(), dy.clone());
+        }
+        Ok(solution)
+    }
+}
+
+// Estimates the local error of a step from the tableau's embedded solutions
+pub(crate) struct ErrorEstimate {
+    // The differences between b and the embedded weights
+    weights: Vec<f64>,
+    low_weights: Option<Vec<f64>>,
+}
+
+impl ErrorEstimate {
+    pub fn new(tableau: &ButcherTableau) -> Result<Self, OdeError> {
+        let difference = |b_hat: &[f64]| tableau.b.iter().zip(b_hat.iter()).map(|(b, e)| b - e).collect();
+        let b_hat = tableau.b_hat.ok_or(OdeError::NotAdaptive)?;
+        Ok(ErrorEstimate { weights: difference(b_hat), low_weights: tableau.b_hat_low.map(difference) })
+    }
+
+    // The error of the step from y to y_new, measured by error_norm. With a
+    // lower order solution too, this is err^2 / sqrt(err^2 + 0.01 err_low^2),
+    // as in DOP853.
+    pub fn norm<S, Y>(&self, y: &Y, y_new: &Y, h: S, stages: &[Y], rtol: S, atol: S) -> S
+    where
+        S: Real,
+        Y: OdeState<S>,
+    {
+        let error = error_norm(&weighted_sum(y, h, &self.weights, stages), y, y_new, rtol, atol);
+        match &self.low_weights {
+            None => error,
+            Some(low_weights) => {
+                let low = error_norm(&weighted_sum(y, h, low_weights, stages), y, y_new, rtol, atol);
+                let denominator = error * error + S::from(0.01) * low * low;
+                if denominator == S::zero() {
+                    S::zero()
+                } else {
+                    error * error / denominator.sqrt()
+                }
+            }
+        }
+    }
+}
+
+// Evaluates every stage of a step of size h from (t, y), where dy = f(t, y)
+fn stages<S, Y, F>(tableau: &ButcherTableau, f: &mut F, t: S, y: &Y, h: S, dy: Y, evaluations: &mut usize) -> Vec<Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+    F: FnMut(S, &Y) -> Y,
+{
+    let mut stages = Vec::with_capacity(tableau.c.len());
+    stages.push(dy);
+    for (&c, a) in tableau.c.iter().zip(tableau.a.iter()).skip(1) {
+        let y_stage = combine(y, h, a, &stages);
+        stages.push(f(t + S::from(c) * h, &y_stage));
+        *evaluations += 1;
+    }
+    stages
+}
+
+// y + h * sum(weights[j] * stages[j])
+pub(crate) fn combine<S, Y>(y: &Y, h: S, weights: &[f64], stages: &[Y]) -> Y
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    let mut digest = weighted_sum(y, h, weights, stages);
+    for i in 0..y.dimension() {
+        digest.set_component(i, y.component(i) + digest.component(i));
+    }
+    digest
+}
+
+// h * sum(weights[j] * stages[j]), shaped like y
+pub(crate) fn weighted_sum<S, Y>(y: &Y, h: S, weights: &[f64], stages: &[Y]) -> Y
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    let mut digest = y.clone();
+    for i in 0..y.dimension() {
+        let mut sum = S::zero();
+        for (&w, stage) in weights.iter().zip(stages.iter()) {
+            if w != 0.0 {
+                sum += S::from(w) * stage.component(i);
+            }
+        }
+        digest.set_component(i, h * sum);
+    }
+    digest
+}
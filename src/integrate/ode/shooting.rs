@@ -0,0 +1,116 @@
+
+pub use super::bvp::BvpError;
+pub use super::ode_solver::{OdeSolution, OdeState, Real, Scalar};
+pub use super::runge_kutta::{RungeKuttaMethod, RungeKuttaSolver};
+
+use super::bvp::difference_jacobian;
+use super::ode_state::{components, from_components};
+
+// The number of times a Newton step may be halved before it's taken anyway
+const BACKTRACKS: usize = 10;
+
+// Solves two point boundary value problems y' = f(x, y), bc(y(a), y(b)) = 0
+// by the shooting method: the initial state y(a) is found by Newton iteration
+// so that integrating to b meets the boundary conditions. Each iteration
+// takes n + 1 integrations for the finite difference Jacobian.
+//
+// Shooting is accurate and cheap for well behaved problems, but fails when
+// the solution from a has growing modes that amplify the error in y(a), in
+// which case BvpSolver should be used.
+pub struct ShootingSolver<S>
+where S: Real {
+    // The integrator used for each shot. Its tolerances should be much
+    // tighter than tol, as the Jacobian is found by finite differences.
+    pub integrator: RungeKuttaSolver<S>,
+    // The tolerance on the boundary condition residuals
+    pub tol: S,
+    pub max_iterations: usize,
+}
+
+impl<S> Default for ShootingSolver<S>
+where S: Real
+{
+    fn default() -> Self {
+        let mut integrator = RungeKuttaSolver::new(RungeKuttaMethod::Rk45);
+        integrator.rtol = S::from(1e-10);
+        integrator.atol = S::from(1e-12);
+        ShootingSolver { integrator, tol: S::from(1e-6), max_iterations: 50 }
+    }
+}
+
+impl<S> ShootingSolver<S>
+where S: Real
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Solves on [a, b] from the guess for y(a), returning the trajectory of
+    // the final shot
+    pub fn solve<Y, F, B>(&self, mut f: F, mut bc: B, a: S, b: S, guess: Y) -> Result<OdeSolution<S, Y>, BvpError>
+    where
+        Y: OdeState<S>,
+        F: FnMut(S, &Y) -> Y,
+        B: FnMut(&Y, &Y) -> Y,
+    {
+        let mut y0 = components(&guess);
+        let mut shot = self.integrator.solve(&mut f, a, b, guess.clone())?;
+        let mut residual = components(&bc(&from_components(&guess, &y0), shot.final_state()));
+        if residual.len() != y0.len() {
+            return Err(BvpError::BoundaryConditionCount { expected: y0.len(), found: residual.len() });
+        }
+        for _ in 0..self.max_iterations {
+            if residual.iter().all(|r| r.abs() <= self.tol) {
+                return Ok(shot);
+            }
+            // The residual as a function of the initial state
+            let mut failure = None;
+            let mut shoot = |y: &[S]| {
+                let start = from_components(&guess, y);
+                match self.integrator.solve(&mut f, a, b, start.clone()) {
+                    Ok(shot) => components(&bc(&start, shot.final_state())),
+                    Err(e) => {
+                        failure = Some(e);
+                        vec![S::zero(); y.len()]
+                    }
+                }
+            };
+            let jac = difference_jacobian(&mut shoot, &y0, &residual, &mut 0);
+            if let Some(e) = failure {
+                return Err(e.into());
+            }
+            let step = jac.solve(&residual).map_err(|_| BvpError::SingularJacobian)?;
+
+            // Halve the step until the residual shrinks
+            let size = max_norm(&residual);
+            let mut alpha = S::from(1.0);
+            for trial in 0..=BACKTRACKS {
+                let candidate: Vec<S> = y0.iter().zip(step.iter()).map(|(&y, &s)| y - alpha * s).collect();
+                let start = from_components(&guess, &candidate);
+                match self.integrator.solve(&mut f, a, b, start.clone()) {
+                    Ok(candidate_shot) => {
+                        let candidate_residual = components(&bc(&start, candidate_shot.final_state()));
+                        if max_norm(&candidate_residual) < size || trial == BACKTRACKS {
+                            y0 = candidate;
+                            shot = candidate_shot;
+                            residual = candidate_residual;
+                            break;
+                        }
+                    }
+                    Err(e) if trial == BACKTRACKS => return Err(e.into()),
+                    Err(_) => {}
+                }
+                alpha *= S::from(0.5);
+            }
+        }
+        if residual.iter().all(|r| r.abs() <= self.tol) {
+            return Ok(shot);
+        }
+        Err(BvpError::NotConverged { iterations: self.max_iterations })
+    }
+}
+
+fn max_norm<S>(x: &[S]) -> S
+where S: Real {
+    x.iter().fold(S::zero(), |acc, v| acc.max(v.abs()))
+}
@@ -0,0 +1,238 @@
+
+pub use super::ode_solver::OdeError;
+pub use crate::math::lin_alg::spatial_vector::SpatialVector;
+pub use crate::math::scalar::Scalar;
+pub use crate::spatial::direction::Direction;
+pub use crate::spatial::position::Position;
+
+// The fourth order triple jump weights 1 / (2 - 2^(1/3)) and
+// -2^(1/3) / (2 - 2^(1/3)), halved and summed as the methods need them
+const W1: f64 = 1.3512071919596578;
+const W0: f64 = -1.7024143839193153;
+const HALF_W1: f64 = 0.6756035959798289;
+const HALF_W0_W1: f64 = -0.17560359597982877;
+
+// A part of a splitting method's step, as a fraction of the step size. A
+// drift moves the positions at the current velocities and a kick changes the
+// velocities by the current accelerations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Substep {
+    Drift(f64),
+    Kick(f64),
+}
+
+const VELOCITY_VERLET: [Substep; 3] = [Substep::Kick(0.5), Substep::Drift(1.0), Substep::Kick(0.5)];
+const LEAPFROG: [Substep; 3] = [Substep::Drift(0.5), Substep::Kick(1.0), Substep::Drift(0.5)];
+const YOSHIDA_4: [Substep; 7] = [
+    Substep::Kick(HALF_W1),
+    Substep::Drift(W1),
+    Substep::Kick(HALF_W0_W1),
+    Substep::Drift(W0),
+    Substep::Kick(HALF_W0_W1),
+    Substep::Drift(W1),
+    Substep::Kick(HALF_W1),
+];
+const FOREST_RUTH: [Substep; 7] = [
+    Substep::Drift(HALF_W1),
+    Substep::Kick(W1),
+    Substep::Drift(HALF_W0_W1),
+    Substep::Kick(W0),
+    Substep::Drift(HALF_W0_W1),
+    Substep::Kick(W1),
+    Substep::Drift(HALF_W1),
+];
+
+// Splitting methods for systems x'' = a(t, x), which alternate exact drifts
+// and kicks. Being symplectic, they keep the energy error of a conservative
+// system bounded over any number of steps, where general purpose methods let
+// it drift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymplecticMethod {
+    // Second order: half kick, drift, half kick
+    VelocityVerlet,
+    // Second order: half drift, kick, half drift (also known as position Verlet)
+    Leapfrog,
+    // Fourth order: Yoshida's triple jump composition of velocity Verlet
+    Yoshida4,
+    // Fourth order: Forest and Ruth's method, the triple jump of the leapfrog
+    ForestRuth,
+}
+
+impl SymplecticMethod {
+    pub fn order(&self) -> usize {
+        match self {
+            SymplecticMethod::VelocityVerlet | SymplecticMethod::Leapfrog => 2,
+            SymplecticMethod::Yoshida4 | SymplecticMethod::ForestRuth => 4,
+        }
+    }
+
+    fn substeps(&self) -> &'static [Substep] {
+        match self {
+            SymplecticMethod::VelocityVerlet => &VELOCITY_VERLET,
+            SymplecticMethod::Leapfrog => &LEAPFROG,
+            SymplecticMethod::Yoshida4 => &YOSHIDA_4,
+            SymplecticMethod::ForestRuth => &FOREST_RUTH,
+        }
+    }
+}
+
+// The positions and velocities of a system of particles, one of each per
+// particle, which the solvers check. With unit masses, the velocities are
+// also the momenta.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MechanicalState<S, V>
+where
+    S: Scalar,
+    V: SpatialVector<S> + Copy,
+{
+    pub positions: Vec<Position<S, V>>,
+    pub velocities: Vec<Direction<S, V>>,
+}
+
+impl<S, V> MechanicalState<S, V>
+where
+    S: Scalar,
+    V: SpatialVector<S> + Copy,
+{
+    pub fn new(positions: Vec<Position<S, V>>, velocities: Vec<Direction<S, V>>) -> Self {
+        MechanicalState { positions, velocities }
+    }
+
+    // The kinetic energy per unit mass, the sum of |v|^2 / 2
+    pub fn kinetic_energy(&self) -> S {
+        self.velocities.iter().fold(S::zero(), |acc, v| acc + v.dot(v)) * S::from(0.5)
+    }
+}
+
+// The trajectory of a mechanical system, with the state at every step
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymplecticSolution<S, V>
+where
+    S: Scalar,
+    V: SpatialVector<S> + Copy,
+{
+    pub times: Vec<S>,
+    pub states: Vec<MechanicalState<S, V>>,
+    // The number of times the accelerations were evaluated
+    pub evaluations: usize,
+}
+
+impl<S, V> SymplecticSolution<S, V>
+where
+    S: Scalar,
+    V: SpatialVector<S> + Copy,
+{
+    pub fn final_time(&self) -> S {
+        *self.times.last().unwrap()
+    }
+
+    pub fn final_state(&self) -> &MechanicalState<S, V> {
+        self.states.last().unwrap()
+    }
+}
+
+// Integrates mechanical systems with fixed steps, since changing the step
+// size breaks the symplectic structure. The accelerations are given by a
+// callback a(t, positions), which returns the force on each particle divided
+// by its mass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymplecticSolver {
+    pub method: SymplecticMethod,
+}
+
+impl Default for SymplecticSolver {
+    fn default() -> Self {
+        SymplecticSolver::new(SymplecticMethod::VelocityVerlet)
+    }
+}
+
+impl SymplecticSolver {
+    pub fn new(method: SymplecticMethod) -> Self {
+        SymplecticSolver { method }
+    }
+
+    // Advances the state by one step of size h from time t. Returns the
+    // number of times the accelerations were evaluated.
+    pub fn step<S, V, A>(&self, accelerations: &mut A, t: S, state: &mut MechanicalState<S, V>, h: S) -> Result<usize, OdeError>
+    where
+        S: Scalar,
+        V: SpatialVector<S> + Copy,
+        A: FnMut(S, &[Position<S, V>]) -> Vec<Direction<S, V>>,
+    {
+        let mut evaluations = 0;
+        self.advance(accelerations, t, state, h, &mut None, &mut evaluations)?;
+        Ok(evaluations)
+    }
+
+    // Solves from the initial state at t0 to t1 in the given number of
+    // equal steps
+    pub fn solve<S, V, A>(&self, mut accelerations: A, t0: S, t1: S, state0: MechanicalState<S, V>, steps: u64) -> Result<SymplecticSolution<S, V>, OdeError>
+    where
+        S: Scalar,
+        V: SpatialVector<S> + Copy,
+        A: FnMut(S, &[Position<S, V>]) -> Vec<Direction<S, V>>,
+    {
+        if steps == 0 {
+            return Err(OdeError::InvalidStepCount { count: steps });
+        }
+        let h = (t1 - t0) / S::from(steps as f64);
+        let mut solution = SymplecticSolution { times: vec![t0], states: vec![state0.clone()], evaluations: 0 };
+        let mut state = state0;
+        // Velocity Verlet and Yoshida end a step with a kick at the positions
+        // the next one starts with, so those accelerations are carried over
+        let mut cached = None;
+        for i in 1..=steps {
+            let t = t0 + h * S::from((i - 1) as f64);
+            self.advance(&mut accelerations, t, &mut state, h, &mut cached, &mut solution.evaluations)?;
+            // Compute each time from t0 so that rounding errors don't build up
+            solution.times.push(if i == steps { t1 } else { t0 + h * S::from(i as f64) });
+            solution.states.push(state.clone());
+        }
+        Ok(solution)
+    }
+
+    // One step, where cached holds the accelerations at the current
+    // positions if they are known
+    fn advance<S, V, A>(&self, accelerations: &mut A, t: S, state: &mut MechanicalState<S, V>, h: S, cached: &mut Option<Vec<Direction<S, V>>>, evaluations: &mut usize) -> Result<(), OdeError>
+    where
+        S: Scalar,
+        V: SpatialVector<S> + Copy,
+        A: FnMut(S, &[Position<S, V>]) -> Vec<Direction<S, V>>,
+    {
+        if state.positions.len() != state.velocities.len() {
+            return Err(OdeError::VelocityCount { positions: state.positions.len(), velocities: state.velocities.len() });
+        }
+        // The time the positions have reached
+        let mut time = t;
+        for substep in self.method.substeps() {
+            match *substep {
+                Substep::Drift(weight) => {
+                    let dt = h * S::from(weight);
+                    for (x, &v) in state.positions.iter_mut().zip(state.velocities.iter()) {
+                        *x = *x + v * dt;
+                    }
+                    time += dt;
+                    *cached = None;
+                }
+                Substep::Kick(weight) => {
+                    let a = match cached.take() {
+                        Some(a) => a,
+                        None => {
+                            *evaluations += 1;
+                            accelerations(time, &state.positions)
+                        }
+                    };
+                    if a.len() != state.velocities.len() {
+                        return Err(OdeError::AccelerationCount { expected: state.velocities.len(), found: a.len() });
+                    }
+                    let dt = h * S::from(weight);
+                    for (v, &a) in state.velocities.iter_mut().zip(a.iter()) {
+                        *v = *v + a * dt;
+                    }
+                    *cached = Some(a);
+                }
+            }
+        }
+        Ok(())
+    }
+}
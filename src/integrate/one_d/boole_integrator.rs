@@ -0,0 +1,54 @@
+
+pub use super::integrator::{IntegrationError, Integrator, Real, Scalar};
+pub use super::newton_cotes_integrator::NewtonCotesIntegrator;
+
+// Composite Boole's rule, which fits a quartic through every four intervals
+pub struct BooleIntegrator<S>
+where S: Scalar {
+    rule: NewtonCotesIntegrator<S>,
+}
+
+impl<S> BooleIntegrator<S>
+where S: Scalar
+{
+    pub fn new() -> Self {
+        BooleIntegrator { rule: NewtonCotesIntegrator::closed(4).unwrap() }
+    }
+}
+
+impl<S> Default for BooleIntegrator<S>
+where S: Scalar
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Integrator<S> for BooleIntegrator<S>
+where S: Scalar
+{
+    // The number of steps is rounded up to a multiple of 4
+    fn integrate_func<F>(&self, f: F, a: S, b: S, steps: u64) -> Result<S, IntegrationError>
+    where F: FnMut(S) -> S
+    {
+        self.rule.integrate_func(f, a, b, steps)
+    }
+
+    fn integrate_func_time_series<F>(&self, f: F, times: &[S]) -> Result<S, IntegrationError>
+    where
+        S: Real,
+        F: FnMut(S) -> S,
+    {
+        self.rule.integrate_func_time_series(f, times)
+    }
+
+    fn integrate_data(&self, vals: &[S], dx: S) -> Result<S, IntegrationError> {
+        self.rule.integrate_data(vals, dx)
+    }
+
+    fn integrate_data_time_series(&self, times: &[S], vals: &[S]) -> Result<S, IntegrationError>
+    where S: Real
+    {
+        self.rule.integrate_data_time_series(times, vals)
+    }
+}
@@ -0,0 +1,282 @@
+
+pub use super::integrator::{Real, Scalar};
+
+use std::f64::consts::PI;
+
+// The classical orthogonal polynomial families, each named with its weight
+// function w(x) and the domain it is integrated over
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GaussFamily<S>
+where S: Real {
+    // w(x) = 1 over [-1, 1]
+    Legendre,
+    // w(x) = x^alpha e^-x over [0, inf), alpha > -1
+    Laguerre { alpha: S },
+    // w(x) = e^(-x^2) over (-inf, inf)
+    Hermite,
+    // w(x) = 1 / sqrt(1 - x^2) over [-1, 1], i.e. Chebyshev polynomials of the first kind
+    Chebyshev,
+    // w(x) = (1 - x)^alpha (1 + x)^beta over [-1, 1], alpha, beta > -1
+    Jacobi { alpha: S, beta: S },
+}
+
+// An n-point Gaussian quadrature rule. Sum_i weights[i] f(nodes[i]) is the
+// integral of f(x) w(x) over the family's domain, and it is exact whenever f
+// is a polynomial of degree 2n - 1 or less.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianQuadrature<S>
+where S: Real {
+    pub family: GaussFamily<S>,
+    // Sorted in increasing order
+    pub nodes: Vec<S>,
+    pub weights: Vec<S>,
+}
+
+impl<S> GaussianQuadrature<S>
+where S: Real
+{
+    // Computes the nodes and weights of the rule with the Golub-Welsch
+    // algorithm: the nodes are the eigenvalues of the Jacobi matrix of the
+    // family's three-term recurrence, and the weights come from the first
+    // components of its normalized eigenvectors.
+    pub fn new(family: GaussFamily<S>, order: usize) -> Result<Self, &'static str> {
+        if order == 0 {
+            return Err("A Gaussian quadrature rule needs at least one node.");
+        }
+        let (diag, off_diag, moment) = match family {
+            GaussFamily::Legendre => {
+                let off_diag = (1..order)
+                    .map(|k| {
+                        let k = S::from(k as f64);
+                        k / (S::from(4.0) * k * k - S::from(1.0)).sqrt()
+                    })
+                    .collect();
+                (vec![S::zero(); order], off_diag, S::from(2.0))
+            }
+            GaussFamily::Laguerre { alpha } => {
+                if alpha <= S::from(-1.0) {
+                    return Err("The Laguerre weight needs alpha > -1.");
+                }
+                let diag = (0..order).map(|k| S::from(2.0 * k as f64 + 1.0) + alpha).collect();
+                let off_diag = (1..order)
+                    .map(|k| {
+                        let k = S::from(k as f64);
+                        (k * (k + alpha)).sqrt()
+                    })
+                    .collect();
+                (diag, off_diag, S::from(gamma(alpha.to_f64() + 1.0)))
+            }
+            GaussFamily::Hermite => {
+                let off_diag = (1..order).map(|k| (S::from(k as f64) * S::from(0.5)).sqrt()).collect();
+                (vec![S::zero(); order], off_diag, S::from(PI).sqrt())
+            }
+            GaussFamily::Chebyshev => {
+                let off_diag = (1..order)
+                    .map(|k| if k == 1 { S::from(0.5).sqrt() } else { S::from(0.5) })
+                    .collect();
+                (vec![S::zero(); order], off_diag, S::from(PI))
+            }
+            GaussFamily::Jacobi { alpha, beta } => {
+                if alpha <= S::from(-1.0) || beta <= S::from(-1.0) {
+                    return Err("The Jacobi weight needs alpha > -1 and beta > -1.");
+                }
+                let (diag, off_diag) = jacobi_recurrence(order, alpha, beta);
+                // The gamma function is only computed in f64
+                let (alpha, beta) = (alpha.to_f64(), beta.to_f64());
+                let moment = 2.0_f64.powf(alpha + beta + 1.0) * gamma(alpha + 1.0) * gamma(beta + 1.0)
+                    / gamma(alpha + beta + 2.0);
+                (diag, off_diag, S::from(moment))
+            }
+        };
+        let (eigenvalues, first_components) = symmetric_tridiagonal_eigen(diag, off_diag)?;
+        if eigenvalues.iter().chain(first_components.iter()).any(|x| !x.to_f64().is_finite()) {
+            return Err("The eigenvalues of the Jacobi matrix are not finite.");
+        }
+        let mut pairs: Vec<(S, S)> = eigenvalues
+            .into_iter()
+            .zip(first_components)
+            .map(|(x, v)| (x, moment * v * v))
+            .collect();
+        pairs.sort_by(|l, r| l.0.to_f64().total_cmp(&r.0.to_f64()));
+        let (nodes, weights) = pairs.into_iter().unzip();
+        Ok(GaussianQuadrature { family, nodes, weights })
+    }
+
+    pub fn order(&self) -> usize {
+        self.nodes.len()
+    }
+
+    // Integrates f(x) w(x) over the family's domain
+    pub fn integrate<F>(&self, mut f: F) -> S
+    where F: FnMut(S) -> S
+    {
+        let mut digest = S::zero();
+        for (&x, &w) in self.nodes.iter().zip(self.weights.iter()) {
+            digest += w * f(x);
+        }
+        digest
+    }
+
+    // Integrates f over [a, b] by linearly mapping the rule's nodes from
+    // [-1, 1]. The weight function is mapped along with the nodes, so for
+    // Legendre rules this is just the integral of f.
+    pub fn integrate_interval<F>(&self, mut f: F, a: S, b: S) -> Result<S, &'static str>
+    where F: FnMut(S) -> S
+    {
+        match self.family {
+            GaussFamily::Laguerre { .. } | GaussFamily::Hermite => {
+                Err("Only rules over [-1, 1] can be mapped onto a finite interval.")
+            }
+            _ => {
+                let half_length = (b - a) * S::from(0.5);
+                let center = (a + b) * S::from(0.5);
+                Ok(self.integrate(|x| f(center + half_length * x)) * half_length)
+            }
+        }
+    }
+}
+
+// The diagonal and off-diagonal of the Jacobi matrix for the Jacobi polynomials
+fn jacobi_recurrence<S>(order: usize, alpha: S, beta: S) -> (Vec<S>, Vec<S>)
+where S: Real {
+    let one = S::from(1.0);
+    let two = S::from(2.0);
+    let sum = alpha + beta;
+    let diff = beta * beta - alpha * alpha;
+    let diag = (0..order)
+        .map(|k| {
+            let s = two * S::from(k as f64) + sum;
+            if k == 0 {
+                // Written this way to avoid 0/0 when alpha + beta = 0
+                (beta - alpha) / (sum + two)
+            } else {
+                diff / (s * (s + two))
+            }
+        })
+        .collect();
+    let off_diag = (1..order)
+        .map(|k| {
+            let kf = S::from(k as f64);
+            let s = two * kf + sum;
+            let squared = if k == 1 {
+                // Cancels the (k + alpha + beta) / (s - 1) factor, which is 0/0 when alpha + beta = -1
+                S::from(4.0) * (one + alpha) * (one + beta) / (s * s * (s + one))
+            } else {
+                S::from(4.0) * kf * (kf + alpha) * (kf + beta) * (kf + sum) / (s * s * (s + one) * (s - one))
+            };
+            squared.sqrt()
+        })
+        .collect();
+    (diag, off_diag)
+}
+
+// Finds the eigenvalues of a symmetric tridiagonal matrix, along with the
+// first component of each normalized eigenvector, using the QL algorithm with
+// implicit shifts. off_diag[i] couples rows i and i + 1.
+fn symmetric_tridiagonal_eigen<S>(mut d: Vec<S>, off_diag: Vec<S>) -> Result<(Vec<S>, Vec<S>), &'static str>
+where S: Real {
+    let n = d.len();
+    let mut e = off_diag;
+    e.push(S::zero());
+    let mut z = vec![S::zero(); n];
+    z[0] = S::from(1.0);
+    let one = S::from(1.0);
+    let two = S::from(2.0);
+    for l in 0..n {
+        let mut iterations = 0;
+        loop {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs() <= S::epsilon() * dd {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+            iterations += 1;
+            if iterations > 60 {
+                return Err("The eigenvalues of the Jacobi matrix did not converge.");
+            }
+            let mut g = (d[l + 1] - d[l]) / (two * e[l]);
+            let mut r = hypot(g, one);
+            g = d[m] - d[l] + e[l] / (g + if g >= S::zero() { r } else { S::zero() - r });
+            let mut s = one;
+            let mut c = one;
+            let mut p = S::zero();
+            let mut underflow = false;
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = hypot(f, g);
+                e[i + 1] = r;
+                if r == S::zero() {
+                    d[i + 1] -= p;
+                    e[m] = S::zero();
+                    underflow = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + two * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+                let z_next = z[i + 1];
+                z[i + 1] = s * z[i] + c * z_next;
+                z[i] = c * z[i] - s * z_next;
+            }
+            if underflow {
+                continue;
+            }
+            d[l] -= p;
+            e[l] = g;
+            e[m] = S::zero();
+        }
+    }
+    Ok((d, z))
+}
+
+// sqrt(a^2 + b^2) without destructive overflow or underflow
+fn hypot<S>(a: S, b: S) -> S
+where S: Real {
+    let a = a.abs();
+    let b = b.abs();
+    let (big, small) = if a > b { (a, b) } else { (b, a) };
+    if big == S::zero() {
+        S::zero()
+    } else {
+        let ratio = small / big;
+        big * (S::from(1.0) + ratio * ratio).sqrt()
+    }
+}
+
+// The gamma function, using the Lanczos approximation (g = 7, n = 9)
+fn gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula
+        PI / ((PI * x).sin() * gamma(1.0 - x))
+    } else {
+        let x = x - 1.0;
+        let mut sum = COEFFS[0];
+        for (i, &c) in COEFFS.iter().enumerate().skip(1) {
+            sum += c / (x + i as f64);
+        }
+        let t = x + 7.5;
+        (2.0 * PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * sum
+    }
+}
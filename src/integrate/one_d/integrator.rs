@@ -1,11 +1,141 @@
 
-pub use crate::math::scalar::Scalar;
+pub use crate::math::scalar::{Real, Scalar};
 
+use std::cmp;
+use std::error;
+use std::fmt;
+
+// The ways in which an integration request can be malformed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrationError {
+    // No samples (or time stamps) were given
+    EmptyInput,
+    // The number of time stamps and the number of samples differ
+    LengthMismatch { times: usize, vals: usize },
+    // The time stamp at this index is not strictly greater than the one before it
+    NonMonotonicTimes { index: usize },
+    // The integrator can't work with this many samples
+    InvalidSampleCount { count: usize },
+}
+
+impl fmt::Display for IntegrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntegrationError::EmptyInput => write!(f, "There is no data to integrate."),
+            IntegrationError::LengthMismatch { times, vals } => write!(
+                f,
+                "There are {} time stamps but {} values; these must be the same.",
+                times, vals
+            ),
+            IntegrationError::NonMonotonicTimes { index } => write!(
+                f,
+                "The time stamp at index {} is not strictly greater than the previous one.",
+                index
+            ),
+            IntegrationError::InvalidSampleCount { count } => write!(
+                f,
+                "This integrator can't be used with {} samples.",
+                count
+            ),
+        }
+    }
+}
+
+impl error::Error for IntegrationError {}
+
+// Integrators of functions and samples over a range. Only the methods that
+// take time stamps need them to be ordered, so integrands and data over
+// complex numbers can use the rest.
 pub trait Integrator<S>
 where S: Scalar {
-    fn integrate_func(f: fn(S) -> S, a: S, b: S, steps: u64) -> S;
-    fn integrate_func_time_series(f: fn(S) -> S, times: Vec<S>) -> S;
-    fn integrate_data(vals: Vec<S>, dx: S) -> S;
-    fn integrate_data_time_series(times: Vec<S>, vals: Vec<S>) -> S;
+    // Integrates f over [a, b] using the given number of sub-intervals, of
+    // which there must be at least one
+    fn integrate_func<F>(&self, f: F, a: S, b: S, steps: u64) -> Result<S, IntegrationError>
+    where F: FnMut(S) -> S;
+
+    // Integrates f by sampling it at the given (strictly increasing) times
+    fn integrate_func_time_series<F>(&self, f: F, times: &[S]) -> Result<S, IntegrationError>
+    where
+        S: Real,
+        F: FnMut(S) -> S;
+
+    // Integrates equally spaced samples that are dx apart
+    fn integrate_data(&self, vals: &[S], dx: S) -> Result<S, IntegrationError>;
+
+    // Integrates samples taken at the given (strictly increasing) times
+    fn integrate_data_time_series(&self, times: &[S], vals: &[S]) -> Result<S, IntegrationError>
+    where S: Real;
+}
+
+// Integrators that pick their own sample points until a tolerance is met
+pub trait AdaptiveIntegrator<S>
+where S: Scalar {
+    fn integrate<F>(&self, f: F, a: S, b: S) -> QuadratureResult<S>
+    where F: FnMut(S) -> S;
 }
 
+// Integrators that can report the running integral of sampled data. With no
+// initial value, entry i is the integral up to sample i + 1. Otherwise, the
+// initial value is used as the integration constant: it is the first entry and
+// is added to every partial integral after it.
+pub trait CumulativeIntegrator<S>
+where S: Scalar {
+    fn cumulative_data(&self, vals: &[S], dx: S, initial: Option<S>) -> Result<Vec<S>, IntegrationError>;
+
+    fn cumulative_data_time_series(&self, times: &[S], vals: &[S], initial: Option<S>) -> Result<Vec<S>, IntegrationError>
+    where S: Real;
+}
+
+// Sums the integrals over each interval into a running total, as described by CumulativeIntegrator
+pub(crate) fn accumulate<S, I>(intervals: I, initial: Option<S>) -> Vec<S>
+where
+    S: Scalar,
+    I: Iterator<Item = S>,
+{
+    let mut digest = Vec::with_capacity(intervals.size_hint().0 + 1);
+    let mut total = initial.unwrap_or(S::zero());
+    if let Some(c) = initial {
+        digest.push(c);
+    }
+    for i in intervals {
+        total += i;
+        digest.push(total);
+    }
+    digest
+}
+
+// Checks that there is at least one time stamp and that they are strictly increasing
+pub fn check_times<S>(times: &[S]) -> Result<(), IntegrationError>
+where S: Real {
+    if times.is_empty() {
+        return Err(IntegrationError::EmptyInput);
+    }
+    // NaN time stamps are unordered, so aren't greater than the one before
+    match times.windows(2).position(|w| w[1].partial_cmp(&w[0]) != Some(cmp::Ordering::Greater)) {
+        Some(i) => Err(IntegrationError::NonMonotonicTimes { index: i + 1 }),
+        None => Ok(()),
+    }
+}
+
+// Checks that the times are valid and that every time stamp has a value
+pub fn check_time_series<S>(times: &[S], vals: &[S]) -> Result<(), IntegrationError>
+where S: Real {
+    if times.len() != vals.len() {
+        return Err(IntegrationError::LengthMismatch { times: times.len(), vals: vals.len() });
+    }
+    check_times(times)
+}
+
+// The outcome of an adaptive integration. Alongside the value, this reports
+// how much the integrator trusts that value and how much work it took.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadratureResult<S>
+where S: Scalar {
+    pub value: S,
+    // An estimate of the absolute error in value
+    pub abs_error: S,
+    // The number of times the integrand was evaluated
+    pub evaluations: usize,
+    // Whether the requested tolerance was met
+    pub converged: bool,
+}
@@ -1,2 +1,10 @@
 pub mod integrator;
 pub mod trapezoid_integrator;
+pub mod gauss_kronrod_integrator;
+pub mod newton_cotes_integrator;
+pub mod simpson_integrator;
+pub mod boole_integrator;
+pub mod romberg_integrator;
+pub mod gaussian_quadrature;
+pub mod tanh_sinh_integrator;
+pub mod streaming_integrator;
@@ -0,0 +1,226 @@
+
+pub use super::integrator::{check_time_series, check_times, IntegrationError, Integrator, Real, Scalar};
+
+// A composite Newton-Cotes rule. The range is split into panels of `intervals`
+// equally sized sub-intervals and the polynomial through the samples in each
+// panel is integrated exactly. Closed rules sample both ends of each panel
+// while open rules only sample the interior points.
+pub struct NewtonCotesIntegrator<S>
+where S: Scalar {
+    intervals: usize,
+    open: bool,
+    // Weights for the sampled nodes of a panel, in units of the step size
+    weights: Vec<S>,
+    // Weights for the closed rule with the same panel width, used for sampled data
+    closed_weights: Vec<S>,
+}
+
+impl<S> NewtonCotesIntegrator<S>
+where S: Scalar
+{
+    // A closed rule whose panels span the given number of sub-intervals,
+    // e.g. 1 is the trapezoid rule and 2 is Simpson's rule
+    pub fn closed(intervals: usize) -> Result<Self, &'static str> {
+        if intervals == 0 {
+            return Err("A closed Newton-Cotes rule needs at least one interval per panel.");
+        }
+        let closed_weights = unit_weights(0, intervals, intervals);
+        Ok(NewtonCotesIntegrator {
+            intervals,
+            open: false,
+            weights: closed_weights.clone(),
+            closed_weights,
+        })
+    }
+
+    // An open rule whose panels span the given number of sub-intervals,
+    // e.g. 2 is the midpoint rule
+    pub fn open(intervals: usize) -> Result<Self, &'static str> {
+        if intervals < 2 {
+            return Err("An open Newton-Cotes rule needs at least two intervals per panel.");
+        }
+        Ok(NewtonCotesIntegrator {
+            intervals,
+            open: true,
+            weights: unit_weights(1, intervals - 1, intervals),
+            closed_weights: unit_weights(0, intervals, intervals),
+        })
+    }
+
+    // The number of sub-intervals in each panel
+    pub fn intervals(&self) -> usize {
+        self.intervals
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    // The weights of each sampled node in a panel, in units of the step size
+    pub fn weights(&self) -> &[S] {
+        &self.weights
+    }
+}
+
+impl<S> Integrator<S> for NewtonCotesIntegrator<S>
+where S: Scalar
+{
+    // The number of steps is rounded up to a multiple of the panel width.
+    // Neighboring panels of a closed rule share an end point, which is only evaluated once.
+    fn integrate_func<F>(&self, mut f: F, a: S, b: S, steps: u64) -> Result<S, IntegrationError>
+    where F: FnMut(S) -> S
+    {
+        if steps == 0 {
+            return Err(IntegrationError::InvalidSampleCount { count: 0 });
+        }
+        let n = self.intervals as u64;
+        let panels = steps.div_ceil(n);
+        let dx = (b - a) / S::from((panels * n) as f64);
+        let mut digest = S::zero();
+        if self.open {
+            for p in 0..panels {
+                for (j, &w) in self.weights.iter().enumerate() {
+                    digest += w * f(a + dx * S::from((p * n + j as u64 + 1) as f64));
+                }
+            }
+        } else {
+            let mut left = f(a);
+            for p in 0..panels {
+                digest += self.weights[0] * left;
+                for (j, &w) in self.weights[1..self.intervals].iter().enumerate() {
+                    digest += w * f(a + dx * S::from((p * n + j as u64 + 1) as f64));
+                }
+                let right = if p + 1 == panels { f(b) } else { f(a + dx * S::from(((p + 1) * n) as f64)) };
+                digest += self.weights[self.intervals] * right;
+                left = right;
+            }
+        }
+        Ok(digest * dx)
+    }
+
+    fn integrate_func_time_series<F>(&self, f: F, times: &[S]) -> Result<S, IntegrationError>
+    where
+        S: Real,
+        F: FnMut(S) -> S,
+    {
+        check_times(times)?;
+        let vals: Vec<S> = times.iter().copied().map(f).collect();
+        Ok(integrate_panels(times, &vals, self.intervals))
+    }
+
+    // Sampled data always includes the end points, so open rules fall back
+    // to the closed rule with the same panel width here.
+    fn integrate_data(&self, vals: &[S], dx: S) -> Result<S, IntegrationError> {
+        if vals.is_empty() {
+            return Err(IntegrationError::EmptyInput);
+        }
+        let n = self.intervals;
+        let steps = vals.len() - 1;
+        if steps < n {
+            let times: Vec<S> = (0..vals.len()).map(|i| S::from(i as f64)).collect();
+            return Ok(dot(&lagrange_weights(&times, times[0], times[steps]), vals) * dx);
+        }
+        let mut digest = S::zero();
+        for p in 0..steps / n {
+            digest += dot(&self.closed_weights, &vals[p * n..=(p + 1) * n]);
+        }
+        let remainder = steps % n;
+        if remainder != 0 {
+            let times: Vec<S> = (0..=n).map(|i| S::from(i as f64)).collect();
+            let weights = lagrange_weights(&times, times[n - remainder], times[n]);
+            digest += dot(&weights, &vals[steps - n..]);
+        }
+        Ok(digest * dx)
+    }
+
+    fn integrate_data_time_series(&self, times: &[S], vals: &[S]) -> Result<S, IntegrationError>
+    where S: Real
+    {
+        check_time_series(times, vals)?;
+        Ok(integrate_panels(times, vals, self.intervals))
+    }
+}
+
+// Integrates (possibly unequally spaced) samples by fitting a polynomial
+// through each panel of n intervals. When the intervals don't divide evenly,
+// the trailing intervals are covered by the polynomial through the last n + 1
+// samples; for n = 2 this is the correction SciPy's simpson uses.
+pub(crate) fn integrate_panels<S>(times: &[S], vals: &[S], n: usize) -> S
+where S: Scalar {
+    let steps = times.len() - 1;
+    if steps == 0 {
+        return S::zero();
+    }
+    if steps < n {
+        return dot(&lagrange_weights(times, times[0], times[steps]), vals);
+    }
+    let mut digest = S::zero();
+    for p in 0..steps / n {
+        let range = p * n..=(p + 1) * n;
+        let nodes = &times[range.clone()];
+        digest += dot(&lagrange_weights(nodes, nodes[0], nodes[n]), &vals[range]);
+    }
+    let remainder = steps % n;
+    if remainder != 0 {
+        let nodes = &times[steps - n..];
+        digest += dot(&lagrange_weights(nodes, nodes[n - remainder], nodes[n]), &vals[steps - n..]);
+    }
+    digest
+}
+
+// The integrals over [from, to] of the Lagrange basis polynomials of the
+// given nodes. Weighting samples at the nodes by these integrates their
+// interpolating polynomial over [from, to].
+pub(crate) fn lagrange_weights<S>(nodes: &[S], from: S, to: S) -> Vec<S>
+where S: Scalar {
+    // Working relative to the first node keeps the coefficients small
+    let origin = nodes[0];
+    let lo = from - origin;
+    let hi = to - origin;
+    let mut weights = Vec::with_capacity(nodes.len());
+    for (i, &x_i) in nodes.iter().enumerate() {
+        // Expand prod_{j != i} (u - u_j) into coefficients of increasing degree
+        let mut coeffs = vec![S::from(1.0)];
+        let mut denom = S::from(1.0);
+        for (j, &x_j) in nodes.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let root = x_j - origin;
+            let mut next = vec![S::zero(); coeffs.len() + 1];
+            for (k, &c) in coeffs.iter().enumerate() {
+                next[k + 1] += c;
+                next[k] -= c * root;
+            }
+            coeffs = next;
+            denom *= x_i - x_j;
+        }
+        let mut integral = S::zero();
+        let mut hi_pow = hi;
+        let mut lo_pow = lo;
+        for (k, &c) in coeffs.iter().enumerate() {
+            integral += c * (hi_pow - lo_pow) / S::from((k + 1) as f64);
+            hi_pow *= hi;
+            lo_pow *= lo;
+        }
+        weights.push(integral / denom);
+    }
+    weights
+}
+
+// Weights for nodes first..=last of a panel spanning 0..=intervals, in units of the step size
+fn unit_weights<S>(first: usize, last: usize, intervals: usize) -> Vec<S>
+where S: Scalar {
+    let nodes: Vec<S> = (first..=last).map(|i| S::from(i as f64)).collect();
+    lagrange_weights(&nodes, S::zero(), S::from(intervals as f64))
+}
+
+// Weighs each sample by the matching weight
+pub(crate) fn dot<S>(weights: &[S], vals: &[S]) -> S
+where S: Scalar {
+    let mut digest = S::zero();
+    for (&w, &v) in weights.iter().zip(vals.iter()) {
+        digest += w * v;
+    }
+    digest
+}
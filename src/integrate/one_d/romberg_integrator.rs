@@ -0,0 +1,165 @@
+
+pub use super::integrator::{AdaptiveIntegrator, IntegrationError, Integrator, QuadratureResult, Real, Scalar};
+pub use super::trapezoid_integrator::TrapezoidIntegrator;
+
+// The triangular table built by Romberg integration. The first column of row
+// k is the trapezoid estimate with 2^k sub-intervals, and each further column
+// applies one more step of Richardson extrapolation to the column before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RombergTableau<S>
+where S: Scalar {
+    pub rows: Vec<Vec<S>>,
+    // The number of times the integrand was evaluated (zero for sampled data)
+    pub evaluations: usize,
+    pub converged: bool,
+}
+
+impl<S> RombergTableau<S>
+where S: Scalar
+{
+    // The most extrapolated estimate
+    pub fn value(&self) -> S {
+        *self.rows.last().unwrap().last().unwrap()
+    }
+
+    // The size of the difference between the last two diagonal entries
+    pub fn abs_error(&self) -> S {
+        let k = self.rows.len() - 1;
+        if k == 0 {
+            S::zero()
+        } else {
+            S::from((self.rows[k][k] - self.rows[k - 1][k - 1]).magnitude())
+        }
+    }
+
+    pub fn diagonal(&self) -> Vec<S> {
+        self.rows.iter().enumerate().map(|(k, row)| row[k]).collect()
+    }
+
+    pub fn to_result(&self) -> QuadratureResult<S> {
+        QuadratureResult {
+            value: self.value(),
+            abs_error: self.abs_error(),
+            evaluations: self.evaluations,
+            converged: self.converged,
+        }
+    }
+}
+
+pub struct RombergIntegrator<S>
+where S: Scalar {
+    pub abs_tol: S,
+    pub rel_tol: S,
+    // The maximum number of times the step size is halved
+    pub max_levels: usize,
+}
+
+impl<S> Default for RombergIntegrator<S>
+where S: Scalar
+{
+    // The defaults match those of SciPy's romberg
+    fn default() -> Self {
+        RombergIntegrator {
+            abs_tol: S::from(1.48e-8),
+            rel_tol: S::from(1.48e-8),
+            max_levels: 10,
+        }
+    }
+}
+
+impl<S> RombergIntegrator<S>
+where S: Scalar
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn integrate<F>(&self, f: F, a: S, b: S) -> QuadratureResult<S>
+    where F: FnMut(S) -> S
+    {
+        self.tableau(f, a, b).to_result()
+    }
+
+    // Builds the tableau for f over [a, b], halving the step until successive
+    // diagonal entries agree to within max(abs_tol, rel_tol * |value|).
+    pub fn tableau<F>(&self, mut f: F, a: S, b: S) -> RombergTableau<S>
+    where F: FnMut(S) -> S
+    {
+        let trapezoid = TrapezoidIntegrator::new();
+        let first = trapezoid.integrate_func(&mut f, a, b, 1).unwrap();
+        let mut tableau = RombergTableau { rows: vec![vec![first]], evaluations: 2, converged: false };
+        let mut steps = 1;
+        for _ in 0..self.max_levels {
+            let coarse = tableau.rows.last().unwrap()[0];
+            let fine = trapezoid.refine(&mut f, a, b, coarse, steps);
+            tableau.evaluations += steps as usize;
+            steps *= 2;
+            self.extend(&mut tableau.rows, fine);
+            if self.within_tolerance(&tableau) {
+                tableau.converged = true;
+                break;
+            }
+        }
+        tableau
+    }
+
+    // Integrates 2^k + 1 equally spaced samples that are dx apart, as SciPy's romb does
+    pub fn integrate_data(&self, vals: &[S], dx: S) -> Result<RombergTableau<S>, IntegrationError> {
+        if vals.is_empty() {
+            return Err(IntegrationError::EmptyInput);
+        }
+        let intervals = vals.len() - 1;
+        if intervals == 0 || !intervals.is_power_of_two() {
+            return Err(IntegrationError::InvalidSampleCount { count: vals.len() });
+        }
+        let half = S::from(0.5);
+        let length = dx * S::from(intervals as f64);
+        let first = (vals[0] + vals[intervals]) * half * length;
+        let mut rows = vec![vec![first]];
+        let mut stride = intervals;
+        while stride > 1 {
+            // The new samples sit halfway between the ones already used
+            let mut digest = S::zero();
+            for i in (stride / 2..intervals).step_by(stride) {
+                digest += vals[i];
+            }
+            let coarse = rows.last().unwrap()[0];
+            let fine = (coarse + digest * dx * S::from(stride as f64)) * half;
+            self.extend(&mut rows, fine);
+            stride /= 2;
+        }
+        let mut tableau = RombergTableau { rows, evaluations: 0, converged: false };
+        tableau.converged = self.within_tolerance(&tableau);
+        Ok(tableau)
+    }
+
+    // Adds a row to the tableau starting with the given trapezoid estimate
+    fn extend(&self, rows: &mut Vec<Vec<S>>, trapezoid: S) {
+        let previous = rows.last().unwrap();
+        let mut row = Vec::with_capacity(previous.len() + 1);
+        row.push(trapezoid);
+        let mut factor = S::from(1.0);
+        for (m, &above) in previous.iter().enumerate() {
+            factor *= S::from(4.0);
+            let current = row[m];
+            row.push(current + (current - above) / (factor - S::from(1.0)));
+        }
+        rows.push(row);
+    }
+
+    fn within_tolerance(&self, tableau: &RombergTableau<S>) -> bool {
+        tableau.rows.len() > 1
+            && tableau.abs_error().magnitude()
+                <= self.abs_tol.magnitude().max(self.rel_tol.magnitude() * tableau.value().magnitude())
+    }
+}
+
+impl<S> AdaptiveIntegrator<S> for RombergIntegrator<S>
+where S: Scalar
+{
+    fn integrate<F>(&self, f: F, a: S, b: S) -> QuadratureResult<S>
+    where F: FnMut(S) -> S
+    {
+        RombergIntegrator::integrate(self, f, a, b)
+    }
+}
@@ -0,0 +1,153 @@
+
+pub use super::integrator::{check_time_series, CumulativeIntegrator, IntegrationError, Integrator, Real, Scalar};
+pub use super::newton_cotes_integrator::NewtonCotesIntegrator;
+
+use super::integrator::accumulate;
+use super::newton_cotes_integrator::{dot, lagrange_weights};
+
+// Composite Simpson's 1/3 rule, which fits a parabola through each pair of intervals.
+// When sampled data has an odd number of intervals, the last one is integrated
+// using the parabola through the last three samples.
+pub struct SimpsonIntegrator<S>
+where S: Scalar {
+    rule: NewtonCotesIntegrator<S>,
+}
+
+impl<S> SimpsonIntegrator<S>
+where S: Scalar
+{
+    pub fn new() -> Self {
+        SimpsonIntegrator { rule: NewtonCotesIntegrator::closed(2).unwrap() }
+    }
+}
+
+impl<S> Default for SimpsonIntegrator<S>
+where S: Scalar
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Integrator<S> for SimpsonIntegrator<S>
+where S: Scalar
+{
+    // The number of steps is rounded up to a multiple of 2
+    fn integrate_func<F>(&self, f: F, a: S, b: S, steps: u64) -> Result<S, IntegrationError>
+    where F: FnMut(S) -> S
+    {
+        self.rule.integrate_func(f, a, b, steps)
+    }
+
+    fn integrate_func_time_series<F>(&self, f: F, times: &[S]) -> Result<S, IntegrationError>
+    where
+        S: Real,
+        F: FnMut(S) -> S,
+    {
+        self.rule.integrate_func_time_series(f, times)
+    }
+
+    fn integrate_data(&self, vals: &[S], dx: S) -> Result<S, IntegrationError> {
+        self.rule.integrate_data(vals, dx)
+    }
+
+    fn integrate_data_time_series(&self, times: &[S], vals: &[S]) -> Result<S, IntegrationError>
+    where S: Real
+    {
+        self.rule.integrate_data_time_series(times, vals)
+    }
+}
+
+// Like SciPy's cumulative_simpson, each interval is integrated using the
+// parabola through its end points and one neighboring sample. The first
+// interval uses the sample after it and every other interval the one before it.
+impl<S> CumulativeIntegrator<S> for SimpsonIntegrator<S>
+where S: Scalar
+{
+    fn cumulative_data(&self, vals: &[S], dx: S, initial: Option<S>) -> Result<Vec<S>, IntegrationError> {
+        if vals.is_empty() {
+            return Err(IntegrationError::EmptyInput);
+        }
+        if vals.len() == 2 {
+            return Ok(accumulate([(vals[0] + vals[1]) * S::from(0.5) * dx].into_iter(), initial));
+        }
+        let nodes = [S::zero(), S::from(1.0), S::from(2.0)];
+        let first = lagrange_weights(&nodes, nodes[0], nodes[1]);
+        let second = lagrange_weights(&nodes, nodes[1], nodes[2]);
+        let intervals = (0..vals.len() - 1).map(|i| {
+            if i == 0 {
+                dot(&first, &vals[..3]) * dx
+            } else {
+                dot(&second, &vals[i - 1..=i + 1]) * dx
+            }
+        });
+        Ok(accumulate(intervals, initial))
+    }
+
+    fn cumulative_data_time_series(&self, times: &[S], vals: &[S], initial: Option<S>) -> Result<Vec<S>, IntegrationError>
+    where S: Real
+    {
+        check_time_series(times, vals)?;
+        if vals.len() == 2 {
+            let interval = (vals[0] + vals[1]) * S::from(0.5) * (times[1] - times[0]);
+            return Ok(accumulate([interval].into_iter(), initial));
+        }
+        let intervals = (0..vals.len() - 1).map(|i| {
+            let start = if i == 0 { 0 } else { i - 1 };
+            let nodes = &times[start..start + 3];
+            dot(&lagrange_weights(nodes, times[i], times[i + 1]), &vals[start..start + 3])
+        });
+        Ok(accumulate(intervals, initial))
+    }
+}
+
+// Composite Simpson's 3/8 rule, which fits a cubic through every three intervals
+pub struct SimpsonThreeEighthsIntegrator<S>
+where S: Scalar {
+    rule: NewtonCotesIntegrator<S>,
+}
+
+impl<S> SimpsonThreeEighthsIntegrator<S>
+where S: Scalar
+{
+    pub fn new() -> Self {
+        SimpsonThreeEighthsIntegrator { rule: NewtonCotesIntegrator::closed(3).unwrap() }
+    }
+}
+
+impl<S> Default for SimpsonThreeEighthsIntegrator<S>
+where S: Scalar
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Integrator<S> for SimpsonThreeEighthsIntegrator<S>
+where S: Scalar
+{
+    // The number of steps is rounded up to a multiple of 3
+    fn integrate_func<F>(&self, f: F, a: S, b: S, steps: u64) -> Result<S, IntegrationError>
+    where F: FnMut(S) -> S
+    {
+        self.rule.integrate_func(f, a, b, steps)
+    }
+
+    fn integrate_func_time_series<F>(&self, f: F, times: &[S]) -> Result<S, IntegrationError>
+    where
+        S: Real,
+        F: FnMut(S) -> S,
+    {
+        self.rule.integrate_func_time_series(f, times)
+    }
+
+    fn integrate_data(&self, vals: &[S], dx: S) -> Result<S, IntegrationError> {
+        self.rule.integrate_data(vals, dx)
+    }
+
+    fn integrate_data_time_series(&self, times: &[S], vals: &[S]) -> Result<S, IntegrationError>
+    where S: Real
+    {
+        self.rule.integrate_data_time_series(times, vals)
+    }
+}
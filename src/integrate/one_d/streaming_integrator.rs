@@ -0,0 +1,132 @@
+
+pub use super::integrator::{IntegrationError, Real, Scalar};
+
+use super::trapezoid_integrator::interval_area;
+
+use std::cmp;
+use std::collections::VecDeque;
+
+// Integrates a time series one sample at a time using the trapezoid rule.
+// Optionally, only the most recent stretch of time (the window) is integrated.
+pub struct StreamingTrapezoidIntegrator<S>
+where S: Real {
+    window: Option<S>,
+    // The samples that still touch the window, oldest first
+    samples: VecDeque<(S, S)>,
+    // The sum of the areas between consecutive retained samples
+    total: S,
+    // The number of samples dropped from the window since the total was last
+    // summed afresh
+    dropped: usize,
+    // The number of samples accepted since the last reset
+    count: usize,
+}
+
+impl<S> Default for StreamingTrapezoidIntegrator<S>
+where S: Real
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> StreamingTrapezoidIntegrator<S>
+where S: Real
+{
+    // Integrates over every sample given
+    pub fn new() -> Self {
+        StreamingTrapezoidIntegrator {
+            window: None,
+            samples: VecDeque::new(),
+            total: S::zero(),
+            dropped: 0,
+            count: 0,
+        }
+    }
+
+    // Only integrates over the last `width` units of time, which must be
+    // positive. The value at the start of the window is linearly interpolated
+    // between samples.
+    pub fn windowed(width: S) -> Result<Self, &'static str> {
+        if width <= S::zero() || width.magnitude().is_nan() {
+            return Err("The window of a streaming integrator must have a positive width.");
+        }
+        let mut digest = Self::new();
+        digest.window = Some(width);
+        Ok(digest)
+    }
+
+    // Adds a sample and returns the updated integral. Samples must arrive with
+    // strictly increasing times; any other sample is rejected and the state is
+    // left untouched.
+    pub fn push(&mut self, t: S, value: S) -> Result<S, IntegrationError> {
+        if let Some(&(last_t, last_value)) = self.samples.back() {
+            if t.partial_cmp(&last_t) != Some(cmp::Ordering::Greater) {
+                return Err(IntegrationError::NonMonotonicTimes { index: self.count });
+            }
+            self.total += interval_area(last_t, last_value, t, value);
+        }
+        self.samples.push_back((t, value));
+        self.count += 1;
+        if let Some(width) = self.window {
+            // Drop samples once the next one is also outside of the window
+            let start = t - width;
+            while self.samples.len() > 1 && self.samples[1].0 <= start {
+                let (t0, v0) = self.samples.pop_front().unwrap();
+                let (t1, v1) = self.samples[0];
+                self.total -= interval_area(t0, v0, t1, v1);
+                self.dropped += 1;
+            }
+            // Adding and taking away areas leaves rounding errors in the total
+            // that would build up without bound over a long stream. Summing it
+            // afresh once the window has turned over bounds them by those of
+            // the samples in it, at a cost of O(1) per sample on average.
+            if self.dropped >= self.samples.len() {
+                self.total = S::zero();
+                for (&(t0, v0), &(t1, v1)) in self.samples.iter().zip(self.samples.iter().skip(1)) {
+                    self.total += interval_area(t0, v0, t1, v1);
+                }
+                self.dropped = 0;
+            }
+        }
+        Ok(self.integral())
+    }
+
+    // The integral over every sample in the window (or every sample if there is no window)
+    pub fn integral(&self) -> S {
+        let window_start = match (self.window, self.samples.back()) {
+            (Some(width), Some(&(last_t, _))) => last_t - width,
+            _ => return self.total,
+        };
+        match (self.samples.front(), self.samples.get(1)) {
+            (Some(&(t0, v0)), Some(&(t1, v1))) if t0 < window_start => {
+                // Only part of the oldest interval lies inside the window
+                let v_start = v0 + (v1 - v0) * (window_start - t0) / (t1 - t0);
+                self.total - interval_area(t0, v0, t1, v1) + interval_area(window_start, v_start, t1, v1)
+            }
+            _ => self.total,
+        }
+    }
+
+    // The time and value of the most recent sample
+    pub fn last_sample(&self) -> Option<(S, S)> {
+        self.samples.back().copied()
+    }
+
+    // The number of samples accepted since the last reset
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn window(&self) -> Option<S> {
+        self.window
+    }
+
+    // Forgets every sample, keeping the window
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.total = S::zero();
+        self.dropped = 0;
+        self.count = 0;
+    }
+}
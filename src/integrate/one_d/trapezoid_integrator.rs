@@ -1,18 +1,43 @@
 
 pub use std::marker::PhantomData;
 
-pub use super::integrator::{Scalar, Integrator};
+pub use super::integrator::{
+    check_time_series, check_times, CumulativeIntegrator, IntegrationError, Integrator, Real, Scalar,
+};
+
+use super::integrator::accumulate;
 
 pub struct TrapezoidIntegrator<S> {
     _s: PhantomData<S>,
 }
 
 impl<S> TrapezoidIntegrator<S>
-where S: Scalar 
+where S: Scalar
 {
     pub fn new() -> Self {
         TrapezoidIntegrator { _s: PhantomData }
     }
+
+    // Given the estimate over [a, b] with the given number of sub-intervals,
+    // returns the estimate with twice as many. Only the new midpoints are evaluated.
+    pub fn refine<F>(&self, mut f: F, a: S, b: S, coarse: S, steps: u64) -> S
+    where F: FnMut(S) -> S
+    {
+        let dx: S = (b - a) / S::from(steps as f64);
+        let mut digest = S::zero();
+        for i in 0..steps {
+            digest += f(a + dx * (S::from(i as f64) + S::from(0.5)));
+        }
+        (coarse + digest * dx) * S::from(0.5)
+    }
+}
+
+impl<S> Default for TrapezoidIntegrator<S>
+where S: Scalar
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<S> Integrator<S> for TrapezoidIntegrator<S>
@@ -20,75 +45,88 @@ where S: Scalar
 {
     // This uses a slimmed-down version of the trapezoid rule.
     // If we avoid double counting, it suffices to only divide the first and last element by two
-    fn integrate_func(f: fn(S) -> S, a: S, b: S, steps: u64) -> S {
+    fn integrate_func<F>(&self, mut f: F, a: S, b: S, steps: u64) -> Result<S, IntegrationError>
+    where F: FnMut(S) -> S
+    {
         if steps == 0 {
-            S::zero()
-        } else {
-            let mut digest: S = f(a) * S::from(0.5);
-            let dx: S = (b-a)/S::from(steps as f64); // NOTE: N is the number of sub-intervals here
-            for i in 1..(steps)
-            {
-                digest += f(a + dx*S::from(i as f64));
-            }
-            digest += f(b) * S::from(0.5);
-            digest * dx
+            return Err(IntegrationError::InvalidSampleCount { count: 0 });
+        }
+        let mut digest: S = f(a) * S::from(0.5);
+        let dx: S = (b - a) / S::from(steps as f64); // NOTE: N is the number of sub-intervals here
+        for i in 1..steps {
+            digest += f(a + dx * S::from(i as f64));
+        }
+        digest += f(b) * S::from(0.5);
+        Ok(digest * dx)
+    }
+
+    fn integrate_func_time_series<F>(&self, mut f: F, times: &[S]) -> Result<S, IntegrationError>
+    where
+        S: Real,
+        F: FnMut(S) -> S,
+    {
+        check_times(times)?;
+        let mut digest = S::zero();
+        let mut past_x: S = times[0];
+        let mut past_val: S = f(past_x);
+        for &x in &times[1..] {
+            let curr_val = f(x);
+            digest += (curr_val + past_val) * (x - past_x);
+            past_val = curr_val;
+            past_x = x;
         }
+        Ok(digest * S::from(0.5))
     }
-    
-    fn integrate_func_time_series(f: fn(S) -> S, times: Vec<S>) -> S {
-        if times.len() == 0 {
-            S::zero()
-        } else {
-            let mut digest = S::zero();
-            let length = times.len();
-            let mut past_x: S = times[0];
-            let mut past_val: S = f(past_x);
-            let mut curr_val: S;
-            for i in 1..length
-            {
-                curr_val = f(times[i]);
-                digest += (curr_val + past_val)*(times[i] - past_x);
-                past_val = curr_val;
-                past_x = times[i];
-            }
-            digest * S::from(0.5)
+
+    fn integrate_data(&self, vals: &[S], dx: S) -> Result<S, IntegrationError> {
+        if vals.is_empty() {
+            return Err(IntegrationError::EmptyInput);
+        }
+        if vals.len() == 1 {
+            return Ok(S::zero());
+        }
+        let mut digest: S = vals[0] * S::from(0.5);
+        for &v in &vals[1..vals.len() - 1] {
+            digest += v;
         }
+        digest += vals[vals.len() - 1] * S::from(0.5);
+        Ok(digest * dx)
     }
-    
-    fn integrate_data(vals: Vec<S>, dx: S) -> S {
-        if vals.len() == 0 {
-            S::zero()
-        } else {
-            let mut digest: S = vals[0] * S::from(0.5);
-            let length = vals.len() - 1;
-            for i in 1..length
-            {
-                digest += vals[i];
-            }
-            digest += *vals.last().unwrap() * S::from(0.5);
-            digest * dx
+
+    fn integrate_data_time_series(&self, times: &[S], vals: &[S]) -> Result<S, IntegrationError>
+    where S: Real
+    {
+        check_time_series(times, vals)?;
+        let mut digest = S::zero();
+        for (t, v) in times.windows(2).zip(vals.windows(2)) {
+            digest += (v[1] + v[0]) * (t[1] - t[0]);
         }
+        Ok(digest * S::from(0.5))
     }
-    
-    fn integrate_data_time_series(times: Vec<S>, vals: Vec<S>) -> S {
-        if times.len() == 0 || vals.len() == 0 {
-            S::zero()
-        } else {
-            let mut digest = S::zero();
-            // TODO: If the length of the vectors aren't equal, what should we do?
-            let length = if times.len() < vals.len() { times.len() } else { vals.len() };
-            let mut past_x: S = times[0];
-            let mut past_val: S = vals[0];
-            let mut curr_val: S;
-            for i in 1..length
-            {
-                curr_val = vals[i];
-                digest += (curr_val + past_val)*(times[i] - past_x);
-                past_val = curr_val;
-                past_x = times[i];
-            }
-            digest * S::from(0.5)
+}
+
+impl<S> CumulativeIntegrator<S> for TrapezoidIntegrator<S>
+where S: Scalar
+{
+    fn cumulative_data(&self, vals: &[S], dx: S, initial: Option<S>) -> Result<Vec<S>, IntegrationError> {
+        if vals.is_empty() {
+            return Err(IntegrationError::EmptyInput);
         }
+        let half = S::from(0.5);
+        Ok(accumulate(vals.windows(2).map(|v| (v[0] + v[1]) * half * dx), initial))
+    }
+
+    fn cumulative_data_time_series(&self, times: &[S], vals: &[S], initial: Option<S>) -> Result<Vec<S>, IntegrationError>
+    where S: Real
+    {
+        check_time_series(times, vals)?;
+        let intervals = times.windows(2).zip(vals.windows(2)).map(|(t, v)| interval_area(t[0], v[0], t[1], v[1]));
+        Ok(accumulate(intervals, initial))
     }
-    
+}
+
+// The area of the trapezoid between two samples
+pub(crate) fn interval_area<S>(t0: S, v0: S, t1: S, v1: S) -> S
+where S: Scalar {
+    (v0 + v1) * S::from(0.5) * (t1 - t0)
 }
@@ -0,0 +1 @@
+pub mod sde_solver;
@@ -0,0 +1,260 @@
+pub use crate::integrate::ode::ode_solver::OdeError;
+pub use crate::integrate::ode::ode_state::{OdeState, Real, Scalar};
+pub use crate::math::random::Rng;
+
+// How the stochastic integral in dX = a(t, X) dt + b(t, X) dW is defined.
+// Ito integrals evaluate b at the start of each increment, which is natural
+// for finance and for noise that is truly white; Stratonovich integrals
+// evaluate it at the middle, which obeys the ordinary chain rule and is the
+// limit of physical noise with a short correlation time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdeCalculus {
+    Ito,
+    Stratonovich,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdeMethod {
+    // Strong order 0.5. With Stratonovich calculus this is the Euler-Heun
+    // predictor-corrector, which converges to the Stratonovich solution, and
+    // is of strong order 1 when the noise commutes.
+    EulerMaruyama,
+    // Strong order 1, using db/dX found by central differences
+    Milstein,
+    // Platen's derivative free scheme of strong order 1, which replaces the
+    // derivative in Milstein's correction with a difference of b values (a
+    // central one with Stratonovich calculus)
+    StochasticRungeKutta,
+}
+
+// A sample path of an SDE. states[i] is the solution at times[i], and
+// brownian[i] is the Wiener process W that drove it, which starts at zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdeSolution<S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub times: Vec<S>,
+    pub states: Vec<Y>,
+    pub brownian: Vec<Y>,
+}
+
+impl<S, Y> SdeSolution<S, Y>
+where
+    S: Real,
+    Y: OdeState<S>,
+{
+    pub fn final_time(&self) -> S {
+        *self.times.last().unwrap()
+    }
+
+    pub fn final_state(&self) -> &Y {
+        self.states.last().unwrap()
+    }
+}
+
+// Integrates SDEs dX = a(t, X) dt + b(t, X) dW with diagonal noise: each
+// component of X is driven by its own independent Wiener process, scaled by
+// the same component of b. For Milstein and the stochastic Runge-Kutta
+// method, component i of b should depend only on component i of X (or the
+// noise should otherwise commute), as the Levy areas aren't simulated.
+//
+// The steps are fixed, and the Gaussian increments come from a seeded Rng,
+// so every path is reproducible.
+pub struct SdeSolver {
+    pub method: SdeMethod,
+    pub calculus: SdeCalculus,
+    pub seed: u64,
+}
+
+impl SdeSolver {
+    pub fn new(method: SdeMethod, calculus: SdeCalculus, seed: u64) -> Self {
+        SdeSolver { method, calculus, seed }
+    }
+
+    // Simulates one path from X(t0) = y0 to t1 in the given number of steps
+    pub fn solve<S, Y, A, B>(&self, drift: A, diffusion: B, t0: S, t1: S, y0: Y, steps: u64) -> Result<SdeSolution<S, Y>, OdeError>
+    where
+        S: Real,
+        Y: OdeState<S>,
+        A: FnMut(S, &Y) -> Y,
+        B: FnMut(S, &Y) -> Y,
+    {
+        self.solve_path(drift, diffusion, t0, t1, y0, steps, self.seed)
+    }
+
+    // Simulates independent paths. Path k is seeded from seed + k, so it
+    // doesn't depend on how many paths are asked for.
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve_paths<S, Y, A, B>(&self, mut drift: A, mut diffusion: B, t0: S, t1: S, y0: Y, steps: u64, paths: usize) -> Result<Vec<SdeSolution<S, Y>>, OdeError>
+    where
+        S: Real,
+        Y: OdeState<S>,
+        A: FnMut(S, &Y) -> Y,
+        B: FnMut(S, &Y) -> Y,
+    {
+        (0..paths)
+            .map(|k| self.solve_path(&mut drift, &mut diffusion, t0, t1, y0.clone(), steps, self.seed.wrapping_add(k as u64)))
+            .collect()
+    }
+
+    // Integrates from X(t0) = y0 to t1 with the given Wiener increments, one
+    // per step, each with the same shape as y0. Driving different methods
+    // or step sizes with the same Brownian path shows their strong error.
+    pub fn solve_with_increments<S, Y, A, B>(&self, mut drift: A, mut diffusion: B, t0: S, t1: S, y0: Y, increments: &[Y]) -> Result<SdeSolution<S, Y>, OdeError>
+    where
+        S: Real,
+        Y: OdeState<S>,
+        A: FnMut(S, &Y) -> Y,
+        B: FnMut(S, &Y) -> Y,
+    {
+        let steps = increments.len() as u64;
+        if steps == 0 {
+            return Err(OdeError::InvalidStepCount { count: steps });
+        }
+        let h = (t1 - t0) / S::from(steps as f64);
+        let mut w = y0.clone();
+        for i in 0..w.dimension() {
+            w.set_component(i, S::zero());
+        }
+        let mut solution = SdeSolution { times: vec![t0], states: vec![y0.clone()], brownian: vec![w.clone()] };
+        let mut t = t0;
+        let mut y = y0;
+        for (i, dw) in increments.iter().enumerate() {
+            y = self.step(&mut drift, &mut diffusion, t, &y, h, dw);
+            for k in 0..w.dimension() {
+                w.set_component(k, w.component(k) + dw.component(k));
+            }
+            // Compute each time from t0 so that rounding errors don't build up
+            t = if i + 1 == increments.len() { t1 } else { t0 + h * S::from((i + 1) as f64) };
+            solution.times.push(t);
+            solution.states.push(y.clone());
+            solution.brownian.push(w.clone());
+        }
+        Ok(solution)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn solve_path<S, Y, A, B>(&self, drift: A, diffusion: B, t0: S, t1: S, y0: Y, steps: u64, seed: u64) -> Result<SdeSolution<S, Y>, OdeError>
+    where
+        S: Real,
+        Y: OdeState<S>,
+        A: FnMut(S, &Y) -> Y,
+        B: FnMut(S, &Y) -> Y,
+    {
+        if steps == 0 {
+            return Err(OdeError::InvalidStepCount { count: steps });
+        }
+        let mut rng = Rng::new(seed);
+        let root_h = ((t1 - t0) / S::from(steps as f64)).abs().sqrt();
+        let increments: Vec<Y> = (0..steps)
+            .map(|_| {
+                let mut dw = y0.clone();
+                for i in 0..dw.dimension() {
+                    dw.set_component(i, root_h * S::from(rng.normal()));
+                }
+                dw
+            })
+            .collect();
+        self.solve_with_increments(drift, diffusion, t0, t1, y0, &increments)
+    }
+
+    // One step of size h from (t, y) with the Wiener increment dw
+    fn step<S, Y, A, B>(&self, drift: &mut A, diffusion: &mut B, t: S, y: &Y, h: S, dw: &Y) -> Y
+    where
+        S: Real,
+        Y: OdeState<S>,
+        A: FnMut(S, &Y) -> Y,
+        B: FnMut(S, &Y) -> Y,
+    {
+        let n = y.dimension();
+        let a = drift(t, y);
+        let b = diffusion(t, y);
+        let half = S::from(0.5);
+        let mut digest = y.clone();
+        match self.method {
+            SdeMethod::EulerMaruyama => {
+                for i in 0..n {
+                    digest.set_component(i, y.component(i) + a.component(i) * h + b.component(i) * dw.component(i));
+                }
+                if self.calculus == SdeCalculus::Stratonovich {
+                    // Average b over the step, using the Euler value as a predictor
+                    let b_predicted = diffusion(t + h, &digest);
+                    for i in 0..n {
+                        let b_mean = half * (b.component(i) + b_predicted.component(i));
+                        digest.set_component(i, y.component(i) + a.component(i) * h + b_mean * dw.component(i));
+                    }
+                }
+            }
+            SdeMethod::Milstein => {
+                let db = diffusion_derivative(diffusion, t, y);
+                for (i, &dbi) in db.iter().enumerate() {
+                    let (bi, dwi) = (b.component(i), dw.component(i));
+                    let correction = match self.calculus {
+                        SdeCalculus::Ito => dwi * dwi - h.abs(),
+                        SdeCalculus::Stratonovich => dwi * dwi,
+                    };
+                    digest.set_component(i, y.component(i) + a.component(i) * h + bi * dwi + half * bi * dbi * correction);
+                }
+            }
+            SdeMethod::StochasticRungeKutta => {
+                let root_h = h.abs().sqrt();
+                let mut support = y.clone();
+                match self.calculus {
+                    SdeCalculus::Ito => {
+                        for i in 0..n {
+                            support.set_component(i, y.component(i) + a.component(i) * h + b.component(i) * root_h);
+                        }
+                        let b_support = diffusion(t, &support);
+                        for i in 0..n {
+                            let dwi = dw.component(i);
+                            let difference = (b_support.component(i) - b.component(i)) / (S::from(2.0) * root_h);
+                            digest.set_component(i, y.component(i) + a.component(i) * h + b.component(i) * dwi + difference * (dwi * dwi - h.abs()));
+                        }
+                    }
+                    SdeCalculus::Stratonovich => {
+                        // The correction no longer has zero mean, so a one
+                        // sided difference would bias the drift
+                        let mut below = y.clone();
+                        for i in 0..n {
+                            support.set_component(i, y.component(i) + b.component(i) * root_h);
+                            below.set_component(i, y.component(i) - b.component(i) * root_h);
+                        }
+                        let (b_above, b_below) = (diffusion(t, &support), diffusion(t, &below));
+                        for i in 0..n {
+                            let dwi = dw.component(i);
+                            let difference = (b_above.component(i) - b_below.component(i)) / (S::from(4.0) * root_h);
+                            digest.set_component(i, y.component(i) + a.component(i) * h + b.component(i) * dwi + difference * dwi * dwi);
+                        }
+                    }
+                }
+            }
+        }
+        digest
+    }
+}
+
+// The derivative of each component of b by the same component of y, by
+// central differences
+fn diffusion_derivative<S, Y, B>(diffusion: &mut B, t: S, y: &Y) -> Vec<S>
+where
+    S: Real,
+    Y: OdeState<S>,
+    B: FnMut(S, &Y) -> Y,
+{
+    let cube_root_eps = S::from(S::epsilon().to_f64().cbrt());
+    let mut shifted = y.clone();
+    (0..y.dimension())
+        .map(|i| {
+            let yi = y.component(i);
+            let step = cube_root_eps * yi.abs().max(S::from(1.0));
+            shifted.set_component(i, yi + step);
+            let above = diffusion(t, &shifted).component(i);
+            shifted.set_component(i, yi - step);
+            let below = diffusion(t, &shifted).component(i);
+            shifted.set_component(i, yi);
+            (above - below) / (S::from(2.0) * step)
+        })
+        .collect()
+}
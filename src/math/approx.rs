@@ -0,0 +1,176 @@
+pub use crate::math::scalar::Scalar;
+
+// Equality up to rounding error. PartialEq on floating point types is
+// bitwise, which is rarely what a computed result should be held to. There
+// are three ways to compare here:
+//
+// - abs_diff_eq: the difference is at most epsilon. This is the right test
+//   near zero, where relative errors are meaningless.
+// - relative_eq: the difference is at most max_relative times the larger of
+//   the two, or at most epsilon, for when they are near zero.
+// - ulps_eq: the two are at most max_ulps representable numbers apart, or
+//   at most epsilon apart, for when they are near zero.
+//
+// Tolerances are f64s whatever the type, as magnitudes are. Compound types
+// are equal when all of their parts are, using the same tolerances.
+pub trait ApproxEq {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool;
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool;
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool;
+
+    // The tolerances used when none are given
+    fn default_epsilon() -> f64;
+    fn default_max_relative() -> f64;
+
+    fn default_max_ulps() -> u32 {
+        4
+    }
+
+    // Equality to within the default relative tolerance
+    fn approx_eq(&self, other: &Self) -> bool {
+        self.relative_eq(other, Self::default_epsilon(), Self::default_max_relative())
+    }
+}
+
+// Every Scalar is compared through the magnitude of its difference, so a
+// complex number is compared by its distance from the other, and a dual
+// number by its value alone, as PartialEq does. Nothing finite is close to
+// an infinity, however large the tolerance relative to it.
+impl<S> ApproxEq for S
+where S: Scalar
+{
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        // Equal infinities have no difference to measure
+        *self == *other || (*self - *other).magnitude() <= epsilon
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        if self.abs_diff_eq(other, epsilon) {
+            return true;
+        }
+        let difference = (*self - *other).magnitude();
+        let largest = self.magnitude().max(other.magnitude());
+        difference.is_finite() && difference <= largest * max_relative
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        if self.abs_diff_eq(other, epsilon) {
+            return true;
+        }
+        let difference = (*self - *other).magnitude();
+        let largest = self.magnitude().max(other.magnitude());
+        difference.is_finite() && difference <= ulp(largest, S::precision()) * max_ulps as f64
+    }
+
+    fn default_epsilon() -> f64 {
+        S::precision()
+    }
+
+    fn default_max_relative() -> f64 {
+        S::precision()
+    }
+}
+
+// The spacing of the numbers with the given precision around a magnitude,
+// which is exact in the binade of the magnitude. Two numbers either side of
+// a power of two are counted in the larger spacing, so are never found
+// further apart than they are.
+fn ulp(magnitude: f64, precision: f64) -> f64 {
+    // Clearing the mantissa leaves the power of two at or below it
+    let binade = f64::from_bits(magnitude.to_bits() & 0xfff0_0000_0000_0000);
+    (binade * precision).max(f64::from_bits(1))
+}
+
+// Assertions for tests, which print both sides and the tolerances on
+// failure. Tolerances that aren't given are the defaults of the type.
+#[macro_export]
+macro_rules! assert_abs_diff_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__assert_approx!(abs_diff_eq, $left, $right, epsilon = default)
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr $(,)?) => {
+        $crate::__assert_approx!(abs_diff_eq, $left, $right, epsilon = $epsilon)
+    };
+}
+
+#[macro_export]
+macro_rules! assert_relative_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__assert_approx!(relative_eq, $left, $right,
+            epsilon = default, max_relative = default)
+    };
+    ($left:expr, $right:expr, max_relative = $max_relative:expr $(,)?) => {
+        $crate::__assert_approx!(relative_eq, $left, $right,
+            epsilon = default, max_relative = $max_relative)
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr, max_relative = $max_relative:expr $(,)?) => {
+        $crate::__assert_approx!(relative_eq, $left, $right,
+            epsilon = $epsilon, max_relative = $max_relative)
+    };
+}
+
+#[macro_export]
+macro_rules! assert_ulps_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__assert_approx!(ulps_eq, $left, $right,
+            epsilon = default, max_ulps = default)
+    };
+    ($left:expr, $right:expr, max_ulps = $max_ulps:expr $(,)?) => {
+        $crate::__assert_approx!(ulps_eq, $left, $right,
+            epsilon = default, max_ulps = $max_ulps)
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr, max_ulps = $max_ulps:expr $(,)?) => {
+        $crate::__assert_approx!(ulps_eq, $left, $right,
+            epsilon = $epsilon, max_ulps = $max_ulps)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_approx {
+    ($method:ident, $left:expr, $right:expr, $($name:ident = $tolerance:tt),+) => {{
+        let (left, right) = (&$left, &$right);
+        $(let $name = $crate::__tolerance!(left, $name, $tolerance);)+
+        if !$crate::math::approx::ApproxEq::$method(left, right, $($name),+) {
+            panic!(
+                concat!("assertion `left ", stringify!($method), " right` failed\n",
+                    "  left: {}\n right: {}", $("\n  ", stringify!($name), ": {}"),+),
+                left, right, $($name),+
+            );
+        }
+    }};
+}
+
+// The defaults depend on the type of the arguments, which can't be named in
+// a macro, so are found through functions of them
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tolerance {
+    ($left:ident, epsilon, default) => {
+        $crate::math::approx::__default_epsilon($left)
+    };
+    ($left:ident, max_relative, default) => {
+        $crate::math::approx::__default_max_relative($left)
+    };
+    ($left:ident, max_ulps, default) => {
+        $crate::math::approx::__default_max_ulps($left)
+    };
+    ($left:ident, $name:ident, $tolerance:expr) => {
+        $tolerance
+    };
+}
+
+#[doc(hidden)]
+pub fn __default_epsilon<T: ApproxEq>(_: &T) -> f64 {
+    T::default_epsilon()
+}
+
+#[doc(hidden)]
+pub fn __default_max_relative<T: ApproxEq>(_: &T) -> f64 {
+    T::default_max_relative()
+}
+
+#[doc(hidden)]
+pub fn __default_max_ulps<T: ApproxEq>(_: &T) -> u32 {
+    T::default_max_ulps()
+}
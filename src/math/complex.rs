@@ -0,0 +1,283 @@
+pub use crate::math::scalar::{Float, Real, Scalar};
+
+use std::fmt;
+use std::ops;
+
+// A complex number re + im i over a real Scalar. Complex numbers are Scalars
+// but not Reals, as they can't be ordered: vectors and dense matrices
+// (including their LU decompositions) work over them, and so do the ODE
+// solvers, through their states.
+//
+// The functions here take care not to overflow or underflow in
+// intermediate results when the answer itself is representable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex<S>
+where S: Real {
+    pub re: S,
+    pub im: S,
+}
+
+impl<S> Complex<S>
+where S: Real
+{
+    pub fn new(re: S, im: S) -> Self {
+        Complex { re, im }
+    }
+
+    // The imaginary unit
+    pub fn i() -> Self {
+        Complex { re: S::zero(), im: S::from(1.0) }
+    }
+
+    // |z|, found without squaring the parts
+    pub fn modulus(&self) -> S {
+        let (a, b) = (self.re.abs(), self.im.abs());
+        let (large, small) = if a > b { (a, b) } else { (b, a) };
+        if large == S::zero() {
+            return S::zero();
+        }
+        let ratio = small / large;
+        large * (S::from(1.0) + ratio * ratio).sqrt()
+    }
+
+    // |z|^2, which is exact as far as rounding allows but may overflow
+    pub fn norm_sqr(&self) -> S {
+        self.re * self.re + self.im * self.im
+    }
+
+    pub fn powi(self, n: i32) -> Self {
+        let mut digest = Complex::new(S::from(1.0), S::zero());
+        let mut base = if n < 0 { self.inv() } else { self };
+        let mut k = n.unsigned_abs();
+        while k > 0 {
+            if k % 2 == 1 {
+                digest *= base;
+            }
+            base *= base;
+            k /= 2;
+        }
+        digest
+    }
+}
+
+// The functions that need more of the real line than arithmetic
+impl<S> Complex<S>
+where S: Float
+{
+    // r e^(i theta)
+    pub fn from_polar(r: S, theta: S) -> Self {
+        Complex { re: r * theta.cos(), im: r * theta.sin() }
+    }
+
+    // The angle of z from the positive real axis, in (-pi, pi]
+    pub fn argument(&self) -> S {
+        self.im.atan2(self.re)
+    }
+
+    pub fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    // The principal logarithm, whose imaginary part is the argument
+    pub fn ln(self) -> Self {
+        Complex { re: self.modulus().ln(), im: self.argument() }
+    }
+
+    // The principal value of z^n, which is zero when z is
+    pub fn powf(self, n: Self) -> Self {
+        if self == Scalar::zero() {
+            return Scalar::zero();
+        }
+        (n * self.ln()).exp()
+    }
+}
+
+impl<S> fmt::Display for Complex<S>
+where S: Real
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.im < S::zero() {
+            write!(f, "{} - {}i", self.re, self.im.abs())
+        } else {
+            write!(f, "{} + {}i", self.re, self.im)
+        }
+    }
+}
+
+impl<S> ops::Neg for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        Complex { re: S::zero() - self.re, im: S::zero() - self.im }
+    }
+}
+
+impl<S> ops::Add for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Complex { re: self.re + rhs.re, im: self.im + rhs.im }
+    }
+}
+
+impl<S> ops::Sub for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Complex { re: self.re - rhs.re, im: self.im - rhs.im }
+    }
+}
+
+impl<S> ops::Mul for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Complex {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+// Smith's algorithm, which scales by the larger part of the divisor
+impl<S> ops::Div for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        if rhs.re.abs() >= rhs.im.abs() {
+            let ratio = rhs.im / rhs.re;
+            let denominator = rhs.re + rhs.im * ratio;
+            Complex {
+                re: (self.re + self.im * ratio) / denominator,
+                im: (self.im - self.re * ratio) / denominator,
+            }
+        } else {
+            let ratio = rhs.re / rhs.im;
+            let denominator = rhs.re * ratio + rhs.im;
+            Complex {
+                re: (self.re * ratio + self.im) / denominator,
+                im: (self.im * ratio - self.re) / denominator,
+            }
+        }
+    }
+}
+
+// Arithmetic with real numbers
+impl<S> ops::Add<S> for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn add(self, rhs: S) -> Self {
+        Complex { re: self.re + rhs, im: self.im }
+    }
+}
+
+impl<S> ops::Sub<S> for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn sub(self, rhs: S) -> Self {
+        Complex { re: self.re - rhs, im: self.im }
+    }
+}
+
+impl<S> ops::Mul<S> for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn mul(self, rhs: S) -> Self {
+        Complex { re: self.re * rhs, im: self.im * rhs }
+    }
+}
+
+impl<S> ops::Div<S> for Complex<S>
+where S: Real
+{
+    type Output = Self;
+    fn div(self, rhs: S) -> Self {
+        Complex { re: self.re / rhs, im: self.im / rhs }
+    }
+}
+
+impl<S> ops::AddAssign for Complex<S>
+where S: Real
+{
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<S> ops::SubAssign for Complex<S>
+where S: Real
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<S> ops::MulAssign for Complex<S>
+where S: Real
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<S> ops::DivAssign for Complex<S>
+where S: Real
+{
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<S> Scalar for Complex<S>
+where S: Real
+{
+    fn from(a: f64) -> Self {
+        Complex { re: S::from(a), im: S::zero() }
+    }
+
+    // The principal square root, whose real part is never negative. The
+    // branch cut runs along the negative real axis, and the roots of
+    // negative reals are positive multiples of i.
+    fn sqrt(self) -> Self {
+        if self.re == S::zero() && self.im == S::zero() {
+            return Self::zero();
+        }
+        // Halving before adding keeps the sum in range for the largest parts
+        let half = S::from(0.5);
+        let t = (self.modulus() * half + self.re.abs() * half).sqrt();
+        let other = self.im.abs() / (S::from(2.0) * t);
+        if self.re >= S::zero() {
+            Complex { re: t, im: if self.im < S::zero() { S::zero() - other } else { other } }
+        } else {
+            Complex { re: other, im: if self.im < S::zero() { S::zero() - t } else { t } }
+        }
+    }
+
+    fn inv(self) -> Self {
+        Complex::new(S::from(1.0), S::zero()) / self
+    }
+
+    fn zero() -> Self {
+        Complex { re: S::zero(), im: S::zero() }
+    }
+
+    fn magnitude(self) -> f64 {
+        self.modulus().to_f64()
+    }
+
+    fn precision() -> f64 {
+        S::precision()
+    }
+
+    fn conj(self) -> Self {
+        Complex { re: self.re, im: S::zero() - self.im }
+    }
+}
@@ -0,0 +1,143 @@
+
+pub use crate::math::complex::Complex;
+pub use crate::math::scalar::{Real, Scalar};
+pub use crate::spatial::vector_3d::Vector3D;
+
+// Anything made of a number of real components that can be read and written
+// one at a time, such as the state of an ODE or the point a derivative is
+// taken at. Scalars, complex numbers, Vector3D, and arrays and Vecs of any of
+// these (e.g. [position, velocity] as a [Vector3D<S>; 2], or a wave function
+// as a Vec<Complex<S>>) all have components.
+pub trait Components<S>: Clone
+where S: Real {
+    // The number of scalar components
+    fn dimension(&self) -> usize;
+    fn component(&self, i: usize) -> S;
+    fn set_component(&mut self, i: usize, value: S);
+}
+
+impl<S> Components<S> for S
+where S: Real
+{
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn component(&self, _: usize) -> S {
+        *self
+    }
+
+    fn set_component(&mut self, _: usize, value: S) {
+        *self = value;
+    }
+}
+
+impl<S> Components<S> for Vector3D<S>
+where S: Real
+{
+    fn dimension(&self) -> usize {
+        3
+    }
+
+    fn component(&self, i: usize) -> S {
+        match i {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+
+    fn set_component(&mut self, i: usize, value: S) {
+        match i {
+            0 => self.x = value,
+            1 => self.y = value,
+            _ => self.z = value,
+        }
+    }
+}
+
+// The real and imaginary parts are separate components
+impl<S> Components<S> for Complex<S>
+where S: Real
+{
+    fn dimension(&self) -> usize {
+        2
+    }
+
+    fn component(&self, i: usize) -> S {
+        match i {
+            0 => self.re,
+            _ => self.im,
+        }
+    }
+
+    fn set_component(&mut self, i: usize, value: S) {
+        match i {
+            0 => self.re = value,
+            _ => self.im = value,
+        }
+    }
+}
+
+// Every element must have the same dimension
+impl<S, Y> Components<S> for Vec<Y>
+where
+    S: Real,
+    Y: Components<S>,
+{
+    fn dimension(&self) -> usize {
+        self.first().map_or(0, |y| y.dimension() * self.len())
+    }
+
+    fn component(&self, i: usize) -> S {
+        let d = self[0].dimension();
+        self[i / d].component(i % d)
+    }
+
+    fn set_component(&mut self, i: usize, value: S) {
+        let d = self[0].dimension();
+        self[i / d].set_component(i % d, value);
+    }
+}
+
+impl<S, Y, const N: usize> Components<S> for [Y; N]
+where
+    S: Real,
+    Y: Components<S>,
+{
+    fn dimension(&self) -> usize {
+        self.first().map_or(0, |y| y.dimension() * N)
+    }
+
+    fn component(&self, i: usize) -> S {
+        let d = self[0].dimension();
+        self[i / d].component(i % d)
+    }
+
+    fn set_component(&mut self, i: usize, value: S) {
+        let d = self[0].dimension();
+        self[i / d].set_component(i % d, value);
+    }
+}
+
+// The components of y, in order
+pub(crate) fn components<S, Y>(y: &Y) -> Vec<S>
+where
+    S: Real,
+    Y: Components<S>,
+{
+    (0..y.dimension()).map(|i| y.component(i)).collect()
+}
+
+// A value shaped like template with the given components
+pub(crate) fn from_components<S, Y>(template: &Y, values: &[S]) -> Y
+where
+    S: Real,
+    Y: Components<S>,
+{
+    let mut digest = template.clone();
+    for (i, &v) in values.iter().enumerate() {
+        digest.set_component(i, v);
+    }
+    digest
+}
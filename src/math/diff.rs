@@ -0,0 +1,503 @@
+pub use crate::math::components::{Components, Real, Scalar};
+pub use crate::math::lin_alg::dense_matrix::DenseMatrix;
+
+use crate::math::components::{components, from_components};
+
+use std::error;
+use std::fmt;
+
+// The ways in which a derivative request can be malformed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffError {
+    // Derivatives of order zero aren't derivatives
+    InvalidOrder { order: usize },
+    // No stencil has this order of accuracy (central differences only come
+    // in even orders)
+    InvalidAccuracy { accuracy: usize },
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiffError::InvalidOrder { order } => write!(f, "A derivative of order {} can't be taken.", order),
+            DiffError::InvalidAccuracy { accuracy } => write!(
+                f,
+                "There is no finite difference with an accuracy of order {} for this scheme.",
+                accuracy
+            ),
+        }
+    }
+}
+
+impl error::Error for DiffError {}
+
+// Which side of x the points of a finite difference lie on. One sided
+// differences are for functions that can't be evaluated across a boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifferenceScheme {
+    Forward,
+    Backward,
+    Central,
+}
+
+// The offsets (in steps from x) and weights of the finite difference for the
+// given derivative, whose error falls as h^accuracy
+pub fn stencil(scheme: DifferenceScheme, order: usize, accuracy: usize) -> Result<(Vec<f64>, Vec<f64>), DiffError> {
+    if order == 0 {
+        return Err(DiffError::InvalidOrder { order });
+    }
+    if accuracy == 0 || (scheme == DifferenceScheme::Central && accuracy % 2 == 1) {
+        return Err(DiffError::InvalidAccuracy { accuracy });
+    }
+    let offsets: Vec<f64> = match scheme {
+        DifferenceScheme::Forward => (0..order + accuracy).map(|k| k as f64).collect(),
+        DifferenceScheme::Backward => (0..order + accuracy).map(|k| -(k as f64)).collect(),
+        DifferenceScheme::Central => {
+            let half = (order.div_ceil(2) - 1 + accuracy / 2) as i64;
+            (-half..=half).map(|k| k as f64).collect()
+        }
+    };
+    let weights = fornberg(&offsets, order);
+    Ok((offsets, weights))
+}
+
+// The weights for the derivative of the given order at zero from values at
+// the offsets, by Fornberg's recursion, which is stable for any spacing
+fn fornberg(offsets: &[f64], order: usize) -> Vec<f64> {
+    let n = offsets.len();
+    let mut c = vec![vec![0.0; order + 1]; n];
+    c[0][0] = 1.0;
+    let mut c1 = 1.0;
+    let mut c4 = offsets[0];
+    for i in 1..n {
+        let highest = i.min(order);
+        let mut c2 = 1.0;
+        let c5 = c4;
+        c4 = offsets[i];
+        for j in 0..i {
+            let c3 = offsets[i] - offsets[j];
+            c2 *= c3;
+            if j == i - 1 {
+                for k in (1..=highest).rev() {
+                    c[i][k] = c1 * (k as f64 * c[i - 1][k - 1] - c5 * c[i - 1][k]) / c2;
+                }
+                c[i][0] = -c1 * c5 * c[i - 1][0] / c2;
+            }
+            for k in (1..=highest).rev() {
+                c[j][k] = (c4 * c[j][k] - k as f64 * c[j][k - 1]) / c3;
+            }
+            c[j][0] = c4 * c[j][0] / c3;
+        }
+        c1 = c2;
+    }
+    c.iter().map(|row| row[order]).collect()
+}
+
+// A derivative found adaptively, with an estimate of its error
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivativeResult<S, Z>
+where
+    S: Real,
+    Z: Components<S>,
+{
+    pub value: Z,
+    // An estimate of the largest absolute error in any component of value
+    pub abs_error: S,
+    // The number of times the function was evaluated
+    pub evaluations: usize,
+    // Whether the requested tolerance was met
+    pub converged: bool,
+}
+
+// Differentiates functions numerically. Only derivatives of functions of one
+// variable are needed; the rest are built from them, so a gradient, Jacobian
+// or Hessian can be taken of functions of scalars, Vector3D, arrays and Vecs
+// alike.
+pub trait Differentiator<S>
+where S: Real {
+    // The derivative of the given order of f at x, where f's value may have
+    // any number of components
+    fn derivative_of<Z, F>(&self, f: F, x: S, order: usize) -> Result<Z, DiffError>
+    where
+        Z: Components<S>,
+        F: FnMut(S) -> Z;
+
+    fn derivative<F>(&self, f: F, x: S) -> Result<S, DiffError>
+    where F: FnMut(S) -> S
+    {
+        self.derivative_of(f, x, 1)
+    }
+
+    fn gradient<Y, F>(&self, mut f: F, x: &Y) -> Result<Y, DiffError>
+    where
+        Y: Components<S>,
+        F: FnMut(&Y) -> S,
+    {
+        let mut digest = x.clone();
+        for i in 0..x.dimension() {
+            let mut point = x.clone();
+            let partial = self.derivative_of(
+                |s| {
+                    point.set_component(i, s);
+                    f(&point)
+                },
+                x.component(i),
+                1,
+            )?;
+            digest.set_component(i, partial);
+        }
+        Ok(digest)
+    }
+
+    // The matrix of partial derivatives df_i / dx_j
+    fn jacobian<Y, Z, F>(&self, mut f: F, x: &Y) -> Result<DenseMatrix<S>, DiffError>
+    where
+        Y: Components<S>,
+        Z: Components<S>,
+        F: FnMut(&Y) -> Z,
+    {
+        let n = x.dimension();
+        let mut columns = Vec::with_capacity(n);
+        for j in 0..n {
+            let mut point = x.clone();
+            let column: Z = self.derivative_of(
+                |s| {
+                    point.set_component(j, s);
+                    f(&point)
+                },
+                x.component(j),
+                1,
+            )?;
+            columns.push(components(&column));
+        }
+        let m = columns.first().map_or(0, |c| c.len());
+        let mut digest = DenseMatrix::zeros(m, n);
+        for (j, column) in columns.iter().enumerate() {
+            for (i, &value) in column.iter().enumerate() {
+                digest[(i, j)] = value;
+            }
+        }
+        Ok(digest)
+    }
+
+    // The symmetric matrix of second partial derivatives of f. The mixed
+    // partials are derivatives of derivatives, which suits adaptive methods.
+    fn hessian<Y, F>(&self, mut f: F, x: &Y) -> Result<DenseMatrix<S>, DiffError>
+    where
+        Y: Components<S>,
+        F: FnMut(&Y) -> S,
+    {
+        let n = x.dimension();
+        let mut digest = DenseMatrix::zeros(n, n);
+        for i in 0..n {
+            let mut point = x.clone();
+            digest[(i, i)] = self.derivative_of(
+                |s| {
+                    point.set_component(i, s);
+                    f(&point)
+                },
+                x.component(i),
+                2,
+            )?;
+            for j in i + 1..n {
+                let mut point = x.clone();
+                let mut failure = None;
+                let mixed = self.derivative_of(
+                    |s| {
+                        point.set_component(i, s);
+                        let mut inner = point.clone();
+                        let partial = self.derivative_of(
+                            |t| {
+                                inner.set_component(j, t);
+                                f(&inner)
+                            },
+                            x.component(j),
+                            1,
+                        );
+                        partial.unwrap_or_else(|e| {
+                            failure = Some(e);
+                            S::zero()
+                        })
+                    },
+                    x.component(i),
+                    1,
+                )?;
+                if let Some(e) = failure {
+                    return Err(e);
+                }
+                digest[(i, j)] = mixed;
+                digest[(j, i)] = mixed;
+            }
+        }
+        Ok(digest)
+    }
+}
+
+// Finite differences with a fixed step. Unless one is given, the step is
+// chosen to balance the truncation error against rounding: for a derivative
+// of order n with accuracy p, h = eps^(1 / (n + p)) max(|x|, 1).
+pub struct FiniteDifference<S>
+where S: Real {
+    pub scheme: DifferenceScheme,
+    // The order of the truncation error
+    pub accuracy: usize,
+    pub step: Option<S>,
+}
+
+impl<S> Default for FiniteDifference<S>
+where S: Real
+{
+    fn default() -> Self {
+        Self::new(DifferenceScheme::Central, 2)
+    }
+}
+
+impl<S> FiniteDifference<S>
+where S: Real
+{
+    pub fn new(scheme: DifferenceScheme, accuracy: usize) -> Self {
+        FiniteDifference { scheme, accuracy, step: None }
+    }
+
+    fn step_at(&self, x: S, order: usize) -> S {
+        let h = self.step.unwrap_or_else(|| {
+            let power = 1.0 / (order + self.accuracy) as f64;
+            S::from(S::epsilon().to_f64().powf(power)) * x.abs().max(S::from(1.0))
+        });
+        // A step that is exactly representable relative to x
+        (x + h) - x
+    }
+}
+
+impl<S> Differentiator<S> for FiniteDifference<S>
+where S: Real
+{
+    fn derivative_of<Z, F>(&self, mut f: F, x: S, order: usize) -> Result<Z, DiffError>
+    where
+        Z: Components<S>,
+        F: FnMut(S) -> Z,
+    {
+        let (offsets, weights) = stencil(self.scheme, order, self.accuracy)?;
+        let h = self.step_at(x, order);
+        Ok(apply(&mut f, x, h, order, &offsets, &weights))
+    }
+
+    // The mixed partials come from the product of two first derivative
+    // stencils, with a step suited to a second derivative
+    fn hessian<Y, F>(&self, mut f: F, x: &Y) -> Result<DenseMatrix<S>, DiffError>
+    where
+        Y: Components<S>,
+        F: FnMut(&Y) -> S,
+    {
+        let (offsets, weights) = stencil(self.scheme, 1, self.accuracy)?;
+        let (second_offsets, second_weights) = stencil(self.scheme, 2, self.accuracy)?;
+        let n = x.dimension();
+        let steps: Vec<S> = (0..n).map(|i| self.step_at(x.component(i), 2)).collect();
+        let mut digest = DenseMatrix::zeros(n, n);
+        let mut point = x.clone();
+        for i in 0..n {
+            let xi = x.component(i);
+            digest[(i, i)] = apply(
+                &mut |s| {
+                    point.set_component(i, s);
+                    f(&point)
+                },
+                xi,
+                steps[i],
+                2,
+                &second_offsets,
+                &second_weights,
+            );
+            point.set_component(i, xi);
+            for j in i + 1..n {
+                let xj = x.component(j);
+                let mut mixed = S::zero();
+                for (&a, &wa) in offsets.iter().zip(weights.iter()) {
+                    for (&b, &wb) in offsets.iter().zip(weights.iter()) {
+                        if wa == 0.0 || wb == 0.0 {
+                            continue;
+                        }
+                        point.set_component(i, xi + S::from(a) * steps[i]);
+                        point.set_component(j, xj + S::from(b) * steps[j]);
+                        mixed += S::from(wa * wb) * f(&point);
+                    }
+                }
+                point.set_component(i, xi);
+                point.set_component(j, xj);
+                mixed /= steps[i] * steps[j];
+                digest[(i, j)] = mixed;
+                digest[(j, i)] = mixed;
+            }
+        }
+        Ok(digest)
+    }
+}
+
+// Ridders' method: central (or one sided) differences with a shrinking step,
+// extrapolated to zero step size in a Neville tableau. The step stops
+// shrinking once the tolerance is met or rounding error starts to dominate,
+// so the result is usually many digits more accurate than any fixed step
+// could give.
+pub struct RichardsonDifferentiator<S>
+where S: Real {
+    pub scheme: DifferenceScheme,
+    // The first step, relative to max(|x|, 1). It should be large enough for
+    // the function to change appreciably.
+    pub initial_step: S,
+    // The step is divided by this at each level
+    pub factor: S,
+    pub max_levels: usize,
+    pub abs_tol: S,
+    pub rel_tol: S,
+}
+
+impl<S> Default for RichardsonDifferentiator<S>
+where S: Real
+{
+    fn default() -> Self {
+        RichardsonDifferentiator {
+            scheme: DifferenceScheme::Central,
+            initial_step: S::from(0.1),
+            factor: S::from(1.4),
+            max_levels: 10,
+            abs_tol: S::from(1e-10),
+            rel_tol: S::from(1e-10),
+        }
+    }
+}
+
+impl<S> RichardsonDifferentiator<S>
+where S: Real
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The derivative of the given order of f at x, with an error estimate
+    pub fn estimate<Z, F>(&self, mut f: F, x: S, order: usize) -> Result<DerivativeResult<S, Z>, DiffError>
+    where
+        Z: Components<S>,
+        F: FnMut(S) -> Z,
+    {
+        // The error of a central difference is a series in h^2, and that of
+        // a one sided difference a series in h
+        let (accuracy, gain) = match self.scheme {
+            DifferenceScheme::Central => (2, 2),
+            _ => (1, 1),
+        };
+        let (offsets, weights) = stencil(self.scheme, order, accuracy)?;
+        let evaluations_per_level = weights.iter().filter(|&&w| w != 0.0).count();
+        let mut evaluations = 0;
+        let mut h = self.initial_step * x.abs().max(S::from(1.0));
+        let mut previous: Vec<Vec<S>> = Vec::new();
+        let mut best: Option<Z> = None;
+        let mut error = S::from(f64::INFINITY);
+        for level in 0..self.max_levels {
+            let step = (x + h) - x;
+            let estimate = apply(&mut f, x, step, order, &offsets, &weights);
+            evaluations += evaluations_per_level;
+            let template = estimate.clone();
+            let mut row = vec![components(&estimate)];
+            if best.is_none() {
+                best = Some(estimate);
+            }
+            let mut ratio = self.factor;
+            for _ in 0..accuracy - 1 {
+                ratio *= self.factor;
+            }
+            for j in 1..=level {
+                let extrapolated: Vec<S> = row[j - 1]
+                    .iter()
+                    .zip(previous[j - 1].iter())
+                    .map(|(&fine, &coarse)| (fine * ratio - coarse) / (ratio - S::from(1.0)))
+                    .collect();
+                let change = max_difference(&extrapolated, &row[j - 1]).max(max_difference(&extrapolated, &previous[j - 1]));
+                if change <= error {
+                    error = change;
+                    best = Some(from_components(&template, &extrapolated));
+                }
+                row.push(extrapolated);
+                for _ in 0..gain {
+                    ratio *= self.factor;
+                }
+            }
+            // Once the diagonal grows, rounding has taken over
+            if level > 0 && max_difference(&row[level], &previous[level - 1]) >= S::from(2.0) * error {
+                break;
+            }
+            if self.within_tolerance(best.as_ref().unwrap(), error) {
+                break;
+            }
+            previous = row;
+            h /= self.factor;
+        }
+        let value = best.unwrap();
+        let converged = self.within_tolerance(&value, error);
+        Ok(DerivativeResult { value, abs_error: error, evaluations, converged })
+    }
+
+    fn within_tolerance<Z>(&self, value: &Z, error: S) -> bool
+    where Z: Components<S>
+    {
+        let size = components(value).iter().fold(S::zero(), |acc, v| acc.max(v.abs()));
+        error <= self.abs_tol.max(self.rel_tol * size)
+    }
+}
+
+impl<S> Differentiator<S> for RichardsonDifferentiator<S>
+where S: Real
+{
+    fn derivative_of<Z, F>(&self, f: F, x: S, order: usize) -> Result<Z, DiffError>
+    where
+        Z: Components<S>,
+        F: FnMut(S) -> Z,
+    {
+        Ok(self.estimate(f, x, order)?.value)
+    }
+}
+
+// The finite difference sum over the stencil, divided by h^order. Points
+// with a zero weight (like x itself for odd central differences) are skipped.
+fn apply<S, Z, F>(f: &mut F, x: S, h: S, order: usize, offsets: &[f64], weights: &[f64]) -> Z
+where
+    S: Real,
+    Z: Components<S>,
+    F: FnMut(S) -> Z,
+{
+    let mut sum: Option<Z> = None;
+    for (&offset, &weight) in offsets.iter().zip(weights.iter()) {
+        if weight == 0.0 {
+            continue;
+        }
+        let value = f(x + S::from(offset) * h);
+        let weight = S::from(weight);
+        match sum.as_mut() {
+            None => {
+                let mut first = value.clone();
+                for i in 0..value.dimension() {
+                    first.set_component(i, weight * value.component(i));
+                }
+                sum = Some(first);
+            }
+            Some(total) => {
+                for i in 0..value.dimension() {
+                    total.set_component(i, total.component(i) + weight * value.component(i));
+                }
+            }
+        }
+    }
+    let mut digest = sum.unwrap();
+    let mut scale = S::from(1.0);
+    for _ in 0..order {
+        scale *= h;
+    }
+    for i in 0..digest.dimension() {
+        digest.set_component(i, digest.component(i) / scale);
+    }
+    digest
+}
+
+fn max_difference<S>(a: &[S], b: &[S]) -> S
+where S: Real {
+    a.iter().zip(b.iter()).fold(S::zero(), |acc, (&x, &y)| acc.max((x - y).abs()))
+}
@@ -0,0 +1,618 @@
+pub use crate::math::scalar::{Float, Real, Scalar};
+
+use std::cmp;
+use std::f64::consts;
+use std::fmt;
+use std::ops;
+
+// A number held as the unevaluated sum hi + lo of two f64s, where hi is the
+// sum rounded to an f64. This carries about 106 bits, or 32 decimal digits,
+// with the range of an f64, and costs a handful of f64 operations per
+// operation. The algorithms are those of the QD library (Hida, Li and
+// Bailey), built on the error-free transformations below.
+//
+// Results are within a few units in the 106th bit, but aren't rounded
+// correctly as f64 results are, so they can differ from exact arithmetic in
+// the last bit or two. Generic code is only as accurate as the constants it
+// brings in as f64s, such as the Runge-Kutta tableaus, which limit those
+// methods to f64 accuracy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+impl DoubleDouble {
+    // Normalizes the sum hi + lo, which needn't be normalized already
+    pub fn new(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = two_sum(hi, lo);
+        DoubleDouble::renormalized(hi, lo)
+    }
+
+    // The square, which takes fewer operations than a product
+    pub fn sqr(self) -> Self {
+        let (p, e) = two_prod(self.hi, self.hi);
+        if !p.is_finite() {
+            return exact(p);
+        }
+        let e = e + 2.0 * self.hi * self.lo + self.lo * self.lo;
+        let (hi, lo) = quick_two_sum(p, e);
+        DoubleDouble::renormalized(hi, lo)
+    }
+
+    // self 2^n, which is exact unless it overflows or underflows. The scale
+    // is applied in two halves, as 2^n alone may not be an f64.
+    fn scaled(self, n: i32) -> Self {
+        let (a, b) = (2f64.powi(n / 2), 2f64.powi(n - n / 2));
+        DoubleDouble::renormalized(self.hi * a * b, self.lo * a * b)
+    }
+
+    // Sums a series from its first term, where each term is the last times
+    // ratio(k) for k = 1, 2, ..., until the terms no longer change the sum
+    fn series(first: Self, ratio: impl Fn(f64) -> Self) -> Self {
+        let (mut sum, mut term) = (first, first);
+        let mut k = 1.0;
+        while term.hi.abs() > Self::precision() * sum.hi.abs() {
+            term *= ratio(k);
+            sum += term;
+            k += 1.0;
+        }
+        sum
+    }
+
+    // The sine and cosine, by their Taylor series after taking off the
+    // nearest multiple of pi / 2. Whole turns come off first, against tau
+    // carried to about 160 bits in three f64s. Below |x| = 2^53 the result
+    // keeps its full accuracy; beyond that the error left in tau grows with
+    // x, to about 1e-27 at 1e22.
+    fn sin_cos(self) -> (Self, Self) {
+        if !self.hi.is_finite() {
+            return (exact(f64::NAN), exact(f64::NAN));
+        }
+        // The count of turns is an f64, so beyond 2^53 turns a pass only
+        // takes off the leading 53 bits of it
+        let mut r = self;
+        while r.hi.abs() > consts::PI {
+            r = r.less_turns((r.hi / consts::TAU).round(), 1.0);
+        }
+        let quadrant = (r.hi / consts::FRAC_PI_2).round();
+        let r = r.less_turns(quadrant, 0.25);
+        let r2 = r.sqr();
+        let sin = DoubleDouble::series(r, |k| -r2 / ((2.0 * k) * (2.0 * k + 1.0)));
+        let cos = DoubleDouble::series(exact(1.0), |k| -r2 / ((2.0 * k - 1.0) * (2.0 * k)));
+        match quadrant as i32 {
+            0 => (sin, cos),
+            1 => (cos, -sin),
+            2 | -2 => (-sin, -cos),
+            _ => (-cos, sin),
+        }
+    }
+
+    // self - n k tau, for an integer n and a power of two k, where each
+    // product with a part of tau is exact
+    fn less_turns(self, n: f64, k: f64) -> Self {
+        TAU_PARTS.iter().fold(self, |r, &part| {
+            let (p, e) = two_prod(n, part * k);
+            r - DoubleDouble { hi: p, lo: e }
+        })
+    }
+
+    // Once hi overflows, lo has nothing left to correct. The operations
+    // check their leading f64 for this too, before their error terms turn
+    // into NaNs.
+    fn renormalized(hi: f64, lo: f64) -> Self {
+        if hi.is_finite() {
+            DoubleDouble { hi, lo }
+        } else {
+            DoubleDouble { hi, lo: 0.0 }
+        }
+    }
+}
+
+// The constants, each rounded to the nearest double-double
+const PI: DoubleDouble = DoubleDouble { hi: consts::PI, lo: 1.2246467991473532e-16 };
+const FRAC_PI_2: DoubleDouble = DoubleDouble { hi: consts::FRAC_PI_2, lo: 6.123233995736766e-17 };
+const E: DoubleDouble = DoubleDouble { hi: consts::E, lo: 1.4456468917292502e-16 };
+const LN_2: DoubleDouble = DoubleDouble { hi: consts::LN_2, lo: 2.3190468138462996e-17 };
+const LN_10: DoubleDouble = DoubleDouble { hi: consts::LN_10, lo: -2.1707562233822494e-16 };
+// tau as the sum of three f64s, the last rounded to the nearest
+const TAU_PARTS: [f64; 3] = [consts::TAU, 2.4492935982947064e-16, -5.989539619436679e-33];
+
+// The double-double equal to an f64
+fn exact(a: f64) -> DoubleDouble {
+    DoubleDouble { hi: a, lo: 0.0 }
+}
+
+// s + e = a + b exactly, where s is the rounded sum (Knuth)
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let shifted = s - a;
+    (s, (a - (s - shifted)) + (b - shifted))
+}
+
+// two_sum for |a| >= |b|, in fewer operations (Dekker)
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+// p + e = a b exactly, where p is the rounded product
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl fmt::Display for DoubleDouble {
+    // Prints the 32 significant digits the number has, less trailing zeros,
+    // or as many decimals as the precision asks for
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.hi.is_finite() || self.hi == 0.0 {
+            return write!(f, "{}", self.hi);
+        }
+        let mut x = self.abs();
+        let mut exponent = x.hi.log10().floor() as i32;
+        // Subnormal numbers need scaling by more than the largest f64, so
+        // they're scaled up in two steps
+        if exponent < 0 {
+            let n = -exponent;
+            x *= exact(10.0).powi(n / 2);
+            x *= exact(10.0).powi(n - n / 2);
+        } else {
+            x /= exact(10.0).powi(exponent);
+        }
+        // log10 may be out by one either way near powers of ten
+        if x.hi >= 10.0 {
+            x /= exact(10.0);
+            exponent += 1;
+        } else if x.hi < 1.0 {
+            x *= exact(10.0);
+            exponent -= 1;
+        }
+        let count = match f.precision() {
+            Some(decimals) => (exponent + 1 + decimals as i32).max(0) as usize,
+            None => 32,
+        };
+        // One more digit than is printed decides the rounding
+        let mut digits = Vec::with_capacity(count + 1);
+        for _ in 0..=count {
+            let digit = x.floor().hi.clamp(0.0, 9.0);
+            digits.push(digit as u8);
+            x = (x - exact(digit)) * exact(10.0);
+        }
+        if digits.pop().is_some_and(|d| d >= 5) {
+            match digits.iter().rposition(|&d| d != 9) {
+                Some(i) => {
+                    digits[i] += 1;
+                    digits[i + 1..].fill(0);
+                }
+                // Rounding 9.99... up carries into a new leading digit
+                None => {
+                    digits.fill(0);
+                    digits.insert(0, 1);
+                    exponent += 1;
+                }
+            }
+        }
+        // Digit i is the one worth 10^(exponent - i), and the digits are padded
+        // with zeros either side
+        let digit = |i: i32| match usize::try_from(i).ok().and_then(|i| digits.get(i)) {
+            Some(&d) => (b'0' + d) as char,
+            None => '0',
+        };
+        let mut digest = String::new();
+        if self.hi < 0.0 {
+            digest.push('-');
+        }
+        for power in (0..=exponent.max(0)).rev() {
+            digest.push(digit(exponent - power));
+        }
+        let decimals = match f.precision() {
+            Some(decimals) => decimals as i32,
+            None => (digits.len() as i32 - exponent - 1).max(0),
+        };
+        let mut fraction: String = (1..=decimals).map(|i| digit(exponent + i)).collect();
+        if f.precision().is_none() {
+            fraction.truncate(fraction.trim_end_matches('0').len());
+        }
+        if !fraction.is_empty() {
+            digest.push('.');
+            digest.push_str(&fraction);
+        }
+        write!(f, "{}", digest)
+    }
+}
+
+impl cmp::PartialOrd for DoubleDouble {
+    fn partial_cmp(&self, rhs: &Self) -> Option<cmp::Ordering> {
+        match self.hi.partial_cmp(&rhs.hi) {
+            Some(cmp::Ordering::Equal) => self.lo.partial_cmp(&rhs.lo),
+            ordering => ordering,
+        }
+    }
+}
+
+impl ops::Neg for DoubleDouble {
+    type Output = Self;
+    fn neg(self) -> Self {
+        DoubleDouble { hi: -self.hi, lo: -self.lo }
+    }
+}
+
+impl ops::Add for DoubleDouble {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let (s, e) = two_sum(self.hi, rhs.hi);
+        if !s.is_finite() {
+            return exact(s);
+        }
+        let (t, f) = two_sum(self.lo, rhs.lo);
+        let (s, e) = quick_two_sum(s, e + t);
+        let (hi, lo) = quick_two_sum(s, e + f);
+        DoubleDouble::renormalized(hi, lo)
+    }
+}
+
+impl ops::Sub for DoubleDouble {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl ops::Mul for DoubleDouble {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (p, e) = two_prod(self.hi, rhs.hi);
+        if !p.is_finite() {
+            return exact(p);
+        }
+        let (hi, lo) = quick_two_sum(p, e + (self.hi * rhs.lo + self.lo * rhs.hi));
+        DoubleDouble::renormalized(hi, lo)
+    }
+}
+
+// Long division, with a quotient digit of an f64 at a time
+impl ops::Div for DoubleDouble {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let q1 = self.hi / rhs.hi;
+        if !q1.is_finite() {
+            return DoubleDouble { hi: q1, lo: 0.0 };
+        }
+        let r = self - rhs * exact(q1);
+        let q2 = r.hi / rhs.hi;
+        let r = r - rhs * exact(q2);
+        let q3 = r.hi / rhs.hi;
+        let (hi, lo) = quick_two_sum(q1, q2);
+        DoubleDouble { hi, lo } + exact(q3)
+    }
+}
+
+// Arithmetic with f64s
+impl ops::Add<f64> for DoubleDouble {
+    type Output = Self;
+    fn add(self, rhs: f64) -> Self {
+        self + exact(rhs)
+    }
+}
+
+impl ops::Sub<f64> for DoubleDouble {
+    type Output = Self;
+    fn sub(self, rhs: f64) -> Self {
+        self - exact(rhs)
+    }
+}
+
+impl ops::Mul<f64> for DoubleDouble {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self {
+        self * exact(rhs)
+    }
+}
+
+impl ops::Div<f64> for DoubleDouble {
+    type Output = Self;
+    fn div(self, rhs: f64) -> Self {
+        self / exact(rhs)
+    }
+}
+
+impl ops::AddAssign for DoubleDouble {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::SubAssign for DoubleDouble {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::MulAssign for DoubleDouble {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::DivAssign for DoubleDouble {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Scalar for DoubleDouble {
+    // Every f64 is a double-double, so this is exact
+    fn from(a: f64) -> Self {
+        DoubleDouble { hi: a, lo: 0.0 }
+    }
+
+    // One Newton step on the f64 root, which doubles its digits (Karp)
+    fn sqrt(self) -> Self {
+        if self.hi <= 0.0 {
+            return exact(self.hi.sqrt());
+        }
+        let x = 1.0 / self.hi.sqrt();
+        let root = self.hi * x;
+        let (hi, lo) = two_sum(root, (self - exact(root).sqr()).hi * (x * 0.5));
+        DoubleDouble::renormalized(hi, lo)
+    }
+
+    fn inv(self) -> Self {
+        exact(1.0) / self
+    }
+
+    fn zero() -> Self {
+        DoubleDouble { hi: 0.0, lo: 0.0 }
+    }
+
+    fn magnitude(self) -> f64 {
+        self.hi.abs()
+    }
+
+    fn precision() -> f64 {
+        f64::EPSILON * f64::EPSILON
+    }
+}
+
+impl Real for DoubleDouble {
+    fn abs(self) -> Self {
+        if self.hi < 0.0 { -self } else { self }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other > self { other } else { self }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other < self { other } else { self }
+    }
+
+    fn epsilon() -> Self {
+        exact(f64::EPSILON * f64::EPSILON)
+    }
+
+    // hi is the sum rounded to the nearest f64
+    fn to_f64(self) -> f64 {
+        self.hi
+    }
+}
+
+// The functions are those of the QD library too: Taylor series after
+// argument reduction, or a Newton step on the f64 result, which doubles its
+// digits. Each is accurate to a few units in the 106th bit over the range
+// where the f64 function is accurate.
+impl Float for DoubleDouble {
+    fn pi() -> Self {
+        PI
+    }
+
+    fn e() -> Self {
+        E
+    }
+
+    fn infinity() -> Self {
+        exact(f64::INFINITY)
+    }
+
+    fn nan() -> Self {
+        exact(f64::NAN)
+    }
+
+    fn is_nan(self) -> bool {
+        self.hi.is_nan()
+    }
+
+    fn is_finite(self) -> bool {
+        self.hi.is_finite()
+    }
+
+    // The largest integer not above self
+    fn floor(self) -> Self {
+        let hi = self.hi.floor();
+        if hi == self.hi {
+            DoubleDouble::new(hi, self.lo.floor())
+        } else {
+            DoubleDouble { hi, lo: 0.0 }
+        }
+    }
+
+    fn ceil(self) -> Self {
+        -(-self).floor()
+    }
+
+    // Halves round away from zero
+    fn round(self) -> Self {
+        let rounded = (self.abs() + 0.5).floor();
+        if self.hi < 0.0 { -rounded } else { rounded }
+    }
+
+    // e^self = 2^m e^r with |r| <= ln 2 / 2. The series is summed for
+    // r / 512, then squared back up nine times, keeping e^x - 1 rather than
+    // e^x so as not to lose the digits of a small result to the 1.
+    fn exp(self) -> Self {
+        if self.hi > 709.8 {
+            return exact(f64::INFINITY);
+        }
+        if self.hi < -745.2 {
+            return DoubleDouble::zero();
+        }
+        if self.hi.is_nan() {
+            return self;
+        }
+        let m = (self.hi / LN_2.hi).round();
+        let r = (self - LN_2 * m) * (1.0 / 512.0);
+        let mut s = DoubleDouble::series(r, |k| r / (k + 1.0));
+        for _ in 0..9 {
+            s = s * 2.0 + s.sqr();
+        }
+        (s + 1.0).scaled(m as i32)
+    }
+
+    // A Newton step on exp(x) = self. The exponent is taken off first, so
+    // that exp(-x) can't overflow. Near 1, where the step would cancel, it is
+    // 2 atanh(t) for t = (self - 1) / (self + 1), by its series.
+    fn ln(self) -> Self {
+        if self.hi <= 0.0 || !self.hi.is_finite() {
+            return exact(self.hi.ln());
+        }
+        if (self.hi - 1.0).abs() < 0.25 {
+            let t = (self - 1.0) / (self + 1.0);
+            let t2 = t.sqr();
+            return DoubleDouble::series(t * 2.0, |k| t2 * (2.0 * k - 1.0) / (2.0 * k + 1.0));
+        }
+        let n = self.hi.log2().floor() as i32;
+        let a = self.scaled(-n);
+        let x = exact(a.hi.ln());
+        x + a * (-x).exp() - 1.0 + LN_2 * n as f64
+    }
+
+    fn log10(self) -> Self {
+        self.ln() / LN_10
+    }
+
+    // Integer powers are exact products, and negative bases have no others
+    fn powf(self, n: Self) -> Self {
+        if n.floor() == n && n.hi.abs() <= i32::MAX as f64 {
+            return self.powi(n.hi as i32);
+        }
+        if self.hi <= 0.0 || !n.hi.is_finite() {
+            return exact(self.hi.powf(n.hi));
+        }
+        (n * self.ln()).exp()
+    }
+
+    fn powi(self, n: i32) -> Self {
+        let mut digest = DoubleDouble::new(1.0, 0.0);
+        let mut base = self;
+        let mut k = n.unsigned_abs();
+        while k > 0 {
+            if k % 2 == 1 {
+                digest *= base;
+            }
+            base = base.sqr();
+            k /= 2;
+        }
+        if n < 0 { digest.inv() } else { digest }
+    }
+
+    fn cbrt(self) -> Self {
+        let x = exact(self.hi.cbrt());
+        if self.hi == 0.0 || !self.hi.is_finite() {
+            return x;
+        }
+        x - (x.sqr() * x - self) / (x.sqr() * 3.0)
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        let (a, b) = (self.abs(), other.abs());
+        let (large, small) = if a > b { (a, b) } else { (b, a) };
+        if large.hi == 0.0 || !large.hi.is_finite() || small.hi.is_nan() {
+            return exact(self.hi.hypot(other.hi));
+        }
+        large * (exact(1.0) + (small / large).sqr()).sqrt()
+    }
+
+    fn sin(self) -> Self {
+        if self.hi == 0.0 {
+            return self;
+        }
+        self.sin_cos().0
+    }
+
+    fn cos(self) -> Self {
+        self.sin_cos().1
+    }
+
+    fn tan(self) -> Self {
+        let (sin, cos) = self.sin_cos();
+        sin / cos
+    }
+
+    fn asin(self) -> Self {
+        if self.hi.abs() > 1.0 {
+            return exact(f64::NAN);
+        }
+        self.atan2(((exact(1.0) - self) * (exact(1.0) + self)).sqrt())
+    }
+
+    fn acos(self) -> Self {
+        if self.hi.abs() > 1.0 {
+            return exact(f64::NAN);
+        }
+        ((exact(1.0) - self) * (exact(1.0) + self)).sqrt().atan2(self)
+    }
+
+    fn atan(self) -> Self {
+        self.atan2(exact(1.0))
+    }
+
+    // A Newton step on the f64 angle z, solving sin z = y / r where the
+    // sine is the smaller, so the steeper, of the two, or cos z = x / r
+    fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self, other);
+        if y.is_nan() || x.is_nan() {
+            return exact(f64::NAN);
+        }
+        if y.hi.is_infinite() && x.hi.is_infinite() {
+            return exact(y.hi.signum()).atan2(exact(x.hi.signum()));
+        }
+        if y.hi == 0.0 || x.hi.is_infinite() {
+            return if x.hi.is_sign_negative() { PI * y.hi.signum() } else { exact(0.0 * y.hi.signum()) };
+        }
+        if x.hi == 0.0 || y.hi.is_infinite() {
+            return FRAC_PI_2 * y.hi.signum();
+        }
+        let z = exact(y.hi.atan2(x.hi));
+        let r = y.hypot(x);
+        let (sin, cos) = z.sin_cos();
+        if x.hi.abs() > y.hi.abs() {
+            z + (y / r - sin) / cos
+        } else {
+            z - (x / r - cos) / sin
+        }
+    }
+
+    // The series near zero, where e^x - e^-x cancels
+    fn sinh(self) -> Self {
+        if self.hi.abs() < 0.05 {
+            let x2 = self.sqr();
+            return DoubleDouble::series(self, |k| x2 / ((2.0 * k) * (2.0 * k + 1.0)));
+        }
+        let e = self.exp();
+        (e - e.inv()) * 0.5
+    }
+
+    fn cosh(self) -> Self {
+        let e = self.abs().exp();
+        (e + e.inv()) * 0.5
+    }
+
+    // Beyond 40, tanh is 1 to within a double-double
+    fn tanh(self) -> Self {
+        if self.hi.abs() > 40.0 {
+            return exact(self.hi.signum());
+        }
+        self.sinh() / self.cosh()
+    }
+}
@@ -0,0 +1,479 @@
+pub use crate::math::scalar::{Float, Real, Scalar};
+
+use crate::math::scalar::power;
+
+use std::cmp;
+use std::fmt;
+use std::ops;
+
+// A dual number v + g1 e1 + ... + gN eN, where every product of the e's is
+// zero. Arithmetic on duals carries derivatives along by the chain rule, so
+// running any code that is generic over Scalar on duals gives the exact
+// derivatives of its result (forward mode automatic differentiation).
+//
+// With N = 1 this is the derivative along one direction. With N inputs
+// seeded as variables, gradient holds the partial derivatives by each of
+// them, so one pass through (say) a Vector3D<Dual<f64, 3>> computation gives
+// a whole gradient.
+//
+// Duals compare by value alone, so that branches on comparisons (as in abs,
+// max and the adaptive solvers' tolerance checks) take the same path as they
+// would for the plain numbers.
+#[derive(Debug, Clone, Copy)]
+pub struct Dual<S, const N: usize = 1>
+where S: Scalar {
+    pub value: S,
+    pub gradient: [S; N],
+}
+
+impl<S, const N: usize> Dual<S, N>
+where S: Scalar
+{
+    pub fn new(value: S, gradient: [S; N]) -> Self {
+        Dual { value, gradient }
+    }
+
+    // A number that doesn't depend on any of the variables
+    pub fn constant(value: S) -> Self {
+        Dual { value, gradient: [S::zero(); N] }
+    }
+
+    // The variable that the derivatives in slot index are taken by
+    pub fn variable(value: S, index: usize) -> Self {
+        let mut gradient = [S::zero(); N];
+        gradient[index] = S::from(1.0);
+        Dual { value, gradient }
+    }
+
+    // One variable for each slot, with the given values
+    pub fn variables(values: [S; N]) -> [Self; N] {
+        let mut index = 0;
+        values.map(|value| {
+            index += 1;
+            Self::variable(value, index - 1)
+        })
+    }
+
+    // Applies a function whose value at self.value is value, and whose
+    // derivative there is slope
+    fn chain(self, value: S, slope: S) -> Self {
+        Dual { value, gradient: self.gradient.map(|g| through(g, slope)) }
+    }
+
+    // Applies a function of two arguments, with its derivative by each
+    fn chain2(self, other: Self, value: S, slope: S, other_slope: S) -> Self {
+        let mut gradient = self.gradient;
+        for (g, &o) in gradient.iter_mut().zip(other.gradient.iter()) {
+            *g = through(*g, slope) + through(o, other_slope);
+        }
+        Dual { value, gradient }
+    }
+
+    pub fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            return Self::constant(S::from(1.0));
+        }
+        // value^(|n| - 1), which the derivative needs as well as the power
+        let power = power(self.value, n.unsigned_abs() - 1);
+        let (value, slope) = if n > 0 {
+            (power * self.value, S::from(n as f64) * power)
+        } else {
+            let value = (power * self.value).inv();
+            (value, S::from(n as f64) * value * self.value.inv())
+        };
+        self.chain(value, slope)
+    }
+}
+
+// The part g of a derivative carried through a function with the given
+// slope. What doesn't depend on a variable still doesn't after the function,
+// even where the slope is infinite, as for sqrt at zero.
+fn through<S: Scalar>(g: S, slope: S) -> S {
+    if g == S::zero() { g } else { g * slope }
+}
+
+impl<S> Dual<S, 1>
+where S: Scalar
+{
+    // The derivative by the single variable
+    pub fn derivative(&self) -> S {
+        self.gradient[0]
+    }
+}
+
+// The value and derivative of f at x
+pub fn derivative<S, F>(f: F, x: S) -> (S, S)
+where
+    S: Scalar,
+    F: FnOnce(Dual<S>) -> Dual<S>,
+{
+    let y = f(Dual::variable(x, 0));
+    (y.value, y.derivative())
+}
+
+// The value and gradient of f at x, in one pass
+pub fn gradient<S, F, const N: usize>(f: F, x: [S; N]) -> (S, [S; N])
+where
+    S: Scalar,
+    F: FnOnce([Dual<S, N>; N]) -> Dual<S, N>,
+{
+    let y = f(Dual::variables(x));
+    (y.value, y.gradient)
+}
+
+impl<S, const N: usize> fmt::Display for Dual<S, N>
+where S: Scalar
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)?;
+        for (i, g) in self.gradient.iter().enumerate() {
+            if N == 1 {
+                write!(f, " + {}ε", g)?;
+            } else {
+                write!(f, " + {}ε{}", g, i)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S, const N: usize> cmp::PartialEq for Dual<S, N>
+where S: Scalar
+{
+    fn eq(&self, rhs: &Self) -> bool {
+        self.value == rhs.value
+    }
+}
+
+impl<S, const N: usize> cmp::PartialOrd for Dual<S, N>
+where S: Real
+{
+    fn partial_cmp(&self, rhs: &Self) -> Option<cmp::Ordering> {
+        self.value.partial_cmp(&rhs.value)
+    }
+}
+
+impl<S, const N: usize> ops::Neg for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.chain(S::zero() - self.value, S::from(-1.0))
+    }
+}
+
+impl<S, const N: usize> ops::Add for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let mut gradient = self.gradient;
+        for (g, r) in gradient.iter_mut().zip(rhs.gradient.iter()) {
+            *g += *r;
+        }
+        Dual { value: self.value + rhs.value, gradient }
+    }
+}
+
+impl<S, const N: usize> ops::Sub for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let mut gradient = self.gradient;
+        for (g, r) in gradient.iter_mut().zip(rhs.gradient.iter()) {
+            *g -= *r;
+        }
+        Dual { value: self.value - rhs.value, gradient }
+    }
+}
+
+impl<S, const N: usize> ops::Mul for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let mut gradient = self.gradient;
+        for (g, r) in gradient.iter_mut().zip(rhs.gradient.iter()) {
+            *g = *g * rhs.value + self.value * *r;
+        }
+        Dual { value: self.value * rhs.value, gradient }
+    }
+}
+
+impl<S, const N: usize> ops::Div for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let inverse = rhs.value.inv();
+        let value = self.value * inverse;
+        let mut gradient = self.gradient;
+        for (g, r) in gradient.iter_mut().zip(rhs.gradient.iter()) {
+            *g = (*g - value * *r) * inverse;
+        }
+        Dual { value, gradient }
+    }
+}
+
+// Arithmetic with plain numbers, which act as constants
+impl<S, const N: usize> ops::Add<S> for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn add(self, rhs: S) -> Self {
+        Dual { value: self.value + rhs, gradient: self.gradient }
+    }
+}
+
+impl<S, const N: usize> ops::Sub<S> for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn sub(self, rhs: S) -> Self {
+        Dual { value: self.value - rhs, gradient: self.gradient }
+    }
+}
+
+impl<S, const N: usize> ops::Mul<S> for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn mul(self, rhs: S) -> Self {
+        self.chain(self.value * rhs, rhs)
+    }
+}
+
+impl<S, const N: usize> ops::Div<S> for Dual<S, N>
+where S: Scalar
+{
+    type Output = Self;
+    fn div(self, rhs: S) -> Self {
+        let inverse = rhs.inv();
+        self.chain(self.value / rhs, inverse)
+    }
+}
+
+impl<S, const N: usize> ops::AddAssign for Dual<S, N>
+where S: Scalar
+{
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<S, const N: usize> ops::SubAssign for Dual<S, N>
+where S: Scalar
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<S, const N: usize> ops::MulAssign for Dual<S, N>
+where S: Scalar
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<S, const N: usize> ops::DivAssign for Dual<S, N>
+where S: Scalar
+{
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<S, const N: usize> Scalar for Dual<S, N>
+where S: Scalar
+{
+    fn from(a: f64) -> Self {
+        Self::constant(S::from(a))
+    }
+
+    // The derivative is infinite at zero, as for the real square root
+    fn sqrt(self) -> Self {
+        let root = self.value.sqrt();
+        self.chain(root, (S::from(2.0) * root).inv())
+    }
+
+    fn inv(self) -> Self {
+        let inverse = self.value.inv();
+        self.chain(inverse, S::zero() - inverse * inverse)
+    }
+
+    fn zero() -> Self {
+        Self::constant(S::zero())
+    }
+
+    fn magnitude(self) -> f64 {
+        self.value.magnitude()
+    }
+
+    fn precision() -> f64 {
+        S::precision()
+    }
+
+    fn conj(self) -> Self {
+        Dual { value: self.value.conj(), gradient: self.gradient.map(|g| g.conj()) }
+    }
+}
+
+impl<S, const N: usize> Real for Dual<S, N>
+where S: Real
+{
+    // The derivative at zero is taken from the positive side
+    fn abs(self) -> Self {
+        if self.value < S::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.value > self.value {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.value < self.value {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn epsilon() -> Self {
+        Self::constant(S::epsilon())
+    }
+
+    fn to_f64(self) -> f64 {
+        self.value.to_f64()
+    }
+}
+
+impl<S, const N: usize> Float for Dual<S, N>
+where S: Float
+{
+    fn pi() -> Self {
+        Self::constant(S::pi())
+    }
+
+    fn e() -> Self {
+        Self::constant(S::e())
+    }
+
+    fn infinity() -> Self {
+        Self::constant(S::infinity())
+    }
+
+    fn nan() -> Self {
+        Self::constant(S::nan())
+    }
+
+    fn is_nan(self) -> bool {
+        self.value.is_nan()
+    }
+
+    fn is_finite(self) -> bool {
+        self.value.is_finite()
+    }
+
+    // Steps are flat wherever they have a derivative
+    fn floor(self) -> Self {
+        self.chain(self.value.floor(), S::zero())
+    }
+
+    fn ceil(self) -> Self {
+        self.chain(self.value.ceil(), S::zero())
+    }
+
+    fn round(self) -> Self {
+        self.chain(self.value.round(), S::zero())
+    }
+
+    fn exp(self) -> Self {
+        let value = self.value.exp();
+        self.chain(value, value)
+    }
+
+    fn ln(self) -> Self {
+        self.chain(self.value.ln(), self.value.inv())
+    }
+
+    fn log10(self) -> Self {
+        self.chain(self.value.log10(), (self.value * S::from(10.0).ln()).inv())
+    }
+
+    // The derivative by the exponent is only taken when it is needed, so
+    // that negative numbers can still be raised to constant powers
+    fn powf(self, n: Self) -> Self {
+        let value = self.value.powf(n.value);
+        let slope = n.value * self.value.powf(n.value - S::one());
+        let n_slope = if n.gradient.iter().all(|&g| g == S::zero()) { S::zero() } else { value * self.value.ln() };
+        self.chain2(n, value, slope, n_slope)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        Dual::powi(self, n)
+    }
+
+    fn cbrt(self) -> Self {
+        let value = self.value.cbrt();
+        self.chain(value, (S::from(3.0) * value * value).inv())
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        let value = self.value.hypot(other.value);
+        self.chain2(other, value, self.value / value, other.value / value)
+    }
+
+    fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+
+    fn cos(self) -> Self {
+        self.chain(self.value.cos(), S::zero() - self.value.sin())
+    }
+
+    fn tan(self) -> Self {
+        let value = self.value.tan();
+        self.chain(value, S::one() + value * value)
+    }
+
+    fn asin(self) -> Self {
+        self.chain(self.value.asin(), (S::one() - self.value * self.value).sqrt().inv())
+    }
+
+    fn acos(self) -> Self {
+        self.chain(self.value.acos(), S::zero() - (S::one() - self.value * self.value).sqrt().inv())
+    }
+
+    fn atan(self) -> Self {
+        self.chain(self.value.atan(), (S::one() + self.value * self.value).inv())
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let square = self.value * self.value + other.value * other.value;
+        self.chain2(other, self.value.atan2(other.value), other.value / square, S::zero() - self.value / square)
+    }
+
+    fn sinh(self) -> Self {
+        self.chain(self.value.sinh(), self.value.cosh())
+    }
+
+    fn cosh(self) -> Self {
+        self.chain(self.value.cosh(), self.value.sinh())
+    }
+
+    fn tanh(self) -> Self {
+        let value = self.value.tanh();
+        self.chain(value, S::one() - value * value)
+    }
+}
@@ -0,0 +1,459 @@
+pub use crate::math::scalar::{Float, Real, Scalar};
+
+use std::cmp;
+use std::fmt;
+use std::ops;
+
+// A closed interval [lo, hi] of the real line, which stands for some number
+// known only to lie within it. Every operation returns an interval holding
+// every result the operation could have on numbers in its operands, with
+// the bounds rounded outwards, so running generic code on intervals gives
+// bounds on its result in exact arithmetic that rounding can't break.
+//
+// Bounds are only rounded when the operation on them was inexact, so exact
+// computations give points. Intervals over the empty set, from things like
+// the square root of a negative interval, have NaN bounds.
+//
+// Comparisons between intervals hold when they hold for every pair of
+// numbers in them, and otherwise the intervals are unordered. Intervals
+// with the same bounds are the same set, so are equal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval<S>
+where S: IntervalBound {
+    pub lo: S,
+    pub hi: S,
+}
+
+// Floating point types that can bound the results of their own arithmetic
+pub trait IntervalBound
+where Self: Float {
+    // The neighbouring representable numbers
+    fn next_up(self) -> Self;
+    fn next_down(self) -> Self;
+    // self * a + b, rounded once
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    // The smallest positive normal number
+    fn min_positive() -> Self;
+}
+
+macro_rules! impl_interval_bound {
+    ($t:ident) => {
+        impl IntervalBound for $t {
+            // Stepping the bits away from or towards zero, as the inherent
+            // next_up and next_down need Rust 1.86
+            fn next_up(self) -> Self {
+                if self.is_nan() || self == $t::INFINITY {
+                    self
+                } else if self == 0.0 {
+                    $t::from_bits(1)
+                } else if self > 0.0 {
+                    $t::from_bits(self.to_bits() + 1)
+                } else {
+                    $t::from_bits(self.to_bits() - 1)
+                }
+            }
+
+            fn next_down(self) -> Self {
+                -IntervalBound::next_up(-self)
+            }
+
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                self.mul_add(a, b)
+            }
+
+            fn min_positive() -> Self {
+                $t::MIN_POSITIVE
+            }
+        }
+    };
+}
+
+impl_interval_bound!(f32);
+impl_interval_bound!(f64);
+
+impl<S> Interval<S>
+where S: IntervalBound
+{
+    pub fn new(lo: S, hi: S) -> Self {
+        assert!(lo <= hi, "An interval's lower bound can't be above its upper bound.");
+        Interval { lo, hi }
+    }
+
+    // The interval holding just x
+    pub fn point(x: S) -> Self {
+        Interval { lo: x, hi: x }
+    }
+
+    // The whole real line
+    pub fn entire() -> Self {
+        Interval { lo: S::zero() - S::infinity(), hi: S::infinity() }
+    }
+
+    pub fn empty() -> Self {
+        Interval { lo: S::nan(), hi: S::nan() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lo.is_nan() || self.hi.is_nan()
+    }
+
+    pub fn contains(&self, x: S) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    // Whether every number in other is in self
+    pub fn encloses(&self, other: &Self) -> bool {
+        other.is_empty() || (self.lo <= other.lo && other.hi <= self.hi)
+    }
+
+    // hi - lo, rounded up
+    pub fn width(&self) -> S {
+        sum(self.hi, S::zero() - self.lo).1
+    }
+
+    // The number nearest the middle of the interval, which is zero for the
+    // whole real line and infinite for other unbounded intervals
+    pub fn midpoint(&self) -> S {
+        if self.lo == S::zero() - S::infinity() && self.hi == S::infinity() {
+            return S::zero();
+        }
+        // Halving first can't overflow
+        let half = S::from(0.5);
+        self.lo * half + self.hi * half
+    }
+
+    // The largest distance from the midpoint to a bound, rounded up, so the
+    // interval lies within the radius of its midpoint
+    pub fn radius(&self) -> S {
+        let mid = self.midpoint();
+        sum(mid, S::zero() - self.lo).1.max(sum(self.hi, S::zero() - mid).1)
+    }
+
+    // The smallest interval holding both
+    pub fn hull(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        Interval { lo: self.lo.min(other.lo), hi: self.hi.max(other.hi) }
+    }
+
+    // The numbers in both, which may be none
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (lo, hi) = (self.lo.max(other.lo), self.hi.min(other.hi));
+        if self.is_empty() || other.is_empty() || lo > hi {
+            return Self::empty();
+        }
+        Interval { lo, hi }
+    }
+
+    // Even powers are never negative, however the interval straddles zero,
+    // which multiplying the interval by itself can't know
+    pub fn powi(self, n: i32) -> Self {
+        let mut digest = Self::point(S::from(1.0));
+        let mut base = self;
+        let mut k = n.unsigned_abs();
+        while k > 0 {
+            if k % 2 == 1 {
+                digest *= base;
+            }
+            base = base.square();
+            k /= 2;
+        }
+        if n < 0 { digest.inv() } else { digest }
+    }
+
+    fn square(self) -> Self {
+        let magnitude = self.abs();
+        let (lo, hi) = (product(magnitude.lo, magnitude.lo).0, product(magnitude.hi, magnitude.hi).1);
+        Interval { lo, hi }
+    }
+}
+
+// Below this, the rounding errors of products and quotients may underflow,
+// so they can't be trusted to say which way a result was rounded
+fn tiny<S>() -> S
+where S: IntervalBound {
+    S::min_positive() / (S::epsilon() * S::epsilon())
+}
+
+// Bounds value + error, the exact result that value is the rounding of. A
+// NaN error means the result overflowed, or the error couldn't be found.
+fn enclose<S>(value: S, error: S) -> (S, S)
+where S: IntervalBound {
+    if error > S::zero() {
+        (value, value.next_up())
+    } else if error < S::zero() {
+        (value.next_down(), value)
+    } else if error == S::zero() {
+        (value, value)
+    } else {
+        (value.next_down(), value.next_up())
+    }
+}
+
+// The bounds of a + b, whose rounding error is found exactly by Knuth's
+// TwoSum
+fn sum<S>(a: S, b: S) -> (S, S)
+where S: IntervalBound {
+    let s = a + b;
+    let shifted = s - a;
+    enclose(s, (a - (s - shifted)) + (b - shifted))
+}
+
+// The bounds of a b, where zero times anything, even an infinity, is zero
+fn product<S>(a: S, b: S) -> (S, S)
+where S: IntervalBound {
+    if a == S::zero() || b == S::zero() {
+        return (S::zero(), S::zero());
+    }
+    let p = a * b;
+    let error = a.mul_add(b, S::zero() - p);
+    if error == S::zero() && p.abs() < tiny() {
+        return (p.next_down(), p.next_up());
+    }
+    enclose(p, error)
+}
+
+// The bounds of a / b, for b that isn't zero, where zero over anything is
+// zero
+fn quotient<S>(a: S, b: S) -> (S, S)
+where S: IntervalBound {
+    if a == S::zero() {
+        return (S::zero(), S::zero());
+    }
+    let q = a / b;
+    // a - q b has the sign of the error times that of b
+    let remainder = (S::zero() - q).mul_add(b, a);
+    if remainder == S::zero() && a.abs() < tiny() {
+        return (q.next_down(), q.next_up());
+    }
+    enclose(q, if b < S::zero() { S::zero() - remainder } else { remainder })
+}
+
+impl<S> fmt::Display for Interval<S>
+where S: IntervalBound
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            write!(f, "∅")
+        } else {
+            write!(f, "[{}, {}]", self.lo, self.hi)
+        }
+    }
+}
+
+impl<S> cmp::PartialOrd for Interval<S>
+where S: IntervalBound
+{
+    fn partial_cmp(&self, rhs: &Self) -> Option<cmp::Ordering> {
+        if self == rhs {
+            Some(cmp::Ordering::Equal)
+        } else if self.hi < rhs.lo {
+            Some(cmp::Ordering::Less)
+        } else if self.lo > rhs.hi {
+            Some(cmp::Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+impl<S> ops::Neg for Interval<S>
+where S: IntervalBound
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        Interval { lo: S::zero() - self.hi, hi: S::zero() - self.lo }
+    }
+}
+
+impl<S> ops::Add for Interval<S>
+where S: IntervalBound
+{
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Interval { lo: sum(self.lo, rhs.lo).0, hi: sum(self.hi, rhs.hi).1 }
+    }
+}
+
+impl<S> ops::Sub for Interval<S>
+where S: IntervalBound
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<S> ops::Mul for Interval<S>
+where S: IntervalBound
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        if self.is_empty() || rhs.is_empty() {
+            return Self::empty();
+        }
+        let products = [
+            product(self.lo, rhs.lo),
+            product(self.lo, rhs.hi),
+            product(self.hi, rhs.lo),
+            product(self.hi, rhs.hi),
+        ];
+        Interval {
+            lo: products.iter().map(|p| p.0).fold(S::infinity(), S::min),
+            hi: products.iter().map(|p| p.1).fold(S::zero() - S::infinity(), S::max),
+        }
+    }
+}
+
+// Dividing by an interval holding zero gives the unbounded intervals of
+// the reciprocal, or the whole line when the divisor straddles zero
+impl<S> ops::Div for Interval<S>
+where S: IntervalBound
+{
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        if self.is_empty() || rhs.is_empty() {
+            return Self::empty();
+        }
+        if rhs.contains(S::zero()) {
+            return self * rhs.inv();
+        }
+        let quotients = [
+            quotient(self.lo, rhs.lo),
+            quotient(self.lo, rhs.hi),
+            quotient(self.hi, rhs.lo),
+            quotient(self.hi, rhs.hi),
+        ];
+        Interval {
+            lo: quotients.iter().map(|q| q.0).fold(S::infinity(), S::min),
+            hi: quotients.iter().map(|q| q.1).fold(S::zero() - S::infinity(), S::max),
+        }
+    }
+}
+
+impl<S> ops::AddAssign for Interval<S>
+where S: IntervalBound
+{
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<S> ops::SubAssign for Interval<S>
+where S: IntervalBound
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<S> ops::MulAssign for Interval<S>
+where S: IntervalBound
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<S> ops::DivAssign for Interval<S>
+where S: IntervalBound
+{
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<S> Scalar for Interval<S>
+where S: IntervalBound
+{
+    // The f64 itself if S can hold it, and otherwise the numbers either side
+    fn from(a: f64) -> Self {
+        let x = S::from(a);
+        let error = if x.to_f64() < a {
+            S::from(1.0)
+        } else if x.to_f64() > a {
+            S::from(-1.0)
+        } else {
+            S::zero()
+        };
+        let (lo, hi) = enclose(x, error);
+        Interval { lo, hi }
+    }
+
+    // The square roots of the non-negative part of the interval
+    fn sqrt(self) -> Self {
+        if self.hi < S::zero() || self.is_empty() {
+            return Self::empty();
+        }
+        let root = |x: S| {
+            let s = x.sqrt();
+            let error = (S::zero() - s).mul_add(s, x);
+            if error == S::zero() && x < tiny() && x > S::zero() {
+                return (s.next_down(), s.next_up());
+            }
+            enclose(s, error)
+        };
+        Interval { lo: root(self.lo.max(S::zero())).0, hi: root(self.hi).1 }
+    }
+
+    fn inv(self) -> Self {
+        if self.is_empty() || (self.lo == S::zero() && self.hi == S::zero()) {
+            Self::empty()
+        } else if self.lo == S::zero() {
+            Interval { lo: quotient(S::from(1.0), self.hi).0, hi: S::infinity() }
+        } else if self.hi == S::zero() {
+            Interval { lo: S::zero() - S::infinity(), hi: quotient(S::from(1.0), self.lo).1 }
+        } else if self.contains(S::zero()) {
+            Self::entire()
+        } else {
+            Self::point(S::from(1.0)) / self
+        }
+    }
+
+    fn zero() -> Self {
+        Self::point(S::zero())
+    }
+
+    // The largest magnitude of any number in the interval
+    fn magnitude(self) -> f64 {
+        self.lo.abs().max(self.hi.abs()).to_f64()
+    }
+
+    fn precision() -> f64 {
+        S::precision()
+    }
+}
+
+impl<S> Real for Interval<S>
+where S: IntervalBound
+{
+    fn abs(self) -> Self {
+        if self.lo >= S::zero() {
+            self
+        } else if self.hi <= S::zero() {
+            -self
+        } else {
+            Interval { lo: S::zero(), hi: (S::zero() - self.lo).max(self.hi) }
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Interval { lo: self.lo.max(other.lo), hi: self.hi.max(other.hi) }
+    }
+
+    fn min(self, other: Self) -> Self {
+        Interval { lo: self.lo.min(other.lo), hi: self.hi.min(other.hi) }
+    }
+
+    fn epsilon() -> Self {
+        Self::point(S::epsilon())
+    }
+
+    fn to_f64(self) -> f64 {
+        self.midpoint().to_f64()
+    }
+}
@@ -0,0 +1,301 @@
+
+use crate::math::approx::ApproxEq;
+use crate::math::scalar::Scalar;
+
+use std::fmt;
+use std::ops;
+
+// A matrix of any size, stored row by row. GeneralMatrix and SquareMatrix
+// are built from spatial vectors, so their rows are as long as the vector
+// type (three, for Vector3D), which can't hold the n by n Jacobian of an
+// arbitrary system. The dimensions of this one are only known at run time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseMatrix<S>
+where S: Scalar {
+    rows: usize,
+    cols: usize,
+    data: Vec<S>,
+}
+
+impl<S> DenseMatrix<S>
+where S: Scalar
+{
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        DenseMatrix { rows, cols, data: vec![S::zero(); rows * cols] }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut digest = Self::zeros(n, n);
+        for i in 0..n {
+            digest[(i, i)] = S::from(1.0);
+        }
+        digest
+    }
+
+    // Builds a matrix from its rows, which must all be the same length
+    pub fn from_rows(rows: &[Vec<S>]) -> Result<Self, &'static str> {
+        let cols = rows.first().map_or(0, |r| r.len());
+        if rows.iter().any(|r| r.len() != cols) {
+            return Err("Every row of a matrix must have the same length.");
+        }
+        Ok(DenseMatrix { rows: rows.len(), cols, data: rows.concat() })
+    }
+
+    // Returns the dimensions of the matrix, (m,n)
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn row(&self, i: usize) -> &[S] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut digest = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                digest[(j, i)] = self[(i, j)];
+            }
+        }
+        digest
+    }
+
+    // The conjugate transpose, which is just the transpose for real matrices
+    pub fn adjoint(&self) -> Self {
+        let mut digest = self.transpose();
+        for a in digest.data.iter_mut() {
+            *a = a.conj();
+        }
+        digest
+    }
+
+    // The product of this matrix and a column vector
+    pub fn mul_vec(&self, x: &[S]) -> Vec<S> {
+        assert_eq!(x.len(), self.cols, "The vector's length must match the number of columns.");
+        (0..self.rows)
+            .map(|i| {
+                let mut digest = S::zero();
+                for (&a, &b) in self.row(i).iter().zip(x.iter()) {
+                    digest += a * b;
+                }
+                digest
+            })
+            .collect()
+    }
+
+    // Factors the matrix with partial pivoting, failing if it is not square
+    // or is singular
+    pub fn lu(&self) -> Result<LuDecomposition<S>, &'static str> {
+        if self.rows != self.cols {
+            return Err("Only square matrices have an LU decomposition.");
+        }
+        let n = self.rows;
+        let mut lu = self.clone();
+        let mut pivots: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+        for k in 0..n {
+            let mut pivot = k;
+            for i in k + 1..n {
+                if lu[(i, k)].magnitude() > lu[(pivot, k)].magnitude() {
+                    pivot = i;
+                }
+            }
+            if lu[(pivot, k)] == S::zero() {
+                return Err("This matrix is singular.");
+            }
+            if pivot != k {
+                for j in 0..n {
+                    lu.data.swap(k * n + j, pivot * n + j);
+                }
+                pivots.swap(k, pivot);
+                swaps += 1;
+            }
+            let inv = lu[(k, k)].inv();
+            for i in k + 1..n {
+                let factor = lu[(i, k)] * inv;
+                lu[(i, k)] = factor;
+                if factor != S::zero() {
+                    for j in k + 1..n {
+                        let u = lu[(k, j)];
+                        lu[(i, j)] -= factor * u;
+                    }
+                }
+            }
+        }
+        Ok(LuDecomposition { lu, pivots, swaps })
+    }
+
+    // Solves self x = b
+    pub fn solve(&self, b: &[S]) -> Result<Vec<S>, &'static str> {
+        Ok(self.lu()?.solve(b))
+    }
+}
+
+// Matrices of different sizes are never approximately equal
+impl<S> ApproxEq for DenseMatrix<S>
+where S: Scalar
+{
+    fn abs_diff_eq(&self, rhs: &Self, epsilon: f64) -> bool {
+        self.size() == rhs.size()
+            && self.data.iter().zip(&rhs.data).all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn relative_eq(&self, rhs: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.size() == rhs.size()
+            && self.data.iter().zip(&rhs.data)
+                .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+    }
+
+    fn ulps_eq(&self, rhs: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.size() == rhs.size()
+            && self.data.iter().zip(&rhs.data).all(|(a, b)| a.ulps_eq(b, epsilon, max_ulps))
+    }
+
+    fn default_epsilon() -> f64 {
+        S::default_epsilon()
+    }
+
+    fn default_max_relative() -> f64 {
+        S::default_max_relative()
+    }
+}
+
+impl<S> ops::Index<(usize, usize)> for DenseMatrix<S>
+where S: Scalar
+{
+    type Output = S;
+    fn index(&self, (i, j): (usize, usize)) -> &S {
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<S> ops::IndexMut<(usize, usize)> for DenseMatrix<S>
+where S: Scalar
+{
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut S {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<S> ops::Add for DenseMatrix<S>
+where S: Scalar
+{
+    type Output = Self;
+    fn add(mut self, rhs: Self) -> Self {
+        assert_eq!(self.size(), rhs.size(), "Only matrices of the same size can be added.");
+        for (a, &b) in self.data.iter_mut().zip(rhs.data.iter()) {
+            *a += b;
+        }
+        self
+    }
+}
+
+impl<S> ops::Sub for DenseMatrix<S>
+where S: Scalar
+{
+    type Output = Self;
+    fn sub(mut self, rhs: Self) -> Self {
+        assert_eq!(self.size(), rhs.size(), "Only matrices of the same size can be subtracted.");
+        for (a, &b) in self.data.iter_mut().zip(rhs.data.iter()) {
+            *a -= b;
+        }
+        self
+    }
+}
+
+impl<S> ops::Mul for DenseMatrix<S>
+where S: Scalar
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        assert_eq!(self.cols, rhs.rows, "The inner dimensions of a matrix product must match.");
+        let mut digest = Self::zeros(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                for j in 0..rhs.cols {
+                    digest[(i, j)] += a * rhs[(k, j)];
+                }
+            }
+        }
+        digest
+    }
+}
+
+impl<S> ops::Mul<S> for DenseMatrix<S>
+where S: Scalar
+{
+    type Output = Self;
+    fn mul(mut self, rhs: S) -> Self {
+        for a in self.data.iter_mut() {
+            *a *= rhs;
+        }
+        self
+    }
+}
+
+impl<S> fmt::Display for DenseMatrix<S>
+where S: Scalar
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.rows {
+            let row: Vec<String> = self.row(i).iter().map(|a| a.to_string()).collect();
+            let (open, close) = match i {
+                _ if self.rows == 1 => ("[", "]"),
+                0 => ("⎡", "⎤"),
+                _ if i + 1 == self.rows => ("⎣", "⎦"),
+                _ => ("⎢", "⎥"),
+            };
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{} {} {}", open, row.join(", "), close)?;
+        }
+        Ok(())
+    }
+}
+
+// The factorisation P A = L U of a square matrix, where L is unit lower
+// triangular and U is upper triangular. Both are stored in one matrix. Once
+// computed, it can solve for any number of right hand sides cheaply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuDecomposition<S>
+where S: Scalar {
+    lu: DenseMatrix<S>,
+    // Row i of P A is row pivots[i] of A
+    pivots: Vec<usize>,
+    swaps: usize,
+}
+
+impl<S> LuDecomposition<S>
+where S: Scalar
+{
+    // Solves A x = b
+    pub fn solve(&self, b: &[S]) -> Vec<S> {
+        let n = self.pivots.len();
+        assert_eq!(b.len(), n, "The right hand side's length must match the matrix.");
+        let mut x: Vec<S> = self.pivots.iter().map(|&p| b[p]).collect();
+        for i in 0..n {
+            let (solved, rest) = x.split_at_mut(i);
+            for (&l, &s) in self.lu.row(i)[..i].iter().zip(solved.iter()) {
+                rest[0] -= l * s;
+            }
+        }
+        for i in (0..n).rev() {
+            let (rest, solved) = x.split_at_mut(i + 1);
+            for (&u, &s) in self.lu.row(i)[i + 1..].iter().zip(solved.iter()) {
+                rest[i] -= u * s;
+            }
+            rest[i] /= self.lu[(i, i)];
+        }
+        x
+    }
+
+    pub fn determinant(&self) -> S {
+        let mut digest = if self.swaps % 2 == 0 { S::from(1.0) } else { S::from(-1.0) };
+        for i in 0..self.pivots.len() {
+            digest *= self.lu[(i, i)];
+        }
+        digest
+    }
+}
@@ -1,4 +1,5 @@
 
+use crate::math::approx::ApproxEq;
 use crate::math::scalar::Scalar;
 use super::spatial_vector::SpatialVector;
 
@@ -79,12 +80,41 @@ where S: Scalar,
     }
 }
 
+impl<S,V> ApproxEq for GeneralMatrix<S,V>
+where S: Scalar,
+      V: SpatialVector<S> + ApproxEq,
+{
+    fn abs_diff_eq(&self, rhs: &Self, epsilon: f64) -> bool {
+        self.matrix.len() == rhs.matrix.len()
+            && self.matrix.iter().zip(&rhs.matrix).all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn relative_eq(&self, rhs: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.matrix.len() == rhs.matrix.len()
+            && self.matrix.iter().zip(&rhs.matrix)
+                .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+    }
+
+    fn ulps_eq(&self, rhs: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.matrix.len() == rhs.matrix.len()
+            && self.matrix.iter().zip(&rhs.matrix).all(|(a, b)| a.ulps_eq(b, epsilon, max_ulps))
+    }
+
+    fn default_epsilon() -> f64 {
+        V::default_epsilon()
+    }
+
+    fn default_max_relative() -> f64 {
+        V::default_max_relative()
+    }
+}
+
 impl<S,V> ops::Mul<Self> for GeneralMatrix<S,V>
 where S: Scalar,
       V: SpatialVector<S>,
 {
     type Output = Self;
-    fn mul(self, rhs: Self) -> Self {
+    fn mul(self, _rhs: Self) -> Self {
         // TODO: Actually do this...
         GeneralMatrix::new()
     }
@@ -95,7 +125,7 @@ where S: Scalar,
       V: SpatialVector<S>,
 {
     type Output = Self;
-    fn mul(self, rhs: V) -> Self {
+    fn mul(self, _rhs: V) -> Self {
         // TODO: Actually do this...
         GeneralMatrix::new()
     }
@@ -105,12 +135,12 @@ impl<S,V> Matrix<S,V> for GeneralMatrix<S,V>
 where S: Scalar,
       V: SpatialVector<S>,
 {
-    fn constuct_col_matrix(vecs: &Vec<V>) -> Result<Self,&str> {
+    fn constuct_col_matrix(_vecs: &Vec<V>) -> Result<Self,&str> {
         // TODO: Actually do this...
         Ok( GeneralMatrix::new() )
     }
     
-    fn constuct_row_matrix(vecs: &Vec<V>) -> Result<Self,&str> {
+    fn constuct_row_matrix(_vecs: &Vec<V>) -> Result<Self,&str> {
         // TODO: Actually do this...
         Ok( GeneralMatrix::new() )
     }
@@ -168,12 +198,41 @@ where S: Scalar,
     }
 }
 
+impl<S,V> ApproxEq for SquareMatrix<S,V>
+where S: Scalar,
+      V: SpatialVector<S> + ApproxEq,
+{
+    fn abs_diff_eq(&self, rhs: &Self, epsilon: f64) -> bool {
+        self.matrix.len() == rhs.matrix.len()
+            && self.matrix.iter().zip(&rhs.matrix).all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn relative_eq(&self, rhs: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.matrix.len() == rhs.matrix.len()
+            && self.matrix.iter().zip(&rhs.matrix)
+                .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+    }
+
+    fn ulps_eq(&self, rhs: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.matrix.len() == rhs.matrix.len()
+            && self.matrix.iter().zip(&rhs.matrix).all(|(a, b)| a.ulps_eq(b, epsilon, max_ulps))
+    }
+
+    fn default_epsilon() -> f64 {
+        V::default_epsilon()
+    }
+
+    fn default_max_relative() -> f64 {
+        V::default_max_relative()
+    }
+}
+
 impl<S,V> ops::Mul<Self> for SquareMatrix<S,V>
 where S: Scalar,
       V: SpatialVector<S>,
 {
     type Output = Self;
-    fn mul(self, rhs: Self) -> Self {
+    fn mul(self, _rhs: Self) -> Self {
         // TODO: Actually do this...
         SquareMatrix::new()
     }
@@ -184,7 +243,7 @@ where S: Scalar,
       V: SpatialVector<S>,
 {
     type Output = Self;
-    fn mul(self, rhs: V) -> Self {
+    fn mul(self, _rhs: V) -> Self {
         // TODO: Actually do this...
         SquareMatrix::new()
     }
@@ -195,12 +254,12 @@ impl<S,V> Matrix<S,V> for SquareMatrix<S,V>
 where S: Scalar,
       V: SpatialVector<S>,
 {
-    fn constuct_col_matrix(vecs: &Vec<V>) -> Result<Self,&str> {
+    fn constuct_col_matrix(_vecs: &Vec<V>) -> Result<Self,&str> {
         // TODO: Actually do this...
         Ok( SquareMatrix::new() )
     }
     
-    fn constuct_row_matrix(vecs: &Vec<V>) -> Result<Self,&str> {
+    fn constuct_row_matrix(_vecs: &Vec<V>) -> Result<Self,&str> {
         // TODO: Actually do this...
         Ok( SquareMatrix::new() )
     }
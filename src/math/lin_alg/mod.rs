@@ -1,2 +1,3 @@
 pub mod spatial_vector;
 pub mod matrix;
+pub mod dense_matrix;
@@ -25,6 +25,13 @@ where
     // The dot ("inner") product
     fn dot(&self, rhs: &Self) -> S;
 
+    // The Hermitian inner product, which conjugates self first. It is the
+    // dot product for real vectors, which is all this default is right for;
+    // vectors that can hold complex numbers must conjugate their components.
+    fn hermitian_dot(&self, rhs: &Self) -> S {
+        self.dot(rhs)
+    }
+
     // The cross ("inner") product
     fn cross(&self, rhs: &Self) -> Self;
 
@@ -1,2 +1,11 @@
 pub mod scalar;
+pub mod approx;
+pub mod components;
 pub mod lin_alg;
+pub mod random;
+pub mod diff;
+pub mod dual;
+pub mod reverse;
+pub mod complex;
+pub mod interval;
+pub mod double_double;
@@ -0,0 +1,584 @@
+pub use crate::math::scalar::{Float, Real, Scalar};
+
+use crate::math::scalar::power;
+
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops;
+
+// The tape index of numbers that don't depend on any variable, which are
+// never recorded
+const CONSTANT: usize = usize::MAX;
+
+// One recorded operation: the tape indices of its operands, and the partial
+// derivative of its result by each of them
+#[derive(Clone, Copy)]
+struct Node<S>
+where S: Real {
+    parents: [(usize, S); 2],
+    // The generation it was recorded in, which tells it apart from the
+    // entries that were at its index before the tape was cut back
+    generation: u64,
+}
+
+// The tapes of the different Real types are kept together, so that they can
+// be measured and cleared without knowing the types
+trait Tape {
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+    fn as_any(&mut self) -> &mut dyn Any;
+}
+
+impl<S> Tape for Vec<Node<S>>
+where S: Real + 'static
+{
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+thread_local! {
+    static TAPES: RefCell<HashMap<TypeId, Box<dyn Tape>>> = RefCell::new(HashMap::new());
+    // The number of times the tapes have been cleared or cut back
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+// The generation of Vars whose tape entries are gone. Nothing is recorded in
+// it, as the count never gets that far.
+const STALE: u64 = u64::MAX;
+
+fn with_tape<S, R>(f: impl FnOnce(&mut Vec<Node<S>>) -> R) -> R
+where S: Real + 'static {
+    TAPES.with(|tapes| {
+        let mut tapes = tapes.borrow_mut();
+        let tape = tapes.entry(TypeId::of::<S>()).or_insert_with(|| Box::new(Vec::<Node<S>>::new()));
+        f(tape.as_any().downcast_mut().expect("Each type's tape is stored under its own TypeId."))
+    })
+}
+
+fn generation() -> u64 {
+    GENERATION.with(|generation| generation.get())
+}
+
+fn next_generation() {
+    GENERATION.with(|generation| generation.set(generation.get() + 1));
+}
+
+// Whether x's entry is still on the tape
+fn is_live<S>(tape: &[Node<S>], x: &Var<S>) -> bool
+where S: Real + 'static {
+    tape.get(x.index).is_some_and(|node| node.generation == x.generation)
+}
+
+// The number of operations recorded on this thread's tapes
+pub fn tape_length() -> usize {
+    TAPES.with(|tapes| tapes.borrow().values().map(|tape| tape.len()).sum())
+}
+
+// Forgets every recorded operation. Vars made before this can't be
+// differentiated afterwards, as their tape entries are gone, and their
+// gradients are errors.
+pub fn clear_tape() {
+    TAPES.with(|tapes| tapes.borrow_mut().values_mut().for_each(|tape| tape.clear()));
+    next_generation();
+}
+
+// A number whose arithmetic is recorded on a tape, for reverse mode automatic
+// differentiation: running generic Scalar code on Vars and then calling
+// gradient on the result gives its derivatives by every input in a single
+// backward pass, however many inputs there are. (Dual numbers need a pass
+// per input instead, but no tape.)
+//
+// A Var is just its value and a position on the tape, so it is Copy like any
+// other Scalar. The tapes belong to the thread, one for each type S, which
+// holds the partial derivatives in that type, so Vars can't be sent to
+// other threads. The tapes grow with every operation until they are
+// cleared; the gradient function below clears what it records.
+//
+// Vars compare by value alone.
+#[derive(Debug, Clone, Copy)]
+pub struct Var<S>
+where S: Real + 'static {
+    pub value: S,
+    index: usize,
+    // The generation its tape entry was recorded in, or STALE if it was
+    // computed from a Var whose entry was already gone
+    generation: u64,
+    // Not Send or Sync, as the index is into this thread's tape
+    thread: PhantomData<*const ()>,
+}
+
+impl<S> Var<S>
+where S: Real + 'static
+{
+    // An input that derivatives can be taken by
+    pub fn new(value: S) -> Self {
+        Self::recorded(value, [(Self::constant(S::zero()), S::zero()); 2])
+    }
+
+    pub fn constant(value: S) -> Self {
+        Var { value, index: CONSTANT, generation: generation(), thread: PhantomData }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.index == CONSTANT
+    }
+
+    // Records an operation on up to two operands, each with the derivative
+    // of the result by it. A result of a Var whose entry is gone is stale
+    // too, and isn't recorded.
+    fn recorded(value: S, operands: [(Self, S); 2]) -> Self {
+        let generation = generation();
+        with_tape(|tape: &mut Vec<Node<S>>| {
+            if operands.iter().any(|(x, _)| !x.is_constant() && !is_live(tape, x)) {
+                return Var { value, index: 0, generation: STALE, thread: PhantomData };
+            }
+            tape.push(Node { parents: operands.map(|(x, slope)| (x.index, slope)), generation });
+            Var { value, index: tape.len() - 1, generation, thread: PhantomData }
+        })
+    }
+
+    // A result with one operand, whose derivative by it is slope
+    fn unary(self, value: S, slope: S) -> Self {
+        if self.is_constant() {
+            return Self::constant(value);
+        }
+        Self::recorded(value, [(self, slope), (Self::constant(S::zero()), S::zero())])
+    }
+
+    // A result with two operands, with the derivative by each
+    fn binary(self, rhs: Self, value: S, slope: S, rhs_slope: S) -> Self {
+        if self.is_constant() && rhs.is_constant() {
+            return Self::constant(value);
+        }
+        Self::recorded(value, [(self, slope), (rhs, rhs_slope)])
+    }
+
+    pub fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            return Self::constant(S::from(1.0));
+        }
+        // value^(|n| - 1), which the derivative needs as well as the power
+        let power = power(self.value, n.unsigned_abs() - 1);
+        if n > 0 {
+            self.unary(power * self.value, S::from(n as f64) * power)
+        } else {
+            let value = (power * self.value).inv();
+            self.unary(value, S::from(n as f64) * value * self.value.inv())
+        }
+    }
+
+    // The derivatives of this by everything recorded before it, from one
+    // backward pass over the tape. This fails if the tape was cleared or
+    // cut back past this, or anything it was computed from, since it was
+    // recorded.
+    pub fn gradient(&self) -> Result<Adjoints<S>, &'static str> {
+        if self.is_constant() {
+            return Ok(Adjoints { values: Vec::new(), generations: Vec::new() });
+        }
+        // The nodes are copied off the tape first, as S may be recorded on a
+        // tape itself
+        let nodes: Vec<Node<S>> = with_tape(|tape: &mut Vec<Node<S>>| {
+            if is_live(tape, self) { tape[..=self.index].to_vec() } else { Vec::new() }
+        });
+        if nodes.is_empty() {
+            return Err("The tape was cleared after this was recorded, so it can't be differentiated.");
+        }
+        let mut values = vec![S::zero(); self.index + 1];
+        values[self.index] = S::one();
+        for i in (0..=self.index).rev() {
+            let adjoint = values[i];
+            if adjoint == S::zero() {
+                continue;
+            }
+            for &(parent, slope) in nodes[i].parents.iter() {
+                if parent != CONSTANT {
+                    values[parent] += adjoint * slope;
+                }
+            }
+        }
+        Ok(Adjoints { values, generations: nodes.iter().map(|node| node.generation).collect() })
+    }
+}
+
+// The derivatives of one Var by the Vars it was computed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adjoints<S>
+where S: Real + 'static {
+    values: Vec<S>,
+    // The generation of each entry the values were computed from
+    generations: Vec<u64>,
+}
+
+impl<S> Adjoints<S>
+where S: Real + 'static
+{
+    // The derivative by x, which is zero if the result doesn't depend on it,
+    // as it can't on a Var whose entry was gone first
+    pub fn wrt(&self, x: &Var<S>) -> S {
+        if self.generations.get(x.index) != Some(&x.generation) {
+            return S::zero();
+        }
+        self.values[x.index]
+    }
+}
+
+// The value and gradient of f at x. Everything f records is taken off the
+// tape again afterwards, so Vars that f keeps can't be differentiated later.
+// This fails if f's result depends on Vars whose tape entries are gone.
+pub fn gradient<S, F, const N: usize>(f: F, x: [S; N]) -> Result<(S, [S; N]), &'static str>
+where
+    S: Real + 'static,
+    F: FnOnce([Var<S>; N]) -> Var<S>,
+{
+    let start = with_tape(|tape: &mut Vec<Node<S>>| tape.len());
+    let inputs = x.map(Var::new);
+    let y = f(inputs);
+    let adjoints = y.gradient();
+    with_tape(|tape: &mut Vec<Node<S>>| tape.truncate(start));
+    // Entries recorded at these indices from now on are told apart from the
+    // ones just taken off
+    next_generation();
+    let adjoints = adjoints?;
+    Ok((y.value, inputs.map(|input| adjoints.wrt(&input))))
+}
+
+impl<S> fmt::Display for Var<S>
+where S: Real + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<S> cmp::PartialEq for Var<S>
+where S: Real + 'static
+{
+    fn eq(&self, rhs: &Self) -> bool {
+        self.value == rhs.value
+    }
+}
+
+impl<S> cmp::PartialOrd for Var<S>
+where S: Real + 'static
+{
+    fn partial_cmp(&self, rhs: &Self) -> Option<cmp::Ordering> {
+        self.value.partial_cmp(&rhs.value)
+    }
+}
+
+impl<S> ops::Neg for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.unary(S::zero() - self.value, S::from(-1.0))
+    }
+}
+
+impl<S> ops::Add for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let one = S::from(1.0);
+        self.binary(rhs, self.value + rhs.value, one, one)
+    }
+}
+
+impl<S> ops::Sub for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.binary(rhs, self.value - rhs.value, S::from(1.0), S::from(-1.0))
+    }
+}
+
+impl<S> ops::Mul for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        self.binary(rhs, self.value * rhs.value, rhs.value, self.value)
+    }
+}
+
+impl<S> ops::Div for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let inverse = rhs.value.inv();
+        let value = self.value * inverse;
+        self.binary(rhs, value, inverse, S::zero() - value * inverse)
+    }
+}
+
+// Arithmetic with plain numbers, which act as constants
+impl<S> ops::Add<S> for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn add(self, rhs: S) -> Self {
+        self.unary(self.value + rhs, S::from(1.0))
+    }
+}
+
+impl<S> ops::Sub<S> for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn sub(self, rhs: S) -> Self {
+        self.unary(self.value - rhs, S::from(1.0))
+    }
+}
+
+impl<S> ops::Mul<S> for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn mul(self, rhs: S) -> Self {
+        self.unary(self.value * rhs, rhs)
+    }
+}
+
+impl<S> ops::Div<S> for Var<S>
+where S: Real + 'static
+{
+    type Output = Self;
+    fn div(self, rhs: S) -> Self {
+        self.unary(self.value / rhs, rhs.inv())
+    }
+}
+
+impl<S> ops::AddAssign for Var<S>
+where S: Real + 'static
+{
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<S> ops::SubAssign for Var<S>
+where S: Real + 'static
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<S> ops::MulAssign for Var<S>
+where S: Real + 'static
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<S> ops::DivAssign for Var<S>
+where S: Real + 'static
+{
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<S> Scalar for Var<S>
+where S: Real + 'static
+{
+    fn from(a: f64) -> Self {
+        Self::constant(S::from(a))
+    }
+
+    fn sqrt(self) -> Self {
+        let root = self.value.sqrt();
+        self.unary(root, (S::from(2.0) * root).inv())
+    }
+
+    fn inv(self) -> Self {
+        let inverse = self.value.inv();
+        self.unary(inverse, S::zero() - inverse * inverse)
+    }
+
+    fn zero() -> Self {
+        Self::constant(S::zero())
+    }
+
+    fn magnitude(self) -> f64 {
+        self.value.magnitude()
+    }
+
+    fn precision() -> f64 {
+        S::precision()
+    }
+}
+
+impl<S> Real for Var<S>
+where S: Real + 'static
+{
+    // The derivative at zero is taken from the positive side
+    fn abs(self) -> Self {
+        if self.value < S::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.value > self.value {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.value < self.value {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn epsilon() -> Self {
+        Self::constant(S::epsilon())
+    }
+
+    fn to_f64(self) -> f64 {
+        self.value.to_f64()
+    }
+}
+
+impl<S> Float for Var<S>
+where S: Float + 'static
+{
+    fn pi() -> Self {
+        Self::constant(S::pi())
+    }
+
+    fn e() -> Self {
+        Self::constant(S::e())
+    }
+
+    fn infinity() -> Self {
+        Self::constant(S::infinity())
+    }
+
+    fn nan() -> Self {
+        Self::constant(S::nan())
+    }
+
+    fn is_nan(self) -> bool {
+        self.value.is_nan()
+    }
+
+    fn is_finite(self) -> bool {
+        self.value.is_finite()
+    }
+
+    // Steps are flat wherever they have a derivative
+    fn floor(self) -> Self {
+        self.unary(self.value.floor(), S::zero())
+    }
+
+    fn ceil(self) -> Self {
+        self.unary(self.value.ceil(), S::zero())
+    }
+
+    fn round(self) -> Self {
+        self.unary(self.value.round(), S::zero())
+    }
+
+    fn exp(self) -> Self {
+        let value = self.value.exp();
+        self.unary(value, value)
+    }
+
+    fn ln(self) -> Self {
+        self.unary(self.value.ln(), self.value.inv())
+    }
+
+    fn log10(self) -> Self {
+        self.unary(self.value.log10(), (self.value * S::from(10.0).ln()).inv())
+    }
+
+    // The derivative by the exponent is only taken when it is needed, so
+    // that negative numbers can still be raised to constant powers
+    fn powf(self, n: Self) -> Self {
+        let value = self.value.powf(n.value);
+        let slope = n.value * self.value.powf(n.value - S::one());
+        let n_slope = if n.is_constant() { S::zero() } else { value * self.value.ln() };
+        self.binary(n, value, slope, n_slope)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        Var::powi(self, n)
+    }
+
+    fn cbrt(self) -> Self {
+        let value = self.value.cbrt();
+        self.unary(value, (S::from(3.0) * value * value).inv())
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        let value = self.value.hypot(other.value);
+        self.binary(other, value, self.value / value, other.value / value)
+    }
+
+    fn sin(self) -> Self {
+        self.unary(self.value.sin(), self.value.cos())
+    }
+
+    fn cos(self) -> Self {
+        self.unary(self.value.cos(), S::zero() - self.value.sin())
+    }
+
+    fn tan(self) -> Self {
+        let value = self.value.tan();
+        self.unary(value, S::one() + value * value)
+    }
+
+    fn asin(self) -> Self {
+        self.unary(self.value.asin(), (S::one() - self.value * self.value).sqrt().inv())
+    }
+
+    fn acos(self) -> Self {
+        self.unary(self.value.acos(), S::zero() - (S::one() - self.value * self.value).sqrt().inv())
+    }
+
+    fn atan(self) -> Self {
+        self.unary(self.value.atan(), (S::one() + self.value * self.value).inv())
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let square = self.value * self.value + other.value * other.value;
+        self.binary(other, self.value.atan2(other.value), other.value / square, S::zero() - self.value / square)
+    }
+
+    fn sinh(self) -> Self {
+        self.unary(self.value.sinh(), self.value.cosh())
+    }
+
+    fn cosh(self) -> Self {
+        self.unary(self.value.cosh(), self.value.sinh())
+    }
+
+    fn tanh(self) -> Self {
+        let value = self.value.tanh();
+        self.unary(value, S::one() - value * value)
+    }
+}
@@ -21,6 +21,45 @@ where
     fn sqrt(self) -> Self;
     fn inv(self) -> Self;
     fn zero() -> Self;
+
+    fn one() -> Self {
+        Self::from(1.0)
+    }
+
+    // The size of the number as an f64, for things like choosing pivots,
+    // which need to compare numbers that may not be ordered themselves. It
+    // has no default, as nothing else turns a Scalar into an f64.
+    fn magnitude(self) -> f64;
+
+    // The machine epsilon of the floating point numbers underneath, as an
+    // f64. Approximate comparisons measure units in the last place with it.
+    // Types built on f64 can keep the default.
+    fn precision() -> f64 {
+        f64::EPSILON
+    }
+
+    // The complex conjugate, which does nothing to real numbers
+    fn conj(self) -> Self {
+        self
+    }
+}
+
+// x^n by repeated squaring, in O(log n) multiplications
+pub(crate) fn power<S>(x: S, n: u32) -> S
+where S: Scalar {
+    let mut digest = S::one();
+    let mut base = x;
+    let mut k = n;
+    while k > 0 {
+        if k % 2 == 1 {
+            digest *= base;
+        }
+        k /= 2;
+        if k > 0 {
+            base *= base;
+        }
+    }
+    digest
 }
 
 impl Scalar for f32 {
@@ -39,6 +78,14 @@ impl Scalar for f32 {
     fn zero() -> Self {
         0.0_f32
     }
+
+    fn magnitude(self) -> f64 {
+        self.abs() as f64
+    }
+
+    fn precision() -> f64 {
+        f32::EPSILON as f64
+    }
 }
 
 impl Scalar for f64 {
@@ -57,4 +104,224 @@ impl Scalar for f64 {
     fn zero() -> Self {
         0.0_f64
     }
+
+    fn magnitude(self) -> f64 {
+        self.abs()
+    }
+}
+
+// Scalars that live on the real line. Unlike a general Scalar, these can be
+// ordered, which is what adaptive algorithms need for their tolerance checks.
+pub trait Real
+where
+    Self: Scalar,
+    Self: cmp::PartialOrd,
+{
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    // The difference between 1.0 and the next representable value
+    fn epsilon() -> Self;
+    // The nearest f64, for computations that need functions Scalar doesn't have
+    fn to_f64(self) -> f64;
+}
+
+impl Real for f32 {
+    fn abs(self) -> Self {
+        self.abs()
+    }
+
+    fn max(self, other: Self) -> Self {
+        self.max(other)
+    }
+
+    fn min(self, other: Self) -> Self {
+        self.min(other)
+    }
+
+    fn epsilon() -> Self {
+        f32::EPSILON
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
 }
+
+impl Real for f64 {
+    fn abs(self) -> Self {
+        self.abs()
+    }
+
+    fn max(self, other: Self) -> Self {
+        self.max(other)
+    }
+
+    fn min(self, other: Self) -> Self {
+        self.min(other)
+    }
+
+    fn epsilon() -> Self {
+        f64::EPSILON
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+// Reals with the elementary functions and constants of floating point
+// numbers, for things like rotations and special functions that can't be
+// built from arithmetic alone. Automatic differentiation types are Floats
+// too, so generic code written against this trait can still be
+// differentiated exactly.
+pub trait Float
+where Self: Real {
+    fn pi() -> Self;
+    // Euler's number
+    fn e() -> Self;
+    fn infinity() -> Self;
+    fn nan() -> Self;
+
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+
+    fn exp(self) -> Self;
+    // The natural logarithm
+    fn ln(self) -> Self;
+    fn log10(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn cbrt(self) -> Self;
+    // sqrt(self^2 + other^2), without overflow in the squares
+    fn hypot(self, other: Self) -> Self;
+
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan(self) -> Self;
+    // The angle of the point (other, self), in (-pi, pi]
+    fn atan2(self, other: Self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn tanh(self) -> Self;
+}
+
+// f32 and f64 have every one of these as an inherent method
+macro_rules! impl_float {
+    ($t:ident) => {
+        impl Float for $t {
+            fn pi() -> Self {
+                std::$t::consts::PI
+            }
+
+            fn e() -> Self {
+                std::$t::consts::E
+            }
+
+            fn infinity() -> Self {
+                $t::INFINITY
+            }
+
+            fn nan() -> Self {
+                $t::NAN
+            }
+
+            fn is_nan(self) -> bool {
+                self.is_nan()
+            }
+
+            fn is_finite(self) -> bool {
+                self.is_finite()
+            }
+
+            fn floor(self) -> Self {
+                self.floor()
+            }
+
+            fn ceil(self) -> Self {
+                self.ceil()
+            }
+
+            fn round(self) -> Self {
+                self.round()
+            }
+
+            fn exp(self) -> Self {
+                self.exp()
+            }
+
+            fn ln(self) -> Self {
+                self.ln()
+            }
+
+            fn log10(self) -> Self {
+                self.log10()
+            }
+
+            fn powf(self, n: Self) -> Self {
+                self.powf(n)
+            }
+
+            fn powi(self, n: i32) -> Self {
+                self.powi(n)
+            }
+
+            fn cbrt(self) -> Self {
+                self.cbrt()
+            }
+
+            fn hypot(self, other: Self) -> Self {
+                self.hypot(other)
+            }
+
+            fn sin(self) -> Self {
+                self.sin()
+            }
+
+            fn cos(self) -> Self {
+                self.cos()
+            }
+
+            fn tan(self) -> Self {
+                self.tan()
+            }
+
+            fn asin(self) -> Self {
+                self.asin()
+            }
+
+            fn acos(self) -> Self {
+                self.acos()
+            }
+
+            fn atan(self) -> Self {
+                self.atan()
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                self.atan2(other)
+            }
+
+            fn sinh(self) -> Self {
+                self.sinh()
+            }
+
+            fn cosh(self) -> Self {
+                self.cosh()
+            }
+
+            fn tanh(self) -> Self {
+                self.tanh()
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
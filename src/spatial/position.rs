@@ -2,6 +2,8 @@ pub use super::direction::Direction;
 pub use crate::math::scalar::Scalar;
 pub use crate::math::lin_alg::spatial_vector::SpatialVector;
 
+use crate::math::approx::ApproxEq;
+
 use std::cmp;
 use std::fmt;
 use std::marker::PhantomData;
@@ -105,6 +107,32 @@ where
     }
 }
 
+impl<S, V> ApproxEq for Position<S, V>
+where
+    S: Scalar,
+    V: SpatialVector<S> + ApproxEq,
+{
+    fn abs_diff_eq(&self, rhs: &Self, epsilon: f64) -> bool {
+        self.v.abs_diff_eq(&rhs.v, epsilon)
+    }
+
+    fn relative_eq(&self, rhs: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.v.relative_eq(&rhs.v, epsilon, max_relative)
+    }
+
+    fn ulps_eq(&self, rhs: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.v.ulps_eq(&rhs.v, epsilon, max_ulps)
+    }
+
+    fn default_epsilon() -> f64 {
+        V::default_epsilon()
+    }
+
+    fn default_max_relative() -> f64 {
+        V::default_max_relative()
+    }
+}
+
 impl<S, V> SpatialVector<S> for Position<S, V>
 where
     S: Scalar,
@@ -126,11 +154,15 @@ where
         self.v.dot(&rhs.v)
     }
 
+    fn hermitian_dot(&self, rhs: &Self) -> S {
+        self.v.hermitian_dot(&rhs.v)
+    }
+
     fn cross(&self, rhs: &Self) -> Self {
         Position::new(self.v.cross(&(rhs.v)))
     }
 
-    fn scale(&mut self, rhs: S) -> () {
+    fn scale(&mut self, rhs: S) {
         self.v.scale(rhs)
     }
 
@@ -1,6 +1,8 @@
 pub use crate::math::scalar::Scalar;
 pub use crate::math::lin_alg::spatial_vector::SpatialVector;
 
+use crate::math::approx::ApproxEq;
+
 use std::cmp;
 use std::fmt;
 use std::ops;
@@ -104,6 +106,37 @@ where
         (self.x == rhs.x) && (self.y == rhs.y) && (self.z == rhs.z)
     }
 }
+
+impl<S> ApproxEq for Vector3D<S>
+where
+    S: Scalar,
+{
+    fn abs_diff_eq(&self, rhs: &Self, epsilon: f64) -> bool {
+        self.x.abs_diff_eq(&rhs.x, epsilon)
+            && self.y.abs_diff_eq(&rhs.y, epsilon)
+            && self.z.abs_diff_eq(&rhs.z, epsilon)
+    }
+
+    fn relative_eq(&self, rhs: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.x.relative_eq(&rhs.x, epsilon, max_relative)
+            && self.y.relative_eq(&rhs.y, epsilon, max_relative)
+            && self.z.relative_eq(&rhs.z, epsilon, max_relative)
+    }
+
+    fn ulps_eq(&self, rhs: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.x.ulps_eq(&rhs.x, epsilon, max_ulps)
+            && self.y.ulps_eq(&rhs.y, epsilon, max_ulps)
+            && self.z.ulps_eq(&rhs.z, epsilon, max_ulps)
+    }
+
+    fn default_epsilon() -> f64 {
+        S::default_epsilon()
+    }
+
+    fn default_max_relative() -> f64 {
+        S::default_max_relative()
+    }
+}
 impl<S> SpatialVector<S> for Vector3D<S>
 where
     S: Scalar,
@@ -124,6 +157,10 @@ where
         self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
     }
 
+    fn hermitian_dot(&self, rhs: &Self) -> S {
+        self.x.conj() * rhs.x + self.y.conj() * rhs.y + self.z.conj() * rhs.z
+    }
+
     fn cross(&self, rhs: &Self) -> Self {
         Vector3D {
             x: self.y * rhs.z - self.z * rhs.y,
@@ -139,21 +176,28 @@ where
         self.z *= rhs;
     }
 
-    // Calculates the vector norm
+    // Calculates the vector norm, which is real even for complex vectors
     fn length(&self) -> S {
-        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+        self.hermitian_dot(self).sqrt()
     }
 
     // Normalizes the vector
     fn normalize(&mut self) -> Result<(),&str> {
         let l = self.length();
-        // TODO: Direct comparisions are not a good idea in the case of
-        // floats... there needs to be a better check here.
-        if l == S::zero() {
-            Err("This vector has length zero, and can't be normalized.")
-        } else {
-            self.scale(l.inv());
-            Ok(())
+        // Lengths below the smallest normal f64 have lost their digits, so
+        // count as zero
+        if l.abs_diff_eq(&S::zero(), f64::MIN_POSITIVE) {
+            return Err("This vector has length zero, and can't be normalized.");
+        }
+        if !l.magnitude().is_finite() {
+            return Err("This vector's length is infinite or NaN, and can't be normalized.");
+        }
+        // A length whose inverse overflows, such as an interval that may be
+        // zero, can't be scaled back to one either
+        if !l.inv().magnitude().is_finite() {
+            return Err("This vector's length is too near zero, and can't be normalized.");
         }
+        self.scale(l.inv());
+        Ok(())
     }
 }
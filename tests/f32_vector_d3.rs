@@ -1,27 +1,8 @@
 #[cfg(test)]
 mod tests {
+    use quartz::assert_relative_eq;
     use quartz::spatial::vector_3d::{SpatialVector, Vector3D};
 
-    fn approx_eq(v1: &Vector3D<f32>, v2: &Vector3D<f32>, thres: f32) -> bool {
-        let mut digest = true;
-        if v1.x == 0.0 {
-            digest &= v2.x == 0.0;
-        } else {
-            digest &= ((v1.x - v2.x) / (v1.x)).abs() <= thres;
-        }
-        if v1.y == 0.0 {
-            digest &= v2.y == 0.0;
-        } else {
-            digest &= ((v1.y - v2.y) / (v1.y)).abs() <= thres;
-        }
-        if v1.z == 0.0 {
-            digest &= v2.z == 0.0;
-        } else {
-            digest &= ((v1.z - v2.z) / (v1.z)).abs() <= thres;
-        }
-        digest
-    }
-
     #[test]
     fn create_vector() {
         let v1 = Vector3D::new(1.0, 1.0, 1.0);
@@ -179,29 +160,31 @@ mod tests {
             z: 1.0,
         };
         assert!( v2.normalize().is_ok() );
-        assert!(approx_eq(
-            &v2,
-            &Vector3D {
+        assert_relative_eq!(
+            v2,
+            Vector3D {
                 x: (1.0 / 3.0_f32).sqrt(),
                 y: (1.0 / 3.0_f32).sqrt(),
                 z: (1.0 / 3.0_f32).sqrt()
             },
-            0.000000001
-        ));
+            epsilon = 0.0,
+            max_relative = 0.000000001
+        );
         let mut v3 = Vector3D {
             x: 1.0,
             y: 2.0,
             z: 3.0,
         };
         assert!( v3.normalize().is_ok() );
-        assert!(approx_eq(
-            &v3,
-            &Vector3D {
+        assert_relative_eq!(
+            v3,
+            Vector3D {
                 x: 1.0 / 14.0_f32.sqrt(),
                 y: 2.0 / 14.0_f32.sqrt(),
                 z: 3.0 / 14.0_f32.sqrt()
             },
-            0.0000000001
-        ));
+            epsilon = 0.0,
+            max_relative = 0.0000000001
+        );
     }
 }
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use quartz::math::approx::ApproxEq;
+    use quartz::math::complex::Complex;
+    use quartz::math::dual::Dual;
+    use quartz::math::lin_alg::dense_matrix::DenseMatrix;
+    use quartz::spatial::direction::Direction;
+    use quartz::spatial::vector_3d::{SpatialVector, Vector3D};
+    use quartz::{assert_abs_diff_eq, assert_relative_eq, assert_ulps_eq};
+
+    #[test]
+    fn test_scalars() {
+        // 0.1 + 0.2 is one unit in the last place above 0.3
+        let sum = 0.1 + 0.2;
+        assert!(sum != 0.3 && sum.approx_eq(&0.3));
+        assert!(sum.ulps_eq(&0.3, 0.0, 1) && !sum.ulps_eq(&0.3, 0.0, 0));
+        assert!(1.0.abs_diff_eq(&1.1, 0.11) && !1.0.abs_diff_eq(&1.1, 0.09));
+        assert!(1e10.relative_eq(&(1e10 + 1.0), 0.0, 1e-9));
+        assert!(!1e-10.relative_eq(&2e-10, 0.0, 0.1) && 1e-10.relative_eq(&2e-10, 1e-9, 0.1));
+        // Units in the last place straddling a power of two
+        assert!(1.0.ulps_eq(&(1.0 - f64::EPSILON / 2.0), 0.0, 1));
+        assert!(2.0f64.ulps_eq(&(2.0 + 3.0 * 2.0 * f64::EPSILON), 0.0, 3));
+        assert!(!(-1e-300).ulps_eq(&1e-300, 0.0, u32::MAX));
+        // f32 works in its own precision
+        assert!((0.1f32 + 0.2).approx_eq(&0.3) && 1.0f32.ulps_eq(&1.0000001, 0.0, 1));
+        assert!(!1.0f64.ulps_eq(&1.0000001, 0.0, 1));
+        assert!(f64::INFINITY.approx_eq(&f64::INFINITY) && !f64::NAN.approx_eq(&f64::NAN));
+        assert!(!f64::INFINITY.approx_eq(&f64::MAX));
+        // Complex numbers are compared by their distance apart, duals by value
+        let z = Complex::new(3.0, 4.0);
+        assert!(z.relative_eq(&Complex::new(3.0, 4.0 + 4e-9), 0.0, 1e-9));
+        assert!(!z.relative_eq(&Complex::new(3.0, 4.0 + 6e-9), 0.0, 1e-9));
+        assert!(Dual::new(1.0, [2.0]).approx_eq(&Dual::new(1.0, [3.0])));
+        assert_ulps_eq!(sum, 0.3);
+        assert_abs_diff_eq!(z, Complex::new(3.0, 4.0 + 1e-12), epsilon = 1e-11);
+        assert_relative_eq!(1e10, 1e10 + 1.0, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_vectors_and_matrices() {
+        let v = Vector3D::new(1.0, 0.0, 1e-20);
+        let w = Vector3D::new(1.0 + 1e-15, 1e-20, 0.0);
+        assert!(v != w && v.abs_diff_eq(&w, 1e-14) && !v.relative_eq(&w, 0.0, 1e-14));
+        assert!(!v.abs_diff_eq(&Vector3D::new(1.0, 0.0, 1e-13), 1e-14));
+        assert_relative_eq!(Direction::new(v), Direction::new(w), epsilon = 1e-19, max_relative = 1e-14);
+        let a = DenseMatrix::from_rows(&[vec![4.0, 3.0], vec![6.0, 3.0]]).unwrap();
+        let x = DenseMatrix::from_rows(&[a.solve(&[10.0, 12.0]).unwrap()]).unwrap();
+        assert_ulps_eq!(x, DenseMatrix::from_rows(&[vec![1.0, 2.0]]).unwrap(), epsilon = 1e-15, max_ulps = 4);
+        assert!(!DenseMatrix::<f64>::zeros(2, 3).approx_eq(&DenseMatrix::zeros(3, 2)));
+    }
+
+    #[test]
+    fn test_normalize() {
+        // Lengths that underflow or overflow can't be normalized
+        let mut tiny = Vector3D::new(1e-150, 0.0, 0.0);
+        assert!(tiny.normalize().is_ok());
+        assert_eq!(tiny, Vector3D::new(1.0, 0.0, 0.0));
+        let zero = Err("This vector has length zero, and can't be normalized.");
+        let unbounded = Err("This vector's length is infinite or NaN, and can't be normalized.");
+        assert_eq!(Vector3D::new(1e-170, 0.0, 0.0).normalize(), zero);
+        assert_eq!(Vector3D::new(1e-30f32, 0.0, 0.0).normalize(), zero);
+        assert_eq!(Vector3D::new(1e200, 0.0, 0.0).normalize(), unbounded);
+        assert_eq!(Vector3D::new(f64::NAN, 1.0, 1.0).normalize(), unbounded);
+        let mut v = Vector3D::new(1e-15f32, 2e-15, 2e-15);
+        assert!(v.normalize().is_ok());
+        assert_relative_eq!(v, Vector3D::new(1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0), max_relative = 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left relative_eq right` failed")]
+    fn test_failed_assertion() {
+        assert_relative_eq!(Vector3D::new(1.0, 2.0, 3.0), Vector3D::new(1.0, 2.0, 3.1));
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::ode::bvp::{BvpError, BvpSolver};
+    use quartz::integrate::ode::shooting::ShootingSolver;
+    use std::f64::consts::PI;
+
+    fn mesh(a: f64, b: f64, nodes: usize) -> Vec<f64> {
+        (0..nodes).map(|i| a + (b - a) * i as f64 / (nodes - 1) as f64).collect()
+    }
+
+    // y'' = -y with y(0) = 0 and y(pi / 2) = 1, so y = sin(x)
+    fn sine(_: f64, y: &[f64; 2]) -> [f64; 2] {
+        [y[1], -y[0]]
+    }
+
+    fn sine_bc(ya: &[f64; 2], yb: &[f64; 2]) -> [f64; 2] {
+        [ya[0], yb[0] - 1.0]
+    }
+
+    #[test]
+    fn test_linear() {
+        let mut solver = BvpSolver::new();
+        solver.tol = 1e-6;
+        let x = mesh(0.0, PI / 2.0, 5);
+        let solution = solver.solve(sine, sine_bc, x, vec![[0.0, 0.0]; 5]).unwrap();
+        assert_eq!(solution.x[0], 0.0);
+        assert_eq!(*solution.x.last().unwrap(), PI / 2.0);
+        assert!(solution.residuals.iter().all(|&r| r <= 1e-6));
+        for i in 0..=20 {
+            let x = PI / 2.0 * i as f64 / 20.0;
+            let y = solution.interpolate(x).unwrap();
+            assert_relative_eq!(y[0], x.sin(), epsilon = 1e-6, max_relative = 1e-6);
+            assert_relative_eq!(y[1], x.cos(), epsilon = 1e-6, max_relative = 1e-6);
+        }
+        assert_eq!(solution.interpolate(-0.1), None);
+        assert_eq!(solution.interpolate(2.0), None);
+    }
+
+    #[test]
+    fn test_beam_deflection() {
+        // A simply supported beam of length 1 under a uniform load, with
+        // EI y'''' = q: the deflection and bending moment vanish at both ends
+        let q = 1.0;
+        let beam = |_: f64, y: &[f64; 4]| [y[1], y[2], y[3], q];
+        let bc = |ya: &[f64; 4], yb: &[f64; 4]| [ya[0], ya[2], yb[0], yb[2]];
+        let exact = |x: f64| q * x * (1.0 - 2.0 * x * x + x * x * x) / 24.0;
+        let solution = BvpSolver::new().solve(beam, bc, mesh(0.0, 1.0, 5), vec![[0.0; 4]; 5]).unwrap();
+        // The collocation solution is exact at the nodes, and the cubic
+        // interpolant is close to the quartic between them
+        for (&x, y) in solution.x.iter().zip(solution.y.iter()) {
+            assert!((y[0] - exact(x)).abs() < 1e-14);
+        }
+        for i in 0..=10 {
+            let x = i as f64 / 10.0;
+            assert!((solution.interpolate(x).unwrap()[0] - exact(x)).abs() < 1e-3 * exact(0.5));
+        }
+    }
+
+    #[test]
+    fn test_heat_fin() {
+        // A fin with an insulated tip: T'' = m^2 (T - T_inf), with the base
+        // held at T_b and no heat flow through the tip
+        let (m, t_inf, t_b, length) = (4.0, 20.0, 100.0, 0.5);
+        let fin = move |_: f64, y: &[f64; 2]| [y[1], m * m * (y[0] - t_inf)];
+        let bc = move |ya: &[f64; 2], yb: &[f64; 2]| [ya[0] - t_b, yb[1]];
+        let exact = |x: f64| t_inf + (t_b - t_inf) * (m * (length - x)).cosh() / (m * length).cosh();
+
+        let mut solver = BvpSolver::new();
+        solver.tol = 1e-8;
+        let solution = solver.solve(fin, bc, mesh(0.0, length, 3), vec![[t_b, 0.0]; 3]).unwrap();
+        let shot = ShootingSolver::new().solve(fin, bc, 0.0, length, [t_b, 0.0]).unwrap();
+        assert_relative_eq!(shot.final_state()[0], exact(length), epsilon = 1e-8, max_relative = 1e-8);
+        for i in 0..=10 {
+            let x = length * i as f64 / 10.0;
+            assert_relative_eq!(solution.interpolate(x).unwrap()[0], exact(x), epsilon = 1e-7, max_relative = 1e-7);
+            assert_relative_eq!(shot.interpolate(x).unwrap()[0], exact(x), epsilon = 1e-7, max_relative = 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_nonlinear() {
+        // Bratu's problem y'' + e^y = 0, y(0) = y(1) = 0, which has two
+        // solutions. Starting from zero finds the lower one.
+        let bratu = |_: f64, y: &[f64; 2]| [y[1], -y[0].exp()];
+        let bc = |ya: &[f64; 2], yb: &[f64; 2]| [ya[0], yb[0]];
+        let mut solver = BvpSolver::new();
+        solver.tol = 1e-6;
+        let solution = solver.solve(bratu, bc, mesh(0.0, 1.0, 5), vec![[0.0, 0.0]; 5]).unwrap();
+        let shot = ShootingSolver::new().solve(bratu, bc, 0.0, 1.0, [0.0, 0.0]).unwrap();
+        assert_relative_eq!(solution.y[0][1], 0.5493527, epsilon = 1e-6, max_relative = 1e-6);
+        assert_relative_eq!(shot.states[0][1], solution.y[0][1], epsilon = 1e-6, max_relative = 1e-6);
+        // The upper solution, from a guess near it
+        let x = mesh(0.0, 1.0, 11);
+        let guess = x.iter().map(|&x| [4.0 * (PI * x).sin(), 4.0 * PI * (PI * x).cos()]).collect();
+        let upper = solver.solve(bratu, bc, x, guess).unwrap();
+        assert_relative_eq!(upper.y[0][1], 10.8469, epsilon = 1e-4, max_relative = 1e-4);
+        assert!(upper.y.last().unwrap()[0].abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mesh_refinement() {
+        // A boundary layer: e y'' = y, y(0) = 1, y(1) = 0, whose solution is
+        // close to exp(-x / sqrt(e))
+        let e = 1e-3;
+        let layer = move |_: f64, y: &[f64; 2]| [y[1], y[0] / e];
+        let bc = |ya: &[f64; 2], yb: &[f64; 2]| [ya[0] - 1.0, yb[0]];
+        let guess = vec![[0.0, 0.0]; 5];
+        let solution = BvpSolver::new().solve(layer, bc, mesh(0.0, 1.0, 5), guess.clone()).unwrap();
+        assert!(solution.x.len() > 5);
+        assert!(solution.iterations > 1);
+        assert!(solution.x.windows(2).all(|w| w[1] > w[0]));
+        for x in [0.01, 0.05, 0.1, 0.2] {
+            assert!((solution.interpolate(x).unwrap()[0] - (-x / e.sqrt()).exp()).abs() < 1e-3);
+        }
+
+        let mut solver = BvpSolver::new();
+        solver.max_nodes = 10;
+        assert_eq!(solver.solve(layer, bc, mesh(0.0, 1.0, 5), guess), Err(BvpError::TooManyNodes { nodes: 10 }));
+    }
+
+    #[test]
+    fn test_coupled_boundaries() {
+        // y = sin(x) + 2 cos(x), from conditions that mix both ends, and from
+        // conditions at the right end alone
+        let exact = |x: f64| [x.sin() + 2.0 * x.cos(), x.cos() - 2.0 * x.sin()];
+        let (ya, yb) = (exact(0.0), exact(1.0));
+        let mixed = |a: &[f64; 2], b: &[f64; 2]| [a[0] + 2.0 * b[0] - ya[0] - 2.0 * yb[0], a[1] - b[1] - ya[1] + yb[1]];
+        let terminal = |_: &[f64; 2], b: &[f64; 2]| [b[0] - yb[0], b[1] - yb[1]];
+        let mut solver = BvpSolver::new();
+        solver.tol = 1e-6;
+        for solution in [solver.solve(sine, mixed, mesh(0.0, 1.0, 6), vec![[0.0, 0.0]; 6]), solver.solve(sine, terminal, mesh(0.0, 1.0, 6), vec![[0.0, 0.0]; 6])] {
+            let solution = solution.unwrap();
+            for (&x, y) in solution.x.iter().zip(solution.y.iter()) {
+                assert_relative_eq!(y[0], exact(x)[0], epsilon = 1e-6, max_relative = 1e-6);
+                assert_relative_eq!(y[1], exact(x)[1], epsilon = 1e-6, max_relative = 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let solver = BvpSolver::new();
+        assert_eq!(solver.solve(sine, sine_bc, vec![0.0], vec![[0.0, 0.0]]), Err(BvpError::InvalidMesh));
+        assert_eq!(solver.solve(sine, sine_bc, vec![0.0, 1.0], vec![[0.0, 0.0]]), Err(BvpError::InvalidMesh));
+        assert_eq!(solver.solve(sine, sine_bc, vec![1.0, 0.0], vec![[0.0, 0.0]; 2]), Err(BvpError::InvalidMesh));
+        // One condition for a second order system
+        let vector_sine = |_: f64, y: &Vec<f64>| vec![y[1], -y[0]];
+        let short = |ya: &Vec<f64>, _: &Vec<f64>| vec![ya[0]];
+        let count = Err(BvpError::BoundaryConditionCount { expected: 2, found: 1 });
+        assert_eq!(solver.solve(vector_sine, short, mesh(0.0, 1.0, 3), vec![vec![0.0, 0.0]; 3]).map(|_| ()), count);
+        assert_eq!(ShootingSolver::new().solve(vector_sine, short, 0.0, 1.0, vec![0.0, 0.0]).map(|_| ()), count);
+        // y(0) = 0 and y(pi) = 1 can't both hold, as every solution through
+        // the origin is a multiple of sin(x)
+        let impossible = |ya: &[f64; 2], yb: &[f64; 2]| [ya[0], yb[0] - 1.0];
+        let mut shooting = ShootingSolver::new();
+        shooting.max_iterations = 5;
+        assert_eq!(shooting.solve(sine, impossible, 0.0, PI, [0.0, 0.0]).map(|_| ()), Err(BvpError::NotConverged { iterations: 5 }));
+    }
+}
@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::ode::runge_kutta::{RungeKuttaMethod, RungeKuttaSolver};
+    use quartz::integrate::one_d::gauss_kronrod_integrator::GaussKronrodIntegrator;
+    use quartz::integrate::one_d::integrator::Integrator;
+    use quartz::integrate::one_d::romberg_integrator::RombergIntegrator;
+    use quartz::integrate::one_d::simpson_integrator::SimpsonIntegrator;
+    use quartz::integrate::one_d::tanh_sinh_integrator::TanhSinhIntegrator;
+    use quartz::integrate::one_d::trapezoid_integrator::TrapezoidIntegrator;
+    use quartz::math::complex::{Complex, Scalar};
+    use quartz::math::lin_alg::dense_matrix::DenseMatrix;
+    use quartz::math::lin_alg::spatial_vector::SpatialVector;
+    use quartz::spatial::vector_3d::Vector3D;
+    use std::f64::consts::PI;
+
+    fn c(re: f64, im: f64) -> Complex<f64> {
+        Complex::new(re, im)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (c(3.0, 4.0), c(1.0, -2.0));
+        assert_eq!(a + b, c(4.0, 2.0));
+        assert_eq!(a - b, c(2.0, 6.0));
+        assert_eq!(a * b, c(11.0, -2.0));
+        assert_relative_eq!(a / b, c(-1.0, 2.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(a * a.inv(), c(1.0, 0.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_eq!(a.conj(), c(3.0, -4.0));
+        assert_eq!(a.modulus(), 5.0);
+        assert_eq!(a.norm_sqr(), 25.0);
+        assert_eq!(Complex::<f64>::i() * Complex::i(), c(-1.0, 0.0));
+        assert_relative_eq!(a.powi(3), a * a * a, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(a.powi(-2), (a * a).inv(), epsilon = 1e-15, max_relative = 1e-15);
+        assert_eq!(a * 2.0 - 1.0, c(5.0, 8.0));
+        assert_eq!(format!("{}", b), "1 - 2i");
+        assert_eq!(format!("{}", a), "3 + 4i");
+        // Division and the modulus survive parts that would overflow squared
+        let huge = c(1e300, 1e300);
+        assert_relative_eq!(huge / huge, c(1.0, 0.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(huge.modulus() / 1e300, 2f64.sqrt(), epsilon = 1e-15, max_relative = 1e-15);
+    }
+
+    #[test]
+    fn test_polar_and_roots() {
+        let z = Complex::from_polar(2.0, PI / 3.0);
+        assert_relative_eq!(z.modulus(), 2.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(z.argument(), PI / 3.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(c(-1.0, 0.0).argument(), PI, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(c(0.0, -1.0).argument(), -PI / 2.0, epsilon = 1e-15, max_relative = 1e-15);
+        // Principal square roots, with the cut along the negative reals
+        assert_eq!(c(-4.0, 0.0).sqrt(), c(0.0, 2.0));
+        assert_eq!(c(0.0, 0.0).sqrt(), c(0.0, 0.0));
+        assert_relative_eq!(c(0.0, 2.0).sqrt(), c(1.0, 1.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(c(-3.0, -4.0).sqrt(), c(1.0, -2.0), epsilon = 1e-15, max_relative = 1e-15);
+        // Parts near the largest f64 don't overflow on the way
+        assert_relative_eq!(c(1e308, 0.0).sqrt(), c(1e154, 0.0), max_relative = 1e-15);
+        let root = c(-1e308, 1.0).sqrt();
+        assert_relative_eq!(root.re, 5e-155, max_relative = 1e-15);
+        assert_relative_eq!(root.im, 1e154, max_relative = 1e-15);
+        let root = c(1e308, -1e308).sqrt();
+        assert_relative_eq!(root * root, c(1e308, -1e308), max_relative = 1e-15);
+        // Euler's identity, and the principal logarithm and powers
+        assert_relative_eq!(c(0.0, PI).exp(), c(-1.0, 0.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(c(-1.0, 0.0).ln(), c(0.0, PI), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(c(1.0, 2.0).ln().exp(), c(1.0, 2.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(Complex::i().powf(Complex::i()), c((-PI / 2.0).exp(), 0.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(c(3.0, 4.0).powf(c(0.5, 0.0)), c(3.0, 4.0).sqrt(), epsilon = 1e-15, max_relative = 1e-15);
+        assert_eq!(c(0.0, 0.0).powf(c(2.0, 0.0)), c(0.0, 0.0));
+        for z in [c(2.0, 3.0), c(-2.0, 3.0), c(-2.0, -3.0), c(1e-200, -1e-200)] {
+            let root = z.sqrt();
+            assert!(root.re >= 0.0);
+            assert_relative_eq!(root * root, z, epsilon = 1e-15, max_relative = 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_linear_algebra() {
+        // An AC circuit: a source V drives R1 into a node joined to ground by
+        // a capacitor C and, through R2, an inductor L. The mesh currents
+        // solve Z I = V with complex impedances.
+        let omega = 2.0 * PI * 50.0;
+        let (r1, r2, cap, ind) = (10.0, 5.0, 100e-6, 50e-3);
+        let zc = c(0.0, -1.0 / (omega * cap));
+        let zl = c(r2, omega * ind);
+        let z = DenseMatrix::from_rows(&[vec![c(r1, 0.0) + zc, -zc], vec![-zc, zc + zl]]).unwrap();
+        let v = [c(230.0, 0.0), c(0.0, 0.0)];
+        let currents = z.solve(&v).unwrap();
+        let residual = z.mul_vec(&currents);
+        assert_relative_eq!(residual[0], v[0], epsilon = 1e-13, max_relative = 1e-13);
+        assert_relative_eq!(residual[1], v[1], epsilon = 1e-13, max_relative = 1e-13);
+        // The total impedance seen by the source
+        let total = c(r1, 0.0) + zc * zl / (zc + zl);
+        assert_relative_eq!(currents[0], v[0] / total, epsilon = 1e-13, max_relative = 1e-13);
+        assert_relative_eq!(z.lu().unwrap().determinant(), (c(r1, 0.0) + zc) * (zc + zl) - zc * zc, epsilon = 1e-13, max_relative = 1e-13);
+
+        // A Hermitian matrix equals its adjoint
+        let h = DenseMatrix::from_rows(&[vec![c(2.0, 0.0), c(1.0, -1.0)], vec![c(1.0, 1.0), c(3.0, 0.0)]]).unwrap();
+        assert_eq!(h.adjoint(), h);
+        assert_ne!(h.transpose(), h);
+    }
+
+    #[test]
+    fn test_vectors() {
+        let u = Vector3D::new(c(1.0, 1.0), c(0.0, 2.0), c(3.0, 0.0));
+        let v = Vector3D::new(c(0.0, 1.0), c(1.0, 0.0), c(2.0, -1.0));
+        // <u, u> is |u|^2 = 2 + 4 + 9, while the plain dot product isn't real
+        assert_eq!(u.hermitian_dot(&u), c(15.0, 0.0));
+        assert_relative_eq!(u.length(), c(15f64.sqrt(), 0.0), epsilon = 1e-15, max_relative = 1e-15);
+        assert_eq!(u.dot(&u), c(5.0, 2.0));
+        assert_eq!(u.hermitian_dot(&v), v.hermitian_dot(&u).conj());
+        let mut n = u;
+        n.normalize().unwrap();
+        assert_relative_eq!(n.length(), c(1.0, 0.0), epsilon = 1e-15, max_relative = 1e-15);
+        // Real vectors are unchanged
+        let r = Vector3D::new(1.0, 2.0, 2.0);
+        assert_eq!(r.hermitian_dot(&r), r.dot(&r));
+        assert_eq!(r.length(), 3.0);
+    }
+
+    #[test]
+    fn test_integration() {
+        // Integrals along straight paths in the complex plane: exp(iz) from 0
+        // to pi is 2i, and z^2 from 0 to 1 + i is (1 + i)^3 / 3
+        let i = Complex::<f64>::i();
+        let (zero, pi, corner) = (Complex::zero(), Complex::new(PI, 0.0), Complex::new(1.0, 1.0));
+        let wave = |z: Complex<f64>| (i * z).exp();
+        let square = |z: Complex<f64>| z * z;
+        let (two_i, cube) = (Complex::new(0.0, 2.0), corner.powi(3) / Complex::new(3.0, 0.0));
+
+        let trapezoid = TrapezoidIntegrator::new().integrate_func(wave, zero, pi, 1000).unwrap();
+        assert_relative_eq!(trapezoid, two_i, max_relative = 1e-5);
+        let simpson = SimpsonIntegrator::new();
+        assert_relative_eq!(simpson.integrate_func(square, zero, corner, 4).unwrap(), cube, max_relative = 1e-14);
+        let samples: Vec<Complex<f64>> = (0..=4).map(|k| square(corner * Complex::new(k as f64 / 4.0, 0.0))).collect();
+        assert_relative_eq!(simpson.integrate_data(&samples, corner / Complex::new(4.0, 0.0)).unwrap(), cube, max_relative = 1e-14);
+
+        let gauss_kronrod = GaussKronrodIntegrator::new().integrate(wave, zero, pi);
+        assert!(gauss_kronrod.converged);
+        assert_relative_eq!(gauss_kronrod.value, two_i, max_relative = 1e-10);
+        let romberg = RombergIntegrator::new().integrate(wave, zero, pi);
+        assert!(romberg.converged);
+        assert_relative_eq!(romberg.value, two_i, max_relative = 1e-8);
+        let tanh_sinh = TanhSinhIntegrator::new().integrate(square, zero, corner);
+        assert!(tanh_sinh.converged);
+        assert_relative_eq!(tanh_sinh.value, cube, max_relative = 1e-10);
+    }
+
+    #[test]
+    fn test_schrodinger() {
+        // A two level system driven on resonance, i dpsi/dt = H psi with
+        // H = (Omega / 2) sigma_x, Rabi oscillates: the excited population
+        // is sin^2(Omega t / 2)
+        let omega = 2.0;
+        let minus_i = c(0.0, -1.0);
+        let schrodinger = |_: f64, psi: &[Complex<f64>; 2]| [minus_i * psi[1] * (omega / 2.0), minus_i * psi[0] * (omega / 2.0)];
+        let mut solver = RungeKuttaSolver::new(RungeKuttaMethod::Rk45);
+        solver.rtol = 1e-10;
+        solver.atol = 1e-12;
+        let solution = solver.solve(schrodinger, 0.0, 3.0, [c(1.0, 0.0), c(0.0, 0.0)]).unwrap();
+        for (&t, psi) in solution.times.iter().zip(solution.states.iter()) {
+            assert_relative_eq!(psi[1].norm_sqr(), (omega * t / 2.0).sin().powi(2), epsilon = 1e-8, max_relative = 1e-8);
+            assert_relative_eq!(psi[0].norm_sqr() + psi[1].norm_sqr(), 1.0, epsilon = 1e-8, max_relative = 1e-8);
+        }
+    }
+}
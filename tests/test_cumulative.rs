@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::one_d::integrator::{CumulativeIntegrator, IntegrationError};
+    use quartz::integrate::one_d::simpson_integrator::SimpsonIntegrator;
+    use quartz::integrate::one_d::trapezoid_integrator::TrapezoidIntegrator;
+
+    #[test]
+    fn test_trapezoid() {
+        let integrator = TrapezoidIntegrator::new();
+        assert_eq!(integrator.cumulative_data(&[1.0, 3.0, 5.0], 0.5, None), Ok(vec![1.0, 3.0]));
+        assert_eq!(integrator.cumulative_data(&[1.0, 3.0, 5.0], 0.5, Some(10.0)), Ok(vec![10.0, 11.0, 13.0]));
+        assert_eq!(integrator.cumulative_data(&[1.0], 0.5, None), Ok(vec![]));
+        assert_eq!(integrator.cumulative_data(&[], 0.5, Some(1.0)), Err(IntegrationError::EmptyInput));
+        assert_eq!(
+            integrator.cumulative_data_time_series(&[0.0, 1.0, 3.0], &[2.0, 2.0, 4.0], Some(1.0)),
+            Ok(vec![1.0, 3.0, 9.0])
+        );
+    }
+
+    #[test]
+    fn test_simpson_exact_for_quadratics() {
+        let vals: Vec<f64> = (0..6).map(|i| (i * i) as f64 * 0.25).collect();
+        let cumulative = SimpsonIntegrator::new().cumulative_data(&vals, 0.5, None).unwrap();
+        assert_eq!(cumulative.len(), 5);
+        for (i, &c) in cumulative.iter().enumerate() {
+            let x = (i + 1) as f64 * 0.5;
+            assert_relative_eq!(c, x * x * x / 3.0, epsilon = 1e-12, max_relative = 1e-12);
+        }
+        let times = [0.0, 0.2, 0.9, 1.0, 1.7];
+        let vals: Vec<f64> = times.iter().map(|&t| 3.0 * t * t + 1.0).collect();
+        let cumulative = SimpsonIntegrator::new().cumulative_data_time_series(&times, &vals, Some(2.0)).unwrap();
+        for (&t, &c) in times.iter().zip(cumulative.iter()) {
+            assert_relative_eq!(c, t * t * t + t + 2.0, epsilon = 1e-12, max_relative = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_acceleration_to_position() {
+        // Constant acceleration of 2 from rest at x = 1
+        let accel = [2.0; 11];
+        let integrator = SimpsonIntegrator::new();
+        let velocity = integrator.cumulative_data(&accel, 0.1, Some(0.0)).unwrap();
+        let position = integrator.cumulative_data(&velocity, 0.1, Some(1.0)).unwrap();
+        assert_relative_eq!(velocity[10], 2.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(position[10], 2.0, epsilon = 1e-12, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_simpson_short_and_invalid() {
+        let integrator = SimpsonIntegrator::new();
+        assert_eq!(integrator.cumulative_data(&[1.0, 3.0], 1.0, None), Ok(vec![2.0]));
+        assert_eq!(
+            integrator.cumulative_data_time_series(&[0.0, 1.0], &[1.0], None),
+            Err(IntegrationError::LengthMismatch { times: 2, vals: 1 })
+        );
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::ode::dde::{DdeSolver, OdeError, RungeKuttaMethod};
+
+    // y'(t) = -y(t - 1) with y = 1 before t = 0, solved by the method of
+    // steps: a polynomial of one degree higher on each unit interval
+    fn exact(t: f64) -> f64 {
+        if t <= 1.0 {
+            1.0 - t
+        } else if t <= 2.0 {
+            1.0 - t + (t - 1.0) * (t - 1.0) / 2.0
+        } else {
+            let u = t - 2.0;
+            -0.5 + u * u / 2.0 - u * u * u / 6.0
+        }
+    }
+
+    #[test]
+    fn test_constant_delay() {
+        let mut solver = DdeSolver::new(RungeKuttaMethod::Rk45);
+        solver.rtol = 1e-8;
+        solver.atol = 1e-10;
+        let solution = solver.solve(|_, _: &f64, lagged: &[f64]| -lagged[0], &[1.0], |_| 1.0, 0.0, 3.0).unwrap();
+        assert_eq!(solution.final_time(), 3.0);
+        assert_relative_eq!(*solution.final_state(), exact(3.0), epsilon = 1e-8, max_relative = 1e-8);
+        // The steps land on the discontinuities in the derivatives
+        assert!(solution.times.contains(&1.0));
+        assert!(solution.times.contains(&2.0));
+        for (&t, &y) in solution.times.iter().zip(solution.states.iter()) {
+            assert_relative_eq!(y, exact(t), epsilon = 1e-8, max_relative = 1e-8);
+        }
+        for i in 0..=30 {
+            let t = i as f64 / 10.0;
+            assert_relative_eq!(solution.interpolate(t).unwrap(), exact(t), epsilon = 1e-6, max_relative = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_delayed_logistic() {
+        // Hutchinson's equation y' = r y(t) (1 - y(t - tau)) oscillates about
+        // 1 once r tau > pi / 2, with a period of about 4 tau
+        let (r, tau) = (2.0, 1.0);
+        let f = move |_: f64, y: &[f64; 1], lagged: &[[f64; 1]]| [r * y[0] * (1.0 - lagged[0][0])];
+        let solution = DdeSolver::new(RungeKuttaMethod::Rk45).solve(f, &[tau], |_| [0.5], 0.0, 60.0).unwrap();
+        let late: Vec<f64> = solution.times.iter().zip(solution.states.iter()).filter(|(&t, _)| t > 30.0).map(|(_, y)| y[0]).collect();
+        let (low, high) = late.iter().fold((f64::INFINITY, 0.0f64), |(lo, hi), &y| (lo.min(y), hi.max(y)));
+        assert!(low > 0.0 && low < 0.5);
+        assert!(high > 1.5);
+        // The lagged values match the dense output of the solution itself
+        let y = |t: f64| solution.interpolate(t).unwrap()[0];
+        let derivative = (y(40.0 + 1e-4) - y(40.0 - 1e-4)) / 2e-4;
+        assert_relative_eq!(derivative, r * y(40.0) * (1.0 - y(40.0 - tau)), epsilon = 1e-2, max_relative = 1e-2);
+    }
+
+    #[test]
+    fn test_state_dependent_delay() {
+        // The pantograph equation y'(t) = y(t / 2), y(0) = 1, has the series
+        // solution sum t^n / (n! 2^(n (n - 1) / 2))
+        let series = |t: f64| {
+            let (mut sum, mut term) = (0.0, 1.0);
+            for n in 0..30 {
+                sum += term;
+                term *= t / ((n + 1) as f64 * 2f64.powi(n));
+            }
+            sum
+        };
+        let mut solver = DdeSolver::new(RungeKuttaMethod::Rk45);
+        solver.rtol = 1e-8;
+        solver.atol = 1e-10;
+        let solution = solver.solve_state_dependent(|_, _: &f64, lagged: &[f64]| lagged[0], |t, _: &f64| vec![t / 2.0], |_| 1.0, 0.0, 2.0).unwrap();
+        assert_relative_eq!(*solution.final_state(), series(2.0), epsilon = 1e-7, max_relative = 1e-7);
+
+        // A constant delay given as state dependent gives the same solution
+        let constant = solver.solve_state_dependent(|_, _: &f64, lagged: &[f64]| -lagged[0], |_, _: &f64| vec![1.0], |_| 1.0, 0.0, 3.0).unwrap();
+        assert_relative_eq!(*constant.final_state(), exact(3.0), epsilon = 1e-6, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn test_commensurate_delays() {
+        // 0.1 + 0.2 rounds to just above 0.3, which mustn't leave a breakpoint
+        // too close to 0.3 to step between them
+        let f = |_: f64, _: &f64, lagged: &[f64]| -lagged[0] - lagged[1] - lagged[2];
+        let solution = DdeSolver::new(RungeKuttaMethod::Dop853).solve(f, &[0.1, 0.2, 0.3], |_| 1.0, 0.0, 2.0).unwrap();
+        assert_eq!(*solution.times.last().unwrap(), 2.0);
+        assert!(solution.times.windows(2).all(|w| w[1] - w[0] > 1e-10));
+    }
+
+    #[test]
+    fn test_errors() {
+        let solver = DdeSolver::new(RungeKuttaMethod::Rk45);
+        let f = |_: f64, _: &f64, lagged: &[f64]| -lagged[0];
+        assert_eq!(solver.solve(f, &[0.0], |_| 1.0, 0.0, 1.0).map(|_| ()), Err(OdeError::InvalidDelay { delay: 0.0 }));
+        assert_eq!(solver.solve(f, &[1.0, -2.0], |_| 1.0, 0.0, 1.0).map(|_| ()), Err(OdeError::InvalidDelay { delay: -2.0 }));
+        let advanced = solver.solve_state_dependent(f, |t, _: &f64| vec![1.0 - t], |_| 1.0, 0.0, 2.0);
+        assert!(matches!(advanced, Err(OdeError::InvalidDelay { .. })));
+        let fixed = DdeSolver::new(RungeKuttaMethod::Rk4);
+        assert_eq!(fixed.solve(f, &[1.0], |_| 1.0, 0.0, 1.0).map(|_| ()), Err(OdeError::NotAdaptive));
+        assert_eq!(solver.solve(f, &[1.0], |_| 1.0, 1.0, 0.0).map(|_| ()), Err(OdeError::Backwards { t0: 1.0, t1: 0.0 }));
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use quartz::math::lin_alg::dense_matrix::DenseMatrix;
+
+    fn example() -> DenseMatrix<f64> {
+        DenseMatrix::from_rows(&[vec![2.0, 1.0, 1.0], vec![4.0, -6.0, 0.0], vec![-2.0, 7.0, 2.0]]).unwrap()
+    }
+
+    #[test]
+    fn test_construction() {
+        let m = example();
+        assert_eq!(m.size(), (3, 3));
+        assert_eq!(m[(1, 0)], 4.0);
+        assert_eq!(m.row(2), &[-2.0, 7.0, 2.0]);
+        assert_eq!(m.transpose()[(0, 1)], 4.0);
+        assert!(DenseMatrix::from_rows(&[vec![1.0, 2.0], vec![3.0]]).is_err());
+        let rectangular = DenseMatrix::from_rows(&[vec![1.0, 2.0, 3.0]]).unwrap();
+        assert_eq!(rectangular.size(), (1, 3));
+        assert_eq!(format!("{}", rectangular), "[ 1, 2, 3 ]");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let m = example();
+        assert_eq!(m.clone() * DenseMatrix::identity(3), m);
+        assert_eq!(m.mul_vec(&[1.0, 1.0, 1.0]), vec![4.0, -2.0, 7.0]);
+        let product = m.transpose() * m.clone();
+        assert_eq!(product, product.transpose());
+        assert_eq!((m.clone() + m.clone())[(2, 1)], 14.0);
+        assert_eq!((m.clone() - m.clone() * 2.0)[(0, 0)], -2.0);
+    }
+
+    #[test]
+    fn test_lu() {
+        let m = example();
+        let lu = m.lu().unwrap();
+        assert!((lu.determinant() - -16.0).abs() < 1e-12);
+        let x = lu.solve(&[5.0, -2.0, 9.0]);
+        for (a, b) in x.iter().zip([1.0, 1.0, 2.0].iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert_eq!(m.mul_vec(&m.solve(&[1.0, 2.0, 3.0]).unwrap()).len(), 3);
+        let singular = DenseMatrix::from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        assert!(singular.lu().is_err());
+        assert!(DenseMatrix::<f64>::zeros(2, 3).lu().is_err());
+    }
+}
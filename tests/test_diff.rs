@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::math::diff::{stencil, DiffError, DifferenceScheme, Differentiator, FiniteDifference, RichardsonDifferentiator};
+    use quartz::spatial::vector_3d::Vector3D;
+
+    #[test]
+    fn test_stencils() {
+        let (offsets, weights) = stencil(DifferenceScheme::Central, 1, 2).unwrap();
+        assert_eq!(offsets, vec![-1.0, 0.0, 1.0]);
+        assert_eq!(weights, vec![-0.5, 0.0, 0.5]);
+        let (_, weights) = stencil(DifferenceScheme::Central, 2, 4).unwrap();
+        let expected = [-1.0 / 12.0, 4.0 / 3.0, -5.0 / 2.0, 4.0 / 3.0, -1.0 / 12.0];
+        for (&w, &e) in weights.iter().zip(expected.iter()) {
+            assert_relative_eq!(w, e, epsilon = 1e-14, max_relative = 1e-14);
+        }
+        let (offsets, weights) = stencil(DifferenceScheme::Forward, 1, 2).unwrap();
+        assert_eq!(offsets, vec![0.0, 1.0, 2.0]);
+        assert_eq!(weights, vec![-1.5, 2.0, -0.5]);
+        let (offsets, weights) = stencil(DifferenceScheme::Backward, 1, 1).unwrap();
+        assert_eq!(offsets, vec![0.0, -1.0]);
+        assert_eq!(weights, vec![1.0, -1.0]);
+        // Every stencil differentiates constants to zero
+        let (_, weights) = stencil(DifferenceScheme::Forward, 3, 3).unwrap();
+        assert!(weights.iter().sum::<f64>().abs() < 1e-12);
+
+        assert_eq!(stencil(DifferenceScheme::Central, 0, 2), Err(DiffError::InvalidOrder { order: 0 }));
+        assert_eq!(stencil(DifferenceScheme::Central, 1, 3), Err(DiffError::InvalidAccuracy { accuracy: 3 }));
+        assert_eq!(stencil(DifferenceScheme::Forward, 1, 0), Err(DiffError::InvalidAccuracy { accuracy: 0 }));
+    }
+
+    #[test]
+    fn test_finite_differences() {
+        let x = 0.7;
+        for (scheme, accuracy, tol) in [
+            (DifferenceScheme::Central, 2, 1e-9),
+            (DifferenceScheme::Central, 6, 1e-12),
+            (DifferenceScheme::Forward, 1, 1e-7),
+            (DifferenceScheme::Forward, 4, 1e-10),
+            (DifferenceScheme::Backward, 2, 1e-9),
+        ] {
+            let diff = FiniteDifference::new(scheme, accuracy);
+            assert_relative_eq!(diff.derivative(f64::sin, x).unwrap(), x.cos(), epsilon = tol, max_relative = tol);
+        }
+        let diff = FiniteDifference::new(DifferenceScheme::Central, 4);
+        assert_relative_eq!(diff.derivative_of(f64::exp, 1.0, 2).unwrap(), 1f64.exp(), epsilon = 1e-6, max_relative = 1e-6);
+        assert_relative_eq!(diff.derivative_of(f64::sin, 0.3, 3).unwrap(), -(0.3f64.cos()), epsilon = 1e-4, max_relative = 1e-4);
+        // A fixed step that is too large shows the truncation error
+        let mut coarse = FiniteDifference::new(DifferenceScheme::Central, 2);
+        coarse.step = Some(0.1);
+        let error = coarse.derivative(f64::exp, 0.0).unwrap() - 1.0;
+        assert_relative_eq!(error, 0.01 / 6.0, epsilon = 1e-2, max_relative = 1e-2);
+    }
+
+    #[test]
+    fn test_richardson() {
+        let ridders = RichardsonDifferentiator::new();
+        for x in [0.0, 1.0, 5.0, 10.0] {
+            let result = ridders.estimate(|t: f64| t.exp() * t.sin(), x, 1).unwrap();
+            let exact = x.exp() * (x.sin() + x.cos());
+            assert_relative_eq!(result.value, exact, epsilon = 1e-10, max_relative = 1e-10);
+            assert!((result.value - exact).abs() <= 10.0 * result.abs_error.max(1e-15 * exact.abs()));
+        }
+        // Far beyond any fixed step
+        let result = ridders.estimate(|t: f64| 1.0 / t, 0.5, 2).unwrap();
+        assert_relative_eq!(result.value, 16.0, epsilon = 1e-9, max_relative = 1e-9);
+        assert!(result.converged);
+        assert!(result.evaluations > 0);
+        // A one sided tableau, for functions defined only on one side
+        let mut one_sided = RichardsonDifferentiator::new();
+        one_sided.scheme = DifferenceScheme::Forward;
+        one_sided.initial_step = 0.005;
+        assert_relative_eq!(one_sided.derivative(f64::sqrt, 0.01).unwrap(), 5.0, epsilon = 1e-7, max_relative = 1e-7);
+        // Values with several components are extrapolated together
+        let both = ridders.estimate(|t: f64| [t.sin(), t.cos()], 0.4, 1).unwrap();
+        assert_relative_eq!(both.value[0], 0.4f64.cos(), epsilon = 1e-10, max_relative = 1e-10);
+        assert_relative_eq!(both.value[1], -(0.4f64.sin()), epsilon = 1e-10, max_relative = 1e-10);
+    }
+
+    #[test]
+    fn test_gradient() {
+        // The Coulomb potential -1 / r has gradient r / |r|^3
+        let potential = |r: &Vector3D<f64>| -1.0 / (r.x * r.x + r.y * r.y + r.z * r.z).sqrt();
+        let r = Vector3D::new(1.0f64, -2.0, 0.5);
+        let cube = (r.x * r.x + r.y * r.y + r.z * r.z).powf(1.5);
+        let finite = FiniteDifference::default().gradient(potential, &r).unwrap();
+        let adaptive = RichardsonDifferentiator::new().gradient(potential, &r).unwrap();
+        for (g, e) in [(finite.x, r.x), (finite.y, r.y), (finite.z, r.z)] {
+            assert_relative_eq!(g, e / cube, epsilon = 1e-9, max_relative = 1e-9);
+        }
+        for (g, e) in [(adaptive.x, r.x), (adaptive.y, r.y), (adaptive.z, r.z)] {
+            assert_relative_eq!(g, e / cube, epsilon = 1e-11, max_relative = 1e-11);
+        }
+        let rosenbrock = |p: &Vec<f64>| (1.0 - p[0]).powi(2) + 100.0 * (p[1] - p[0] * p[0]).powi(2);
+        let minimum = FiniteDifference::default().gradient(rosenbrock, &vec![1.0, 1.0]).unwrap();
+        assert!(minimum.iter().all(|g| g.abs() < 1e-7));
+    }
+
+    #[test]
+    fn test_jacobian() {
+        // Polar to Cartesian coordinates, whose Jacobian has determinant r
+        let polar = |p: &[f64; 2]| [p[0] * p[1].cos(), p[0] * p[1].sin(), p[0]];
+        let (r, theta) = (2.0, 0.6);
+        for jacobian in [
+            FiniteDifference::default().jacobian(polar, &[r, theta]).unwrap(),
+            RichardsonDifferentiator::new().jacobian(polar, &[r, theta]).unwrap(),
+        ] {
+            assert_eq!(jacobian.size(), (3, 2));
+            let expected = [[theta.cos(), -r * theta.sin()], [theta.sin(), r * theta.cos()], [1.0, 0.0]];
+            for (i, row) in expected.iter().enumerate() {
+                for (j, &e) in row.iter().enumerate() {
+                    assert_relative_eq!(jacobian[(i, j)], e, epsilon = 1e-9, max_relative = 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hessian() {
+        // f = x^2 y + e^(y z) + sin(x z)
+        let f = |p: &Vector3D<f64>| p.x * p.x * p.y + (p.y * p.z).exp() + (p.x * p.z).sin();
+        let p = Vector3D::new(0.5f64, 1.0, -0.8);
+        let (x, y, z) = (p.x, p.y, p.z);
+        let e = (y * z).exp();
+        let s = (x * z).sin();
+        let c = (x * z).cos();
+        let expected = [
+            [2.0 * y - z * z * s, 2.0 * x, c - x * z * s],
+            [2.0 * x, z * z * e, e + y * z * e],
+            [c - x * z * s, e + y * z * e, y * y * e - x * x * s],
+        ];
+        let finite = FiniteDifference::default().hessian(f, &p).unwrap();
+        let adaptive = RichardsonDifferentiator::new().hessian(f, &p).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_relative_eq!(finite[(i, j)], expected[i][j], epsilon = 1e-6, max_relative = 1e-6);
+                assert_relative_eq!(adaptive[(i, j)], expected[i][j], epsilon = 1e-8, max_relative = 1e-8);
+                assert_eq!(finite[(i, j)], finite[(j, i)]);
+            }
+        }
+        let odd = FiniteDifference::new(DifferenceScheme::Central, 3);
+        assert_eq!(odd.hessian(f, &p), Err(DiffError::InvalidAccuracy { accuracy: 3 }));
+    }
+}
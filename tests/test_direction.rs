@@ -1,28 +1,9 @@
 #[cfg(test)]
 mod tests {
+    use quartz::assert_relative_eq;
     use quartz::spatial::direction::{Direction, SpatialVector};
     use quartz::spatial::vector_3d::Vector3D;
 
-    fn approx_eq(v1: &Vector3D<f64>, v2: &Vector3D<f64>, thres: f64) -> bool {
-        let mut digest = true;
-        if v1.x == 0.0 {
-            digest &= v2.x == 0.0;
-        } else {
-            digest &= ((v1.x - v2.x) / (v1.x)).abs() <= thres;
-        }
-        if v1.y == 0.0 {
-            digest &= v2.y == 0.0;
-        } else {
-            digest &= ((v1.y - v2.y) / (v1.y)).abs() <= thres;
-        }
-        if v1.z == 0.0 {
-            digest &= v2.z == 0.0;
-        } else {
-            digest &= ((v1.z - v2.z) / (v1.z)).abs() <= thres;
-        }
-        digest
-    }
-
     #[test]
     fn create_vector() {
         let v1 = Direction::new(Vector3D::new(1.0, 1.0, 1.0));
@@ -49,6 +30,14 @@ mod tests {
         assert_eq!(v1, Direction::new(v2));
     }
 
+    #[test]
+    fn subtract_vectors() {
+        let v1 = Direction::new(Vector3D::new(1.0, 2.0, 3.0));
+        let v2 = Direction::new(Vector3D::new(3.0, 2.0, 1.0));
+        assert_eq!(v1 - v2, Direction::new(Vector3D::new(-2.0, 0.0, 2.0)));
+        assert_eq!(v1 - v1, Direction::zero());
+    }
+
     #[test]
     fn scale_vector() {
         let mut v = Direction::new(Vector3D {
@@ -180,31 +169,31 @@ mod tests {
             z: 1.0,
         });
         assert!( v2.normalize().is_ok() );
-        assert!(approx_eq(
-            &v2.v,
-            &Direction::new(Vector3D {
+        assert_relative_eq!(
+            v2,
+            Direction::new(Vector3D {
                 x: 1.0 / 3.0_f64.sqrt(),
                 y: 1.0 / 3.0_f64.sqrt(),
                 z: 1.0 / 3.0_f64.sqrt()
-            })
-            .v,
-            0.000000001
-        ));
+            }),
+            epsilon = 0.0,
+            max_relative = 0.000000001
+        );
         let mut v3 = Direction::new(Vector3D {
             x: 1.0,
             y: 2.0,
             z: 3.0,
         });
         assert!( v3.normalize().is_ok() );
-        assert!(approx_eq(
-            &v3.v,
-            &Direction::new(Vector3D {
+        assert_relative_eq!(
+            v3,
+            Direction::new(Vector3D {
                 x: 1.0 / 14.0_f64.sqrt(),
                 y: 2.0 / 14.0_f64.sqrt(),
                 z: 3.0 / 14.0_f64.sqrt()
-            })
-            .v,
-            0.0000000001
-        ));
+            }),
+            epsilon = 0.0,
+            max_relative = 0.0000000001
+        );
     }
 }
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::one_d::gaussian_quadrature::{GaussFamily, GaussianQuadrature};
+    use quartz::math::double_double::{DoubleDouble, Float, Real, Scalar};
+    use quartz::math::lin_alg::dense_matrix::DenseMatrix;
+    use quartz::spatial::vector_3d::{SpatialVector, Vector3D};
+
+    type DD = DoubleDouble;
+
+    fn dd(a: f64) -> DD {
+        <DD as Scalar>::from(a)
+    }
+
+    // Pi to 106 bits
+    fn pi() -> DD {
+        DD::new(std::f64::consts::PI, 1.2246467991473532e-16)
+    }
+
+    fn error(a: DD, b: DD) -> f64 {
+        (a - b).abs().to_f64()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // What f64 rounds away is kept
+        let tiny = 2f64.powi(-80);
+        assert_eq!((dd(1.0) + dd(tiny) - dd(1.0)).to_f64(), tiny);
+        // The f64s nearest 0.1 and 0.2 add to 2^-55 below the f64 nearest 0.3
+        let sum = dd(0.1) + dd(0.2);
+        assert_eq!((sum.hi, sum.lo), (0.1 + 0.2, -(2f64.powi(-55))));
+        assert!(error(dd(1.0) / dd(3.0) * dd(3.0), dd(1.0)) < 1e-31);
+        assert!(error(dd(2.0).sqrt().sqr(), dd(2.0)) < 1e-31);
+        assert!(error(dd(10.0).powi(-3) * dd(1000.0), dd(1.0)) < 1e-31);
+        assert_eq!(dd(9.0).sqrt(), dd(3.0));
+        assert!(dd(-1.0).sqrt().hi.is_nan());
+        // The low part orders numbers with the same high part
+        assert!(dd(1.0) < dd(1.0) + dd(tiny) && DD::new(1.0, -tiny) < dd(1.0));
+        assert_eq!((dd(1.0) + dd(tiny)).to_f64(), 1.0);
+        assert_eq!(dd(f64::MAX) * dd(2.0), dd(f64::INFINITY));
+        assert_eq!(DD::new(2.5, 0.0).floor(), dd(2.0));
+        assert_eq!(DD::new(3.0, -tiny).floor(), dd(2.0));
+    }
+
+    #[test]
+    fn test_functions() {
+        // Against values to 80 digits at the same f64 arguments, rounded to
+        // double-doubles
+        let relative = |a: DD, (hi, lo): (f64, f64)| error(a, DD::new(hi, lo)) / hi.abs();
+        let cases = [
+            (DD::pi(), (std::f64::consts::PI, 1.2246467991473532e-16)),
+            (DD::e(), (std::f64::consts::E, 1.4456468917292502e-16)),
+            (dd(1.0).exp(), (std::f64::consts::E, 1.4456468917292502e-16)),
+            (dd(0.7).exp(), (2.0137527074704766, -2.0058243549764793e-16)),
+            (dd(-20.5).exp(), (1.2501528663867426e-09, 6.448235878237776e-26)),
+            (dd(2.0).ln(), (std::f64::consts::LN_2, 2.3190468138462996e-17)),
+            (dd(3.0).ln(), (1.0986122886681098, -9.07129723500153e-17)),
+            (dd(7.0).log10(), (0.8450980400142568, 1.7965820250441286e-17)),
+            (dd(1.0).sin(), (0.8414709848078965, 1.776845092935536e-18)),
+            (dd(1.0).cos(), (0.5403023058681398, -4.760954612604417e-17)),
+            (dd(10.0).sin(), (-0.5440211108893698, -3.8949898668223557e-17)),
+            (dd(1e10).sin(), (-0.4875060250875107, -1.665199285246269e-17)),
+            (dd(1e15 + 0.3).cos(), (-0.7095798744366656, -2.8367411645067895e-18)),
+            (dd(2f64.powi(60)).sin(), (-0.8306492176372546, -1.7357801136213062e-17)),
+            (dd(0.5).tan(), (0.5463024898437905, 2.9096576216837176e-17)),
+            (dd(0.5).atan(), (0.4636476090008061, 2.2698777452961687e-17)),
+            (dd(0.3).asin(), (0.3046926540153975, -2.7469740051157017e-17)),
+            (dd(-0.8).acos(), (2.498091544796509, 6.962246070911915e-17)),
+            (dd(2.0).cbrt(), (1.2599210498948732, -2.589933375300507e-17)),
+            (dd(2.0).powf(dd(0.5)), (std::f64::consts::SQRT_2, -9.667293313452913e-17)),
+            (dd(3.0).powf(dd(1.5)), (5.196152422706632, -1.4303668319585554e-16)),
+            (dd(0.01).sinh(), (0.010000166667500003, -5.644562789430375e-19)),
+            (dd(2.0).cosh(), (3.7621956910836314, 7.146584908813439e-17)),
+            (dd(0.3).tanh(), (0.2913126124515909, -6.4602656586469586e-18)),
+        ];
+        for (value, expected) in cases {
+            assert!(relative(value, expected) < 1e-30, "{} against {:?}", value, expected);
+        }
+        // Beyond 2^53 the error left in tau grows with the angle
+        assert!(relative(dd(1e22).sin(), (-0.8522008497671888, -6.7806825896773284e-18)) < 1e-26);
+        assert!(relative(dd(1e22).cos(), (0.523214785395139, -4.7143201076575164e-17)) < 1e-26);
+        // Inverses and identities
+        let x = dd(1.0) / dd(3.0);
+        assert!(error(x.exp().ln(), x) < 1e-31);
+        assert!(error((dd(1.0) + dd(1e-20)).ln(), dd(1e-20) - dd(5e-41)) < 1e-50);
+        assert!(error(x.sin().sqr() + x.cos().sqr(), dd(1.0)) < 1e-31);
+        assert!(error(x.tan().atan(), x) < 1e-31);
+        assert!(error(dd(1.0).atan() * dd(4.0), pi()) < 1e-31);
+        assert!(error(dd(-1.0).atan2(dd(-1.0)), pi() * dd(-0.75)) < 1e-31);
+        assert!(error(dd(3.0).hypot(dd(4.0)), dd(5.0)) < 1e-31);
+        assert!(error(dd(-27.0).cbrt(), dd(-3.0)) < 1e-31);
+        // Out to the subnormals, where 2f64.powi(-1070) would underflow
+        for (x, n) in [(2f64.powi(1000), 1000.0), (f64::from_bits(1 << 4), -1070.0)] {
+            assert!(error(dd(x).ln(), dd(2.0).ln() * dd(n)) < 1e-28);
+        }
+        // Special values, as f64 has them
+        assert_eq!(dd(0.0).atan2(dd(-1.0)), pi());
+        assert_eq!(dd(1.0).asin(), pi() / dd(2.0));
+        assert_eq!(dd(-1.0).acos(), pi());
+        assert_eq!(dd(2.0).powf(dd(-3.0)), dd(0.125));
+        assert_eq!(dd(1000.0).exp(), DD::infinity());
+        assert_eq!(dd(-1000.0).exp(), dd(0.0));
+        assert_eq!(dd(100.0).tanh(), dd(1.0));
+        assert!(dd(-1.0).ln().is_nan() && dd(2.0).asin().is_nan() && dd(-2.0).powf(dd(0.5)).is_nan());
+        assert!(DD::nan().is_nan() && !DD::infinity().is_finite() && !dd(f64::INFINITY).sin().is_finite());
+        assert_eq!((DD::new(2.5, 0.0).round(), dd(-2.5).round(), DD::new(2.5, -1e-20).round()), (dd(3.0), dd(-3.0), dd(2.0)));
+        assert_eq!(DD::new(3.0, 1e-20).ceil(), dd(4.0));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", dd(2.0).sqrt()), "1.4142135623730950488016887242097");
+        assert_eq!(format!("{}", pi()), "3.1415926535897932384626433832795");
+        assert_eq!(format!("{}", -pi() * dd(1e10)), "-31415926535.897932384626433832795");
+        assert_eq!(format!("{}", dd(1.0) / dd(3e5)), "0.0000033333333333333333333333333333333");
+        assert_eq!(format!("{:.5}", pi()), "3.14159");
+        assert_eq!(format!("{:.2}", dd(9.999)), "10.00");
+        assert_eq!(format!("{}", dd(1.5)), "1.5");
+        assert_eq!(format!("{}", dd(-0.0)), "-0");
+        // Subnormals, which are scaled up by more than the largest f64. The
+        // scaling costs the last digit or two.
+        let subnormal = format!("{}", dd(1e-320));
+        assert_eq!(subnormal.len(), 2 + 320 + 32);
+        assert!(subnormal.starts_with(&format!("0.{}999988867182683005413375236765", "0".repeat(320))));
+        let smallest = format!("{}", dd(f64::from_bits(1)));
+        assert_eq!(smallest.len(), 2 + 323 + 32);
+        assert!(smallest.starts_with(&format!("0.{}494065645841246544176568792868", "0".repeat(323))));
+    }
+
+    #[test]
+    fn test_generic_code() {
+        // Gauss-Legendre nodes computed in double-double integrate
+        // 1 / (1 + x^2) over [0, 1], which is pi / 4, to 106 bits
+        let rule = GaussianQuadrature::<DD>::new(GaussFamily::Legendre, 30).unwrap();
+        let integral = rule.integrate_interval(|x| (dd(1.0) + x * x).inv(), dd(0.0), dd(1.0)).unwrap();
+        assert!(error(integral, pi() / dd(4.0)) < 1e-30);
+        // The 8 by 8 Hilbert matrix has a condition number of 1.5e10, which
+        // costs f64 most of its digits but leaves double-double plenty
+        let n = 8;
+        let hilbert = |i: usize, j: usize| 1.0 / (i + j + 1) as f64;
+        let mut f64_matrix = DenseMatrix::<f64>::zeros(n, n);
+        let mut dd_matrix = DenseMatrix::<DD>::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                f64_matrix[(i, j)] = hilbert(i, j);
+                dd_matrix[(i, j)] = dd(1.0) / dd((i + j + 1) as f64);
+            }
+        }
+        let ones = vec![dd(1.0); n];
+        let b: Vec<DD> = (0..n).map(|i| (0..n).fold(dd(0.0), |s, j| s + dd_matrix[(i, j)])).collect();
+        let x = dd_matrix.solve(&b).unwrap();
+        assert!(x.iter().zip(&ones).all(|(&x, &one)| error(x, one) < 1e-20));
+        let b: Vec<f64> = b.iter().map(|b| b.to_f64()).collect();
+        let x = f64_matrix.solve(&b).unwrap();
+        assert!(x.iter().any(|x| (x - 1.0).abs() > 1e-9));
+        // Vectors
+        let v = Vector3D::new(dd(1.0), dd(1.0), dd(1.0));
+        assert!(error(v.length(), dd(3.0).sqrt()) < 1e-31);
+        assert!(error(v.length().sqr(), dd(3.0)) < 1e-30);
+    }
+}
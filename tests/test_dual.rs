@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::ode::runge_kutta::{RungeKuttaMethod, RungeKuttaSolver};
+    use quartz::integrate::one_d::integrator::Integrator;
+    use quartz::integrate::one_d::trapezoid_integrator::TrapezoidIntegrator;
+    use quartz::math::dual::{derivative, gradient, Dual, Float, Real, Scalar};
+    use quartz::math::lin_alg::spatial_vector::SpatialVector;
+    use quartz::spatial::vector_3d::Vector3D;
+
+    #[test]
+    fn test_arithmetic() {
+        // x^3 - 2x + 1 / x has derivative 3x^2 - 2 - 1 / x^2
+        let (value, slope) = derivative(|x: Dual<f64>| x * x * x - x * 2.0 + x.inv(), 2.0);
+        assert_eq!(value, 4.5);
+        assert_eq!(slope, 9.75);
+        let (value, slope) = derivative(|x: Dual<f64>| (x + 1.0).sqrt() / (x - 1.0), 3.0);
+        assert_eq!(value, 1.0);
+        assert_relative_eq!(slope, 1.0 / 8.0 - 0.5, epsilon = 1e-15, max_relative = 1e-15);
+        let (value, slope) = derivative(|x: Dual<f64>| x.powi(-2) + x.powi(3) + x.powi(0), 2.0);
+        assert_eq!(value, 0.25 + 8.0 + 1.0);
+        assert_eq!(slope, -0.25 + 12.0);
+        // Large powers take a logarithmic number of products
+        let (value, slope) = derivative(|x: Dual<f64>| x.powi(i32::MAX) + x.powi(i32::MIN), 1.0);
+        assert_eq!(value, 2.0);
+        assert_eq!(slope, -1.0);
+        let (_, slope) = derivative(|x: Dual<f64>| -(x.abs().max(Dual::constant(0.5))), -3.0);
+        assert_eq!(slope, 1.0);
+
+        let x = Dual::new(2.0, [1.0]);
+        assert_eq!(format!("{}", x), "2 + 1ε");
+        assert_eq!(format!("{}", Dual::new(2.0, [1.0, 0.5])), "2 + 1ε0 + 0.5ε1");
+        // Comparisons ignore the derivatives
+        assert!(x == Dual::constant(2.0));
+        assert!(x < Dual::constant(3.0));
+        assert_eq!(Dual::<f64>::epsilon().to_f64(), f64::EPSILON);
+        assert_eq!(Dual::<f32>::zero().derivative(), 0.0);
+    }
+
+    #[test]
+    fn test_constant_zeros() {
+        // Infinite slopes at zero leave constants with zero derivatives
+        let zero = Dual::<f64, 2>::constant(0.0);
+        let [x, y] = Dual::variables([2.0, 3.0]);
+        for z in [zero.sqrt(), (zero * x).sqrt(), zero.cbrt(), zero.hypot(zero), (zero * y).hypot(zero)] {
+            assert_eq!((z.value, z.gradient), (0.0, [0.0, 0.0]));
+        }
+        // while variables keep theirs
+        let z = x.hypot(zero);
+        assert_eq!((z.value, z.gradient), (2.0, [1.0, 0.0]));
+        assert_eq!((x - x).sqrt().gradient, [0.0, 0.0]);
+        assert!(Dual::<f64>::variable(0.0, 0).sqrt().derivative().is_infinite());
+    }
+
+    #[test]
+    fn test_vector_gradient() {
+        // The gradient of |r| is r / |r|, found in one pass
+        let (length, grad) = gradient(|[x, y, z]| Vector3D::new(x, y, z).length(), [3.0, -4.0, 12.0]);
+        assert_eq!(length, 13.0);
+        assert_relative_eq!(grad[0], 3.0 / 13.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad[1], -4.0 / 13.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad[2], 12.0 / 13.0, epsilon = 1e-15, max_relative = 1e-15);
+
+        // The derivatives of a normalized vector by its first component
+        let x = Dual::variable(3.0, 0);
+        let mut v = Vector3D::new(x, Dual::constant(4.0), Dual::constant(0.0));
+        v.normalize().unwrap();
+        assert_relative_eq!(v.x.derivative(), 16.0 / 125.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(v.y.derivative(), -12.0 / 125.0, epsilon = 1e-15, max_relative = 1e-15);
+    }
+
+    #[test]
+    fn test_integral_sensitivity() {
+        // The integral I of sqrt(k x) over [1, 2] has dI/dk = I / 2k, and
+        // differentiating the trapezoid rule differentiates each sample
+        let k = Dual::variable(4.0, 0);
+        let integral = TrapezoidIntegrator::new().integrate_func(|x: Dual<f64>| (k * x).sqrt(), Dual::constant(1.0), Dual::constant(2.0), 1000).unwrap();
+        assert_relative_eq!(integral.value, 4.0 / 3.0 * (2.0f64.powf(1.5) - 1.0), epsilon = 1e-6, max_relative = 1e-6);
+        assert_relative_eq!(integral.derivative(), integral.value / 8.0, epsilon = 1e-14, max_relative = 1e-14);
+    }
+
+    #[test]
+    fn test_ode_sensitivity() {
+        // y' = -k y, y(0) = 1 has dy(t)/dk = -t e^(-k t)
+        let k = Dual::variable(2.0, 0);
+        let mut solver = RungeKuttaSolver::new(RungeKuttaMethod::Rk45);
+        solver.rtol = Dual::constant(1e-10);
+        solver.atol = Dual::constant(1e-12);
+        let solution = solver.solve(|_, y: &Dual<f64>| -k * *y, Dual::constant(0.0), Dual::constant(1.0), Dual::constant(1.0)).unwrap();
+        let y = solution.final_state();
+        assert_relative_eq!(y.value, (-2.0f64).exp(), epsilon = 1e-9, max_relative = 1e-9);
+        assert_relative_eq!(y.derivative(), -(-2.0f64).exp(), epsilon = 1e-8, max_relative = 1e-8);
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::one_d::gauss_kronrod_integrator::{GaussKronrodIntegrator, GaussKronrodRule};
+
+    #[test]
+    fn test_polynomial_exact() {
+        let mut integrator = GaussKronrodIntegrator::<f64>::new();
+        for rule in [GaussKronrodRule::G7K15, GaussKronrodRule::G10K21] {
+            integrator.rule = rule;
+            let result = integrator.integrate(|x| x.powi(10) - 3.0 * x * x + 1.0, -1.0, 2.0);
+            let exact = (2048.0 + 1.0) / 11.0 - (8.0 + 1.0) + 3.0;
+            assert!((result.value - exact).abs() < 1e-12);
+            assert!(result.converged);
+            assert_eq!(result.evaluations, rule.points());
+        }
+    }
+
+    #[test]
+    fn test_closure() {
+        let k = 3.0_f64;
+        let result = GaussKronrodIntegrator::new().integrate(|x: f64| (k * x).sin(), 0.0, std::f64::consts::PI);
+        assert!((result.value - 2.0 / 3.0).abs() < 1e-12);
+        assert!(result.abs_error < 1e-8);
+    }
+
+    #[test]
+    fn test_adaptive_subdivision() {
+        let result = GaussKronrodIntegrator::<f64>::new().integrate(|x| x.sqrt(), 0.0, 1.0);
+        assert!(result.converged);
+        assert!(result.evaluations > 21);
+        assert!((result.value - 2.0 / 3.0).abs() < 1e-10);
+        assert!((result.value - 2.0 / 3.0).abs() <= result.abs_error);
+    }
+
+    #[test]
+    fn test_reversed_bounds() {
+        let result = GaussKronrodIntegrator::<f64>::new().integrate(|x| x.exp(), 1.0, 0.0);
+        assert!((result.value + (1.0_f64.exp() - 1.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_subdivision_limit() {
+        let mut integrator = GaussKronrodIntegrator::<f64>::new();
+        integrator.max_subdivisions = 2;
+        integrator.abs_tol = 0.0;
+        integrator.rel_tol = 1e-15;
+        let result = integrator.integrate(|x| 1.0 / x.sqrt(), 0.0, 1.0);
+        assert!(!result.converged);
+        assert!(result.abs_error > 0.0);
+    }
+}
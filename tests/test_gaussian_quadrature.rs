@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::one_d::gaussian_quadrature::{GaussFamily, GaussianQuadrature};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_legendre() {
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Legendre, 2).unwrap();
+        assert_relative_eq!(rule.nodes[0], -(1.0 / 3.0_f64).sqrt(), epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(rule.nodes[1], (1.0 / 3.0_f64).sqrt(), epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(rule.weights[0], 1.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(rule.weights[1], 1.0, epsilon = 1e-12, max_relative = 1e-12);
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Legendre, 5).unwrap();
+        assert_eq!(rule.order(), 5);
+        assert_relative_eq!(rule.integrate(|x| x.powi(8)), 2.0 / 9.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(rule.integrate_interval(|x| x.powi(3), 0.0, 2.0).unwrap(), 4.0, epsilon = 1e-12, max_relative = 1e-12);
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Legendre, 40).unwrap();
+        assert_relative_eq!(rule.integrate_interval(f64::sin, 0.0, PI).unwrap(), 2.0, epsilon = 1e-12, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_laguerre() {
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Laguerre { alpha: 0.0 }, 6).unwrap();
+        assert_relative_eq!(rule.integrate(|x| x.powi(3)), 6.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert!(rule.nodes.iter().all(|&x| x > 0.0));
+        // x^0.5 e^-x x^2 integrates to gamma(3.5)
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Laguerre { alpha: 0.5 }, 4).unwrap();
+        assert_relative_eq!(rule.integrate(|x| x * x), 15.0 / 8.0 * PI.sqrt(), epsilon = 1e-12, max_relative = 1e-12);
+        assert!(rule.integrate_interval(|x| x, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_hermite() {
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Hermite, 5).unwrap();
+        assert_relative_eq!(rule.integrate(|x| x.powi(4)), 3.0 * PI.sqrt() / 4.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(rule.nodes[2], 0.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(rule.nodes[0], -rule.nodes[4], epsilon = 1e-12, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_chebyshev() {
+        let order = 7;
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Chebyshev, order).unwrap();
+        for (k, (&x, &w)) in rule.nodes.iter().zip(rule.weights.iter()).enumerate() {
+            let expected = -((2 * k + 1) as f64 * PI / (2 * order) as f64).cos();
+            assert_relative_eq!(x, expected, epsilon = 1e-12, max_relative = 1e-12);
+            assert_relative_eq!(w, PI / order as f64, epsilon = 1e-12, max_relative = 1e-12);
+        }
+        assert_relative_eq!(rule.integrate(|x| x * x), PI / 2.0, epsilon = 1e-12, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_jacobi() {
+        let legendre = GaussianQuadrature::<f64>::new(GaussFamily::Legendre, 6).unwrap();
+        let jacobi = GaussianQuadrature::<f64>::new(GaussFamily::Jacobi { alpha: 0.0, beta: 0.0 }, 6).unwrap();
+        for (a, b) in legendre.nodes.iter().zip(jacobi.nodes.iter()) {
+            assert_relative_eq!(*a, *b, epsilon = 1e-12, max_relative = 1e-12);
+        }
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Jacobi { alpha: 1.0, beta: 0.0 }, 3).unwrap();
+        assert_relative_eq!(rule.integrate(|x| x), -2.0 / 3.0, epsilon = 1e-12, max_relative = 1e-12);
+        // alpha = beta = -1/2 is the Chebyshev weight
+        let rule = GaussianQuadrature::<f64>::new(GaussFamily::Jacobi { alpha: -0.5, beta: -0.5 }, 4).unwrap();
+        assert_relative_eq!(rule.integrate(|x| x * x), PI / 2.0, epsilon = 1e-12, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(GaussianQuadrature::<f64>::new(GaussFamily::Legendre, 0).is_err());
+        assert!(GaussianQuadrature::<f64>::new(GaussFamily::Laguerre { alpha: -1.0 }, 3).is_err());
+        assert!(GaussianQuadrature::<f64>::new(GaussFamily::Jacobi { alpha: 0.0, beta: -2.0 }, 3).is_err());
+        assert!(GaussianQuadrature::<f64>::new(GaussFamily::Laguerre { alpha: f64::NAN }, 3).is_err());
+    }
+
+    #[test]
+    fn test_f32() {
+        let rule = GaussianQuadrature::<f32>::new(GaussFamily::Legendre, 4).unwrap();
+        assert!((rule.integrate(|x| x.powi(6)) - 2.0 / 7.0).abs() < 1e-6);
+    }
+}
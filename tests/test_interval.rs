@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::one_d::trapezoid_integrator::{Integrator, TrapezoidIntegrator};
+    use quartz::math::interval::{Interval, IntervalBound, Real, Scalar};
+    use quartz::spatial::vector_3d::{SpatialVector, Vector3D};
+
+    type I32 = Interval<f32>;
+    type I64 = Interval<f64>;
+
+    fn i64(lo: f64, hi: f64) -> I64 {
+        Interval::new(lo, hi)
+    }
+
+    // Whether the interval holds x, which is exact in f64
+    fn holds(i: I32, x: f64) -> bool {
+        (i.lo as f64) <= x && x <= (i.hi as f64)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // Exact operations stay points
+        assert_eq!(i64(1.0, 2.0) + i64(3.0, 4.0), i64(4.0, 6.0));
+        assert_eq!(i64(1.0, 2.0) - i64(3.0, 4.0), i64(-3.0, -1.0));
+        assert_eq!(i64(-1.0, 2.0) * i64(3.0, 4.0), i64(-4.0, 8.0));
+        assert_eq!(i64(1.0, 2.0) / i64(-4.0, -2.0), i64(-1.0, -0.25));
+        assert_eq!(i64(0.0, 0.0) / i64(3.0, 3.0), i64(0.0, 0.0));
+        assert_eq!(i64(0.0, 1.0) / i64(2.0, 4.0), i64(0.0, 0.5));
+        assert_eq!(i64(4.0, 9.0).sqrt(), i64(2.0, 3.0));
+        // Inexact ones are rounded outwards by one place
+        let third = I32::point(1.0) / I32::point(3.0);
+        assert_eq!(IntervalBound::next_up(third.lo), third.hi);
+        assert!(holds(third, 1.0 / 3.0));
+        let sum = I32::point(1.0) + I32::point(1e-10);
+        assert_eq!(sum, Interval::new(1.0, IntervalBound::next_up(1.0f32)));
+        assert_eq!(<I32 as Scalar>::from(0.1), Interval::new(IntervalBound::next_down(0.1f32), 0.1f32));
+        assert!(holds(<I32 as Scalar>::from(0.1), 0.1));
+        // Neighbours across zero and at the ends of the range
+        let tiny = f64::from_bits(1);
+        assert_eq!((IntervalBound::next_up(-0.0f64), IntervalBound::next_down(0.0f64)), (tiny, -tiny));
+        assert_eq!((IntervalBound::next_up(-tiny), IntervalBound::next_down(tiny)), (0.0, 0.0));
+        assert_eq!(IntervalBound::next_up(-1.0f64), -1.0 + f64::EPSILON / 2.0);
+        assert_eq!(IntervalBound::next_up(f64::MAX), f64::INFINITY);
+        assert_eq!(IntervalBound::next_down(f64::INFINITY), f64::MAX);
+        assert_eq!(IntervalBound::next_up(f64::INFINITY), f64::INFINITY);
+        assert_eq!(IntervalBound::next_up(f32::NEG_INFINITY), f32::MIN);
+        assert!(IntervalBound::next_down(f32::NAN).is_nan());
+        // Even powers know they can't be negative, where products can't
+        let x = i64(-2.0, 3.0);
+        assert_eq!(x.powi(2), i64(0.0, 9.0));
+        assert_eq!(x * x, i64(-6.0, 9.0));
+        assert_eq!(i64(2.0, 4.0).powi(-1), i64(0.25, 0.5));
+        // Division by intervals holding zero, and square roots of negatives
+        assert_eq!(i64(1.0, 2.0) / i64(0.0, 4.0), i64(0.25, f64::INFINITY));
+        assert_eq!(i64(1.0, 2.0) / i64(-1.0, 1.0), I64::entire());
+        assert!((i64(1.0, 2.0) / i64(0.0, 0.0)).is_empty());
+        assert!(i64(-2.0, -1.0).sqrt().is_empty());
+        assert_eq!(i64(-1.0, 4.0).sqrt(), i64(0.0, 2.0));
+        // Overflow leaves an unbounded interval
+        assert_eq!(I64::point(f64::MAX) + I64::point(f64::MAX), i64(f64::MAX, f64::INFINITY));
+        assert_eq!(i64(0.0, 1.0) * i64(1.0, f64::INFINITY), i64(0.0, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let (a, b) = (i64(1.0, 3.0), i64(2.0, 5.0));
+        assert_eq!(a.hull(&b), i64(1.0, 5.0));
+        assert_eq!(a.intersection(&b), i64(2.0, 3.0));
+        assert!(a.intersection(&i64(4.0, 5.0)).is_empty());
+        assert_eq!(a.hull(&I64::empty()), a);
+        assert!(a.encloses(&i64(1.5, 3.0)) && !a.encloses(&b));
+        assert!(a.contains(3.0) && !a.contains(3.5));
+        assert_eq!((a.width(), a.midpoint(), a.radius()), (2.0, 2.0, 1.0));
+        assert_eq!(I64::entire().midpoint(), 0.0);
+        let thin = i64(1.0, IntervalBound::next_up(1.0f64));
+        assert!(thin.width() >= f64::EPSILON && thin.radius() >= f64::EPSILON / 2.0);
+        // Comparisons hold only for every pair of numbers
+        assert!(a < i64(4.0, 5.0) && a == i64(1.0, 3.0));
+        assert_eq!(a.partial_cmp(&b), None);
+        assert_eq!(a.abs(), a);
+        assert_eq!(i64(-3.0, 1.0).abs(), i64(0.0, 3.0));
+        assert_eq!(a.to_f64(), 2.0);
+        assert_eq!(format!("{}", a), "[1, 3]");
+        assert_eq!(format!("{}", I64::empty()), "∅");
+    }
+
+    #[test]
+    fn test_generic_code() {
+        // Lengths of f32 vectors, against the exact lengths
+        let v = Vector3D::new(I32::point(1.0), I32::point(2.0), I32::point(2.0));
+        assert_eq!(v.length(), I32::point(3.0));
+        let v = Vector3D::new(I32::point(1.0), I32::point(1.0), I32::point(1.0));
+        assert!(holds(v.length(), 3f64.sqrt()) && v.length().width() <= 2.0 * f32::EPSILON);
+        let mut u = Vector3D::new(I32::point(0.3), I32::point(0.4), I32::point(1.2));
+        assert!(u.normalize().is_ok());
+        assert!(holds(u.x, 0.3 / 1.3) && holds(u.y, 0.4 / 1.3) && holds(u.z, 1.2 / 1.3));
+        // A wide length can still be normalized, unless it may be zero
+        let mut w = Vector3D::new(i64(0.9, 1.1), I64::zero(), I64::zero());
+        assert!(w.normalize().is_ok());
+        assert!(w.x.contains(1.0) && w.x.width() > 0.1);
+        let mut z = Vector3D::new(i64(-0.1, 0.1), I64::zero(), I64::zero());
+        assert_eq!(z.normalize(), Err("This vector's length is too near zero, and can't be normalized."));
+        // The trapezoid rule for x^2 over [0, 1] with ten steps is 0.335 in exact
+        // arithmetic. Each rounding could have moved it the width of an f32.
+        let integrator = TrapezoidIntegrator::<I32>::new();
+        let area = integrator.integrate_func(|x| x * x, I32::point(0.0), I32::point(1.0), 10).unwrap();
+        assert!(holds(area, 0.335) && area.width() < 1e-6);
+        let area = TrapezoidIntegrator::<I64>::new()
+            .integrate_func(|x| x, I64::point(0.0), I64::point(1.0), 3)
+            .unwrap();
+        assert!(area.contains(0.5) && area.width() > 0.0 && area.width() < 1e-15);
+    }
+
+    #[test]
+    fn test_rump() {
+        // Rump's polynomial, which f64 arithmetic gets wrong even in sign. The
+        // interval result can't be trusted either, but it says so.
+        let rump = |a: I64, b: I64| {
+            let c = |x: f64| <I64 as Scalar>::from(x);
+            c(333.75) * b.powi(6)
+                + a.powi(2) * (c(11.0) * a.powi(2) * b.powi(2) - b.powi(6) - c(121.0) * b.powi(4) - c(2.0))
+                + c(5.5) * b.powi(8)
+                + a / (c(2.0) * b)
+        };
+        let (a, b) = (77617.0f64, 33096.0f64);
+        let float = 333.75 * b.powi(6) + a * a * (11.0 * a * a * b * b - b.powi(6) - 121.0 * b.powi(4) - 2.0)
+            + 5.5 * b.powi(8)
+            + a / (2.0 * b);
+        let exact = -0.827_396_059_946_821_4;
+        assert!((float - exact).abs() > 1.0);
+        let enclosure = rump(I64::point(a), I64::point(b));
+        assert!(enclosure.contains(exact) && enclosure.contains(float) && enclosure.width() > 1e20);
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::monte_carlo::low_discrepancy::{Halton, Sobol};
+    use quartz::integrate::monte_carlo::monte_carlo_integrator::{MonteCarloIntegrator, Rng, Vector3D};
+    use quartz::integrate::monte_carlo::quasi_monte_carlo_integrator::{QuasiMonteCarloIntegrator, SequenceKind};
+    use quartz::spatial::vector_3d::SpatialVector;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_plain() {
+        let integrator = MonteCarloIntegrator::new(200_000, 1);
+        let result = integrator.integrate(|p: &[f64]| p[0] * p[1], &[0.0, 0.0], &[1.0, 2.0]).unwrap();
+        assert!((result.value - 1.0).abs() < 4.0 * result.std_error);
+        assert!(result.std_error < 0.01);
+        assert_eq!(result.samples, 200_000);
+        // The same seed gives the same answer
+        let again = integrator.integrate(|p: &[f64]| p[0] * p[1], &[0.0, 0.0], &[1.0, 2.0]).unwrap();
+        assert_eq!(result, again);
+        assert!(integrator.integrate(|p: &[f64]| p[0], &[0.0, 0.0], &[1.0]).is_err());
+    }
+
+    #[test]
+    fn test_vector_3d() {
+        // The volume of the unit ball, as the fraction of the enclosing cube inside it
+        let integrator = MonteCarloIntegrator::new(100_000, 2);
+        let inside = |p: Vector3D<f64>| if p.length() <= 1.0 { 1.0 } else { 0.0 };
+        let result = integrator.integrate_vector_3d(inside, Vector3D::new(-1.0, -1.0, -1.0), Vector3D::new(1.0, 1.0, 1.0)).unwrap();
+        assert!((result.value - 4.0 * PI / 3.0).abs() < 4.0 * result.std_error);
+    }
+
+    #[test]
+    fn test_stratified() {
+        let integrator = MonteCarloIntegrator::new(10_000, 3);
+        let f = |p: &[f64]| (p[0] + p[1]).exp();
+        let exact = (1.0_f64.exp() - 1.0).powi(2);
+        let plain = integrator.integrate(f, &[0.0, 0.0], &[1.0, 1.0]).unwrap();
+        let stratified = integrator.integrate_stratified(f, &[0.0, 0.0], &[1.0, 1.0], 10).unwrap();
+        assert_eq!(stratified.samples, 10_000);
+        assert!((stratified.value - exact).abs() < 4.0 * stratified.std_error);
+        assert!(stratified.std_error < plain.std_error / 5.0);
+        assert!(integrator.integrate_stratified(f, &[0.0; 2], &[1.0; 3], 10).is_err());
+        // 10^40 cells can't be counted
+        assert!(integrator.integrate_stratified(f, &[0.0; 40], &[1.0; 40], 10).is_err());
+    }
+
+    #[test]
+    fn test_importance() {
+        // The integral of x^2 e^-x over [0, inf) is 2, sampling from the exponential distribution
+        let integrator = MonteCarloIntegrator::new(100_000, 4);
+        let result = integrator.integrate_importance(
+            |p: &[f64]| p[0] * p[0] * (-p[0]).exp(),
+            |rng: &mut Rng| vec![-(1.0 - rng.uniform()).ln()],
+            |p: &[f64]| (-p[0]).exp(),
+        );
+        assert!((result.value - 2.0).abs() < 4.0 * result.std_error);
+        assert!(result.std_error < 0.02);
+    }
+
+    #[test]
+    fn test_sequences() {
+        let points: Vec<Vec<f64>> = Sobol::new(2).unwrap().take(5).collect();
+        assert_eq!(
+            points,
+            vec![vec![0.0, 0.0], vec![0.5, 0.5], vec![0.75, 0.25], vec![0.25, 0.75], vec![0.375, 0.375]]
+        );
+        let points: Vec<Vec<f64>> = Halton::new(2).take(4).collect();
+        assert_eq!(points[1], vec![0.5, 1.0 / 3.0]);
+        assert_eq!(points[3], vec![0.75, 1.0 / 9.0]);
+        assert!(Sobol::new(0).is_err());
+        assert!(Sobol::new(Sobol::MAX_DIMENSION + 1).is_err());
+        // Every one-dimensional projection of the first 2^k Sobol points hits each 2^-k bin once
+        let sobol = Sobol::new(Sobol::MAX_DIMENSION).unwrap();
+        let points: Vec<Vec<f64>> = sobol.take(64).collect();
+        for d in 0..Sobol::MAX_DIMENSION {
+            let mut bins: Vec<usize> = points.iter().map(|p| (p[d] * 64.0) as usize).collect();
+            bins.sort();
+            assert_eq!(bins, (0..64).collect::<Vec<usize>>());
+        }
+    }
+
+    #[test]
+    fn test_quasi_monte_carlo() {
+        let f = |p: &[f64]| p.iter().map(|x| (PI * x).sin()).product::<f64>();
+        let exact = (2.0 / PI).powi(5);
+        let lower = [0.0; 5];
+        let upper = [1.0; 5];
+        let random = MonteCarloIntegrator::new(1 << 13, 5).integrate(f, &lower, &upper).unwrap();
+        for kind in [SequenceKind::Sobol, SequenceKind::Halton] {
+            let result = QuasiMonteCarloIntegrator::new(kind, 1 << 13, 5).integrate(f, &lower, &upper).unwrap();
+            assert!((result.value - exact).abs() < 1e-3);
+            assert!(result.std_error < random.std_error / 5.0);
+        }
+        let result = QuasiMonteCarloIntegrator::new(SequenceKind::Sobol, 1 << 12, 6)
+            .integrate_vector_3d(|p: Vector3D<f64>| p * p, Vector3D::zero(), Vector3D::new(1.0, 1.0, 1.0))
+            .unwrap();
+        assert!((result.value - 1.0).abs() < 1e-3);
+        assert!(QuasiMonteCarloIntegrator::new(SequenceKind::Sobol, 4, 0).integrate(f, &lower, &upper).is_err());
+        assert!(QuasiMonteCarloIntegrator::new(SequenceKind::Sobol, 1 << 10, 0).integrate(f, &lower, &upper[..4]).is_err());
+    }
+}
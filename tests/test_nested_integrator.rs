@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::multi_d::nested_integrator::{NestedIntegrator, Vector3D};
+    use quartz::integrate::one_d::tanh_sinh_integrator::TanhSinhIntegrator;
+    use quartz::spatial::vector_3d::SpatialVector;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_dblquad_rectangle() {
+        let result = NestedIntegrator::default().dblquad(|x: f64, y: f64| x * y * y, 0.0, 2.0, |_| 0.0, |_| 3.0);
+        assert!(result.converged);
+        assert_relative_eq!(result.value, 2.0 * 9.0, epsilon = 1e-9, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_dblquad_variable_limits() {
+        // The area of the unit disk
+        let result = NestedIntegrator::default().dblquad(
+            |_: f64, _: f64| 1.0,
+            -1.0,
+            1.0,
+            |x: f64| -(1.0 - x * x).sqrt(),
+            |x: f64| (1.0 - x * x).sqrt(),
+        );
+        assert!((result.value - PI).abs() < 1e-7);
+        assert!(result.evaluations > 0);
+    }
+
+    #[test]
+    fn test_tplquad_moment_of_inertia() {
+        // The moment of inertia about the z axis of the unit cube with unit density
+        let result = NestedIntegrator::default().tplquad(
+            |p: Vector3D<f64>| p.x * p.x + p.y * p.y,
+            0.0,
+            1.0,
+            |_| 0.0,
+            |_| 1.0,
+            |_, _| 0.0,
+            |_, _| 1.0,
+        );
+        assert_relative_eq!(result.value, 2.0 / 3.0, epsilon = 1e-9, max_relative = 1e-9);
+        // The volume of the tetrahedron under x + y + z = 1
+        let result = NestedIntegrator::default().tplquad(
+            |_: Vector3D<f64>| 1.0,
+            0.0,
+            1.0,
+            |_| 0.0,
+            |x| 1.0 - x,
+            |_, _| 0.0,
+            |x, y| 1.0 - x - y,
+        );
+        assert_relative_eq!(result.value, 1.0 / 6.0, epsilon = 1e-9, max_relative = 1e-9);
+        // The second moment of the unit ball
+        let result = NestedIntegrator::default().tplquad(
+            |p: Vector3D<f64>| p.length().powi(2),
+            -1.0,
+            1.0,
+            |x: f64| -(1.0 - x * x).sqrt(),
+            |x: f64| (1.0 - x * x).sqrt(),
+            |x: f64, y: f64| -(1.0 - x * x - y * y).max(0.0).sqrt(),
+            |x: f64, y: f64| (1.0 - x * x - y * y).max(0.0).sqrt(),
+        );
+        assert!((result.value - 4.0 * PI / 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nquad() {
+        let integrator = NestedIntegrator::new(TanhSinhIntegrator::new());
+        let unit = |_: &[f64]| (0.0, 1.0);
+        // The volume of the unit 4-simplex is 1/24
+        let simplex = |p: &[f64]| (0.0, 1.0 - p.iter().sum::<f64>());
+        let result = integrator.nquad(|_| 1.0, &[&unit, &simplex, &simplex, &simplex]);
+        assert_relative_eq!(result.value, 1.0 / 24.0, epsilon = 1e-9, max_relative = 1e-9);
+        let result = integrator.nquad(|p| p.iter().product(), &[&unit, &unit, &unit, &unit]);
+        assert_relative_eq!(result.value, 1.0 / 16.0, epsilon = 1e-9, max_relative = 1e-9);
+        assert_relative_eq!(integrator.nquad(|p| p.len() as f64, &[]).value, 0.0, epsilon = 1e-9, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_infinite_limits() {
+        let integrator = NestedIntegrator::new(TanhSinhIntegrator::new());
+        let result = integrator.dblquad(|x: f64, y: f64| (-x * x - y * y).exp(), 0.0, f64::INFINITY, |_| 0.0, |_| f64::INFINITY);
+        assert!(result.converged);
+        assert_relative_eq!(result.value, PI / 4.0, epsilon = 1e-9, max_relative = 1e-9);
+        assert!(result.abs_error.is_finite() && result.abs_error < 1e-6);
+    }
+}
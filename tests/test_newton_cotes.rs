@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::one_d::boole_integrator::BooleIntegrator;
+    use quartz::integrate::one_d::newton_cotes_integrator::{IntegrationError, Integrator, NewtonCotesIntegrator};
+    use quartz::integrate::one_d::simpson_integrator::{SimpsonIntegrator, SimpsonThreeEighthsIntegrator};
+
+    fn cubic(x: f64) -> f64 {
+        x * x * x - 2.0 * x + 1.0
+    }
+
+    #[test]
+    fn test_weights() {
+        let simpson = NewtonCotesIntegrator::<f64>::closed(2).unwrap();
+        let expected = [1.0 / 3.0, 4.0 / 3.0, 1.0 / 3.0];
+        for (&w, &e) in simpson.weights().iter().zip(expected.iter()) {
+            assert_relative_eq!(w, e, epsilon = 1e-12, max_relative = 1e-12);
+        }
+        let boole = NewtonCotesIntegrator::<f64>::closed(4).unwrap();
+        let expected = [14.0 / 45.0, 64.0 / 45.0, 24.0 / 45.0, 64.0 / 45.0, 14.0 / 45.0];
+        for (&w, &e) in boole.weights().iter().zip(expected.iter()) {
+            assert_relative_eq!(w, e, epsilon = 1e-12, max_relative = 1e-12);
+        }
+        let midpoint = NewtonCotesIntegrator::<f64>::open(2).unwrap();
+        assert_eq!(midpoint.weights().len(), 1);
+        assert_relative_eq!(midpoint.weights()[0], 2.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert!(NewtonCotesIntegrator::<f64>::closed(0).is_err());
+        assert!(NewtonCotesIntegrator::<f64>::open(1).is_err());
+    }
+
+    #[test]
+    fn test_exact_polynomials() {
+        // 1/4 x^4 - x^2 + x over [0, 2]
+        let exact = 4.0 - 4.0 + 2.0;
+        assert_relative_eq!(SimpsonIntegrator::new().integrate_func(cubic, 0.0, 2.0, 2).unwrap(), exact, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(SimpsonThreeEighthsIntegrator::new().integrate_func(cubic, 0.0, 2.0, 3).unwrap(), exact, epsilon = 1e-12, max_relative = 1e-12);
+        // Steps are rounded up to a multiple of the panel width
+        assert_relative_eq!(SimpsonIntegrator::new().integrate_func(cubic, 0.0, 2.0, 5).unwrap(), exact, epsilon = 1e-12, max_relative = 1e-12);
+        let quintic = |x: f64| x.powi(5);
+        assert_relative_eq!(BooleIntegrator::new().integrate_func(quintic, -1.0, 1.0, 4).unwrap(), 0.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(BooleIntegrator::new().integrate_func(quintic, 0.0, 1.0, 8).unwrap(), 1.0 / 6.0, epsilon = 1e-12, max_relative = 1e-12);
+        let open = NewtonCotesIntegrator::open(4).unwrap();
+        assert_relative_eq!(open.integrate_func(cubic, 0.0, 2.0, 4).unwrap(), exact, epsilon = 1e-12, max_relative = 1e-12);
+        assert_eq!(BooleIntegrator::new().integrate_func(cubic, 0.0, 2.0, 0), Err(IntegrationError::InvalidSampleCount { count: 0 }));
+    }
+
+    #[test]
+    fn test_convergence() {
+        let exact = 2.0;
+        let coarse = SimpsonIntegrator::new().integrate_func(f64::sin, 0.0, std::f64::consts::PI, 8).unwrap();
+        let fine = SimpsonIntegrator::new().integrate_func(f64::sin, 0.0, std::f64::consts::PI, 16).unwrap();
+        // Simpson's rule is fourth order
+        let ratio = (coarse - exact) / (fine - exact);
+        assert!((ratio - 16.0).abs() < 0.5);
+        let high_order = NewtonCotesIntegrator::closed(6).unwrap();
+        let value = high_order.integrate_func(f64::sin, 0.0, std::f64::consts::PI, 60).unwrap();
+        assert!((value - exact).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_data() {
+        let vals: Vec<f64> = (0..7).map(|i| cubic(i as f64 * 0.5)).collect();
+        // 1/4 x^4 - x^2 + x over [0, 3]
+        let exact = 81.0 / 4.0 - 9.0 + 3.0;
+        assert_relative_eq!(SimpsonIntegrator::new().integrate_data(&vals, 0.5).unwrap(), exact, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(SimpsonThreeEighthsIntegrator::new().integrate_data(&vals, 0.5).unwrap(), exact, epsilon = 1e-12, max_relative = 1e-12);
+        // An odd number of intervals
+        let quadratic: Vec<f64> = (0..6).map(|i| (i * i) as f64).collect();
+        assert_relative_eq!(SimpsonIntegrator::new().integrate_data(&quadratic, 1.0).unwrap(), 125.0 / 3.0, epsilon = 1e-12, max_relative = 1e-12);
+        // Too few samples for a full panel
+        assert_relative_eq!(BooleIntegrator::new().integrate_data(&quadratic[..3], 1.0).unwrap(), 8.0 / 3.0, epsilon = 1e-12, max_relative = 1e-12);
+        assert_eq!(SimpsonIntegrator::new().integrate_data(&[], 1.0), Err(IntegrationError::EmptyInput));
+    }
+
+    #[test]
+    fn test_irregular_time_series() {
+        let times = [0.0, 0.3, 1.0, 1.2, 2.0, 2.9];
+        let vals: Vec<f64> = times.iter().map(|&t| t * t).collect();
+        let exact = 2.9_f64.powi(3) / 3.0;
+        let simpson = SimpsonIntegrator::new();
+        assert_relative_eq!(simpson.integrate_data_time_series(&times, &vals).unwrap(), exact, epsilon = 1e-12, max_relative = 1e-12);
+        assert_relative_eq!(simpson.integrate_data_time_series(&times[..5], &vals[..5]).unwrap(), 8.0 / 3.0, epsilon = 1e-12, max_relative = 1e-12);
+        let cubic_exact = 2.9_f64.powi(4) / 4.0 - 2.9 * 2.9 + 2.9;
+        let boole = BooleIntegrator::new();
+        assert_relative_eq!(boole.integrate_func_time_series(cubic, &times).unwrap(), cubic_exact, epsilon = 1e-12, max_relative = 1e-12);
+        assert_eq!(
+            simpson.integrate_data_time_series(&[0.0, 1.0, 1.0], &[1.0, 1.0, 1.0]),
+            Err(IntegrationError::NonMonotonicTimes { index: 2 })
+        );
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::ode::runge_kutta::{OdeError, RungeKuttaMethod, RungeKuttaSolver};
+    use quartz::spatial::vector_3d::Vector3D;
+    use quartz::math::lin_alg::spatial_vector::SpatialVector;
+    use std::f64::consts::PI;
+
+    fn tight(method: RungeKuttaMethod) -> RungeKuttaSolver<f64> {
+        let mut solver = RungeKuttaSolver::new(method);
+        solver.rtol = 1e-10;
+        solver.atol = 1e-12;
+        solver
+    }
+
+    #[test]
+    fn test_exponential_decay() {
+        for method in [RungeKuttaMethod::Rk23, RungeKuttaMethod::Rk45, RungeKuttaMethod::Dop853] {
+            let solution = tight(method).solve(|_, y: &f64| -y, 0.0, 2.0, 1.0).unwrap();
+            assert_eq!(solution.times[0], 0.0);
+            assert_eq!(solution.final_time(), 2.0);
+            assert!(solution.times.windows(2).all(|w| w[1] > w[0]));
+            assert_relative_eq!(*solution.final_state(), (-2.0f64).exp(), epsilon = 1e-8, max_relative = 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_higher_order_takes_fewer_steps() {
+        let oscillator = |_: f64, y: &[f64; 2]| [y[1], -y[0]];
+        let rk23 = tight(RungeKuttaMethod::Rk23).solve(oscillator, 0.0, 10.0, [1.0, 0.0]).unwrap();
+        let rk45 = tight(RungeKuttaMethod::Rk45).solve(oscillator, 0.0, 10.0, [1.0, 0.0]).unwrap();
+        let dop853 = tight(RungeKuttaMethod::Dop853).solve(oscillator, 0.0, 10.0, [1.0, 0.0]).unwrap();
+        assert!(rk45.accepted_steps < rk23.accepted_steps);
+        assert!(dop853.accepted_steps < rk45.accepted_steps);
+        for solution in [rk23, rk45, dop853] {
+            let y = solution.final_state();
+            assert_relative_eq!(y[0], 10f64.cos(), epsilon = 1e-7, max_relative = 1e-7);
+            assert_relative_eq!(y[1], -10f64.sin(), epsilon = 1e-7, max_relative = 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_default_tolerances() {
+        let solution = RungeKuttaSolver::default().solve(|t, _: &f64| t.cos(), 0.0, PI / 2.0, 0.0).unwrap();
+        assert_relative_eq!(*solution.final_state(), 1.0, epsilon = 1e-3, max_relative = 1e-3);
+        assert_eq!(solution.states.len(), solution.accepted_steps + 1);
+    }
+
+    #[test]
+    fn test_backwards() {
+        let solution = tight(RungeKuttaMethod::Rk45).solve(|_, y: &f64| *y, 1.0, 0.0, 1f64.exp()).unwrap();
+        assert_eq!(solution.final_time(), 0.0);
+        assert!(solution.times.windows(2).all(|w| w[1] < w[0]));
+        assert_relative_eq!(*solution.final_state(), 1.0, epsilon = 1e-8, max_relative = 1e-8);
+    }
+
+    #[test]
+    fn test_rk4_fixed_steps_converge_at_fourth_order() {
+        let solver = RungeKuttaSolver::new(RungeKuttaMethod::Rk4);
+        let error = |steps| {
+            let solution = solver.solve_fixed(|_, y: &f64| -y, 0.0, 1.0, 1.0, steps).unwrap();
+            assert_eq!(solution.times.len(), steps as usize + 1);
+            (solution.final_state() - (-1.0f64).exp()).abs()
+        };
+        let ratio = error(10) / error(20);
+        assert!(ratio > 15.0 && ratio < 17.0);
+    }
+
+    #[test]
+    fn test_dop853_fixed_steps_converge_at_eighth_order() {
+        let solver = RungeKuttaSolver::new(RungeKuttaMethod::Dop853);
+        let error = |steps| {
+            let solution = solver.solve_fixed(|t, y: &f64| -y * t.cos(), 0.0, 2.0, 1.0, steps).unwrap();
+            (solution.final_state() - (-(2.0f64).sin()).exp()).abs()
+        };
+        // 2^8 = 256, where a seventh order method would give 128
+        let ratio = error(8) / error(16);
+        assert!(ratio > 200.0 && ratio < 350.0);
+    }
+
+    #[test]
+    fn test_vector_3d_rotation() {
+        // A point rotating about the z axis stays on its circle
+        let omega = Vector3D::new(0.0, 0.0, 1.0);
+        let solution = tight(RungeKuttaMethod::Rk45)
+            .solve(|_, r: &Vector3D<f64>| omega.cross(r), 0.0, PI, Vector3D::new(1.0, 0.0, 0.5))
+            .unwrap();
+        for r in solution.states.iter() {
+            assert_relative_eq!(r.length(), 1.25f64.sqrt(), epsilon = 1e-8, max_relative = 1e-8);
+        }
+        let r = solution.final_state();
+        assert_relative_eq!(r.x, -1.0, epsilon = 1e-8, max_relative = 1e-8);
+        assert!(r.y.abs() < 1e-8);
+        assert_relative_eq!(r.z, 0.5, epsilon = 1e-12, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_kepler_orbit() {
+        // A circular orbit with unit radius and speed has period 2 pi
+        let gravity = |_: f64, s: &[Vector3D<f64>; 2]| {
+            let r = s[0].length();
+            [s[1], s[0] * (-1.0 / (r * r * r))]
+        };
+        let start = [Vector3D::new(1.0, 0.0, 0.0), Vector3D::new(0.0, 1.0, 0.0)];
+        let solution = tight(RungeKuttaMethod::Dop853).solve(gravity, 0.0, 2.0 * PI, start).unwrap();
+        let [position, velocity] = *solution.final_state();
+        assert!((position - start[0]).length() < 1e-8);
+        assert!((velocity - start[1]).length() < 1e-8);
+    }
+
+    #[test]
+    fn test_f32_and_vec_states() {
+        let solver = RungeKuttaSolver::<f32>::new(RungeKuttaMethod::Rk45);
+        let solution = solver.solve(|_, y: &Vec<f32>| vec![-y[0], -2.0 * y[1]], 0.0, 1.0, vec![1.0, 1.0]).unwrap();
+        let y = solution.final_state();
+        assert!((y[0] - (-1.0f32).exp()).abs() < 1e-3);
+        assert!((y[1] - (-2.0f32).exp()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_errors() {
+        let rk4 = RungeKuttaSolver::new(RungeKuttaMethod::Rk4);
+        assert_eq!(rk4.solve(|_, y: &f64| -y, 0.0, 1.0, 1.0), Err(OdeError::NotAdaptive));
+        assert_eq!(rk4.solve_fixed(|_, y: &f64| -y, 0.0, 1.0, 1.0, 0), Err(OdeError::InvalidStepCount { count: 0 }));
+        let mut solver = RungeKuttaSolver::new(RungeKuttaMethod::Rk45);
+        solver.max_steps = 5;
+        let result = solver.solve(|_, y: &f64| -y, 0.0, 100.0, 1.0);
+        assert!(matches!(result, Err(OdeError::TooManySteps { .. })));
+        // y' = y^2 blows up at t = 1
+        let result = RungeKuttaSolver::default().solve(|_, y: &f64| y * y, 0.0, 2.0, 1.0);
+        assert!(matches!(result, Err(OdeError::StepSizeTooSmall { t }) if t > 0.99 && t < 1.0));
+        // The same, but blowing up at t = 1e-20, where steps can't be measured against t
+        let result = RungeKuttaSolver::default().solve(|_, y: &f64| y * y, 0.0, 1.0, 1e20);
+        assert!(matches!(result, Err(OdeError::StepSizeTooSmall { t }) if t < 1e-20));
+    }
+}
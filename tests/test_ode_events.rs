@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::ode::bdf::BdfSolver;
+    use quartz::integrate::ode::ode_event::{Event, EventDirection};
+    use quartz::integrate::ode::radau::RadauSolver;
+    use quartz::integrate::ode::rosenbrock::RosenbrockSolver;
+    use quartz::integrate::ode::runge_kutta::{RungeKuttaMethod, RungeKuttaSolver};
+    use std::f64::consts::PI;
+
+    fn tight(method: RungeKuttaMethod) -> RungeKuttaSolver<f64> {
+        let mut solver = RungeKuttaSolver::new(method);
+        solver.rtol = 1e-10;
+        solver.atol = 1e-12;
+        solver
+    }
+
+    // A ball dropped from 10 m, as [height, velocity]
+    fn falling(_: f64, y: &[f64; 2]) -> [f64; 2] {
+        [y[1], -9.81]
+    }
+
+    #[test]
+    fn test_terminal_event() {
+        let impact = (2.0 * 10.0 / 9.81f64).sqrt();
+        let mut events = [Event::new(|_, y: &[f64; 2]| y[0], EventDirection::Falling, true)];
+        let solution = tight(RungeKuttaMethod::Rk45).solve_with_events(falling, 0.0, 10.0, [10.0, 0.0], &mut events).unwrap();
+        assert!(solution.terminated);
+        assert_eq!(solution.events.len(), 1);
+        assert_eq!(solution.events[0].index, 0);
+        assert_relative_eq!(solution.events[0].t, impact, epsilon = 1e-10, max_relative = 1e-10);
+        assert_relative_eq!(solution.final_time(), impact, epsilon = 1e-10, max_relative = 1e-10);
+        assert!(solution.final_state()[0].abs() < 1e-9);
+        assert_relative_eq!(solution.final_state()[1], -9.81 * impact, epsilon = 1e-10, max_relative = 1e-10);
+    }
+
+    #[test]
+    fn test_counting_crossings() {
+        // cos crosses zero at odd multiples of pi / 2
+        let oscillator = |_: f64, y: &[f64; 2]| [y[1], -y[0]];
+        let mut events = [
+            Event::new(|_, y: &[f64; 2]| y[0], EventDirection::Either, false),
+            Event::new(|_, y: &[f64; 2]| y[0], EventDirection::Rising, false),
+            Event::new(|_, y: &[f64; 2]| y[0], EventDirection::Falling, false),
+        ];
+        let solution = tight(RungeKuttaMethod::Rk45).solve_with_events(oscillator, 0.0, 10.0, [1.0, 0.0], &mut events).unwrap();
+        assert!(!solution.terminated);
+        assert_eq!(solution.final_time(), 10.0);
+        let times = |index: usize| solution.events.iter().filter(|e| e.index == index).map(|e| e.t).collect::<Vec<f64>>();
+        let either = times(0);
+        assert_eq!(either.len(), 3);
+        for (k, &t) in either.iter().enumerate() {
+            assert_relative_eq!(t, (2 * k + 1) as f64 * PI / 2.0, epsilon = 1e-9, max_relative = 1e-9);
+        }
+        assert_eq!(times(1), vec![either[1]]);
+        assert_eq!(times(2), vec![either[0], either[2]]);
+        assert!(solution.events.windows(2).all(|w| w[0].t <= w[1].t));
+    }
+
+    #[test]
+    fn test_dense_output() {
+        let solution = tight(RungeKuttaMethod::Rk45).solve(|_, y: &f64| -y, 0.0, 5.0, 1.0).unwrap();
+        assert_eq!(solution.derivatives.len(), solution.states.len());
+        for i in 0..=50 {
+            let t = i as f64 / 10.0;
+            assert_relative_eq!(solution.interpolate(t).unwrap(), (-t).exp(), epsilon = 1e-7, max_relative = 1e-7);
+        }
+        assert_eq!(solution.interpolate(5.0), Some(*solution.final_state()));
+        assert_eq!(solution.interpolate(-0.1), None);
+        assert_eq!(solution.interpolate(5.1), None);
+
+        let backwards = tight(RungeKuttaMethod::Rk45).solve(|_, y: &f64| *y, 1.0, 0.0, 1f64.exp()).unwrap();
+        assert_relative_eq!(backwards.interpolate(0.5).unwrap(), 0.5f64.exp(), epsilon = 1e-7, max_relative = 1e-7);
+        assert_eq!(backwards.interpolate(1.5), None);
+    }
+
+    #[test]
+    fn test_implicit_solvers() {
+        let impact = (2.0 * 10.0 / 9.81f64).sqrt();
+        let event = || Event::new(|_, y: &[f64; 2]| y[0], EventDirection::Falling, true);
+        let mut bdf = BdfSolver::new();
+        bdf.rtol = 1e-8;
+        bdf.atol = 1e-10;
+        let mut radau = RadauSolver::new();
+        radau.rtol = 1e-8;
+        radau.atol = 1e-10;
+        let mut rosenbrock = RosenbrockSolver::new();
+        rosenbrock.rtol = 1e-8;
+        rosenbrock.atol = 1e-10;
+        let solutions = [
+            bdf.solve_with_events(falling, 0.0, 10.0, [10.0, 0.0], &mut [event()]).unwrap(),
+            radau.solve_with_events(falling, 0.0, 10.0, [10.0, 0.0], &mut [event()]).unwrap(),
+            rosenbrock.solve_with_events(falling, 0.0, 10.0, [10.0, 0.0], &mut [event()]).unwrap(),
+        ];
+        for solution in solutions {
+            assert!(solution.terminated);
+            assert_relative_eq!(solution.final_time(), impact, epsilon = 1e-6, max_relative = 1e-6);
+            assert_relative_eq!(solution.interpolate(1.0).unwrap()[0], 10.0 - 9.81 / 2.0, epsilon = 1e-6, max_relative = 1e-6);
+        }
+    }
+}
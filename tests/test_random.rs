@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use quartz::math::random::Rng;
+
+    #[test]
+    fn test_reproducible() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let second: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+        let third: Vec<u64> = (0..5).map(|_| c.next_u64()).collect();
+        assert_eq!(first, second);
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn test_uniform() {
+        let mut rng = Rng::new(7);
+        let n = 100_000;
+        let samples: Vec<f64> = (0..n).map(|_| rng.uniform()).collect();
+        assert!(samples.iter().all(|&u| (0.0..1.0).contains(&u)));
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        assert!((mean - 0.5).abs() < 0.01);
+        let x = rng.uniform_range(-3.0, -2.0);
+        assert!((-3.0..-2.0).contains(&x));
+    }
+
+    #[test]
+    fn test_normal() {
+        let mut rng = Rng::new(11);
+        let n = 100_000;
+        let samples: Vec<f64> = (0..n).map(|_| rng.normal()).collect();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1) as f64;
+        assert!(mean.abs() < 0.02);
+        assert!((variance - 1.0).abs() < 0.02);
+    }
+}
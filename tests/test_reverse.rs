@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::math::double_double::DoubleDouble;
+    use quartz::math::dual::{self, Dual};
+    use quartz::math::lin_alg::spatial_vector::SpatialVector;
+    use quartz::math::reverse::{clear_tape, gradient, tape_length, Real, Scalar, Var};
+    use quartz::spatial::vector_3d::Vector3D;
+
+    #[test]
+    fn test_arithmetic() {
+        clear_tape();
+        let x = Var::new(2.0);
+        let y = Var::new(3.0);
+        // f = x y + x / y - sqrt(x) + 1 / y^2
+        let f = x * y + x / y - x.sqrt() + y.powi(-2);
+        let grad = f.gradient().unwrap();
+        assert_relative_eq!(f.value, 6.0 + 2.0 / 3.0 - 2f64.sqrt() + 1.0 / 9.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad.wrt(&x), 3.0 + 1.0 / 3.0 - 0.5 / 2f64.sqrt(), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad.wrt(&y), 2.0 - 2.0 / 9.0 - 2.0 / 27.0, epsilon = 1e-15, max_relative = 1e-15);
+        // Large powers take a logarithmic number of products
+        let one = Var::new(1.0);
+        let power = one.powi(i32::MAX) + one.powi(i32::MIN);
+        assert_eq!(power.value, 2.0);
+        assert_eq!(power.gradient().unwrap().wrt(&one), -1.0);
+        // A variable that wasn't used, and constants, have no derivative
+        let z = Var::new(5.0);
+        assert_eq!(grad.wrt(&z), 0.0);
+        let c = Var::constant(4.0) * <Var<f64> as Scalar>::from(2.0);
+        assert!(c.is_constant());
+        assert_eq!(grad.wrt(&c), 0.0);
+        // A variable used twice gets both contributions
+        let g = (x * x - x.inv()).abs().max(Var::zero());
+        assert_relative_eq!(g.gradient().unwrap().wrt(&x), 4.0 + 0.25, epsilon = 1e-15, max_relative = 1e-15);
+        assert!(Var::new(1.0) < Var::constant(2.0));
+        assert_eq!(Var::<f32>::epsilon().to_f64(), f32::EPSILON as f64);
+        assert_eq!(format!("{}", x), "2");
+    }
+
+    #[test]
+    fn test_vectors() {
+        // The gradient of |r| is r / |r|
+        let (length, grad) = gradient(|[x, y, z]| Vector3D::new(x, y, z).length(), [2.0, -3.0, 6.0]).unwrap();
+        assert_eq!(length, 7.0);
+        assert_relative_eq!(grad[0], 2.0 / 7.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad[1], -3.0 / 7.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad[2], 6.0 / 7.0, epsilon = 1e-15, max_relative = 1e-15);
+        // d(n . a) / dr for the normalized n = r / |r|, with a fixed a
+        let a = Vector3D::new(Var::constant(1.0), Var::constant(0.0), Var::constant(0.0));
+        let (_, grad) = gradient(
+            |[x, y, z]| {
+                let mut n = Vector3D::new(x, y, z);
+                n.normalize().unwrap();
+                n.dot(&a)
+            },
+            [3.0, 4.0, 0.0],
+        )
+        .unwrap();
+        assert_relative_eq!(grad[0], 16.0 / 125.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad[1], -12.0 / 125.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_eq!(grad[2], 0.0);
+    }
+
+    #[test]
+    fn test_many_inputs() {
+        // The Rosenbrock function in 100 dimensions, matched against forward
+        // mode one input at a time
+        let n = 100;
+        let x: Vec<f64> = (0..n).map(|i| 1.0 + 0.3 * ((i as f64) * 0.7).sin()).collect();
+        let start = tape_length();
+        let inputs: Vec<Var<f64>> = x.iter().map(|&v| Var::new(v)).collect();
+        let mut f = Var::zero();
+        for i in 0..n - 1 {
+            let a = inputs[i + 1] - inputs[i] * inputs[i];
+            let b = <Var<f64> as Scalar>::from(1.0) - inputs[i];
+            f += a * a * 100.0 + b * b;
+        }
+        let grad = f.gradient().unwrap();
+        for i in [0, 1, 50, 98, 99] {
+            let (_, expected) = dual::derivative(
+                |t: Dual<f64>| {
+                    let mut sum = Dual::zero();
+                    for j in 0..n - 1 {
+                        let at = |k: usize| if k == i { t } else { Dual::constant(x[k]) };
+                        let a = at(j + 1) - at(j) * at(j);
+                        let b = Dual::constant(1.0) - at(j);
+                        sum += a * a * 100.0 + b * b;
+                    }
+                    sum
+                },
+                x[i],
+            );
+            assert_relative_eq!(grad.wrt(&inputs[i]), expected, epsilon = 1e-13, max_relative = 1e-13);
+        }
+        assert!(tape_length() > start);
+        // The gradient function leaves the tape as it found it
+        let before = tape_length();
+        gradient(|[a, b]| a * b, [1.0, 2.0]).unwrap();
+        assert_eq!(tape_length(), before);
+        clear_tape();
+        assert_eq!(tape_length(), 0);
+    }
+
+    #[test]
+    fn test_cleared_tape() {
+        let x = Var::new(2.0);
+        let f = x * x;
+        clear_tape();
+        assert!(f.gradient().is_err());
+        // Anything computed from a cleared Var can't be differentiated either
+        let y = Var::new(3.0);
+        assert!((f * y).gradient().is_err());
+        assert!(gradient(|[y]| x * y, [1.0]).is_err());
+        // but new results can, and don't depend on the old Vars
+        let g = (y * y).gradient().unwrap();
+        assert_eq!(g.wrt(&y), 6.0);
+        assert_eq!(g.wrt(&x), 0.0);
+    }
+
+    #[test]
+    fn test_escaped_vars() {
+        // A Var kept from inside the gradient function is taken off the tape
+        // with the rest, even once its index is reused
+        let mut kept = None;
+        gradient(
+            |[a]| {
+                kept = Some(a * a);
+                a
+            },
+            [2.0],
+        )
+        .unwrap();
+        let kept = kept.unwrap();
+        let x = Var::new(5.0);
+        let y = x * x;
+        assert!(kept.gradient().is_err());
+        assert!((kept + y).gradient().is_err());
+        assert_eq!(y.gradient().unwrap().wrt(&kept), 0.0);
+        // while Vars from before the gradient function are untouched
+        let outer = Var::new(3.0);
+        let (_, [slope]) = gradient(|[a]| a * outer, [2.0]).unwrap();
+        assert_eq!(slope, 3.0);
+        assert_eq!((outer * outer).gradient().unwrap().wrt(&outer), 6.0);
+    }
+
+    #[test]
+    fn test_partials_keep_precision() {
+        // The derivative 2x of x^2 at 1 + 2^-60 is lost below f64 precision
+        let x = Var::new(DoubleDouble::new(1.0, 2f64.powi(-60)));
+        let grad = (x * x).gradient().unwrap().wrt(&x);
+        assert_eq!(grad, DoubleDouble::new(2.0, 2f64.powi(-59)));
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::one_d::romberg_integrator::{IntegrationError, RombergIntegrator};
+
+    #[test]
+    fn test_converges() {
+        let result = RombergIntegrator::<f64>::new().integrate(f64::exp, 0.0, 1.0);
+        assert!(result.converged);
+        assert!((result.value - (1.0_f64.exp() - 1.0)).abs() < 1e-10);
+        assert!(result.abs_error < 1.48e-8);
+    }
+
+    #[test]
+    fn test_tableau() {
+        let tableau = RombergIntegrator::<f64>::new().tableau(|x| x * x, 0.0, 1.0);
+        // The first extrapolation (Simpson's rule) is already exact for quadratics
+        assert_eq!(tableau.rows[0], vec![0.5]);
+        assert_eq!(tableau.rows[1][0], 0.375);
+        assert!((tableau.rows[1][1] - 1.0 / 3.0).abs() < 1e-15);
+        assert_eq!(tableau.diagonal().len(), tableau.rows.len());
+        assert_eq!(tableau.evaluations, 2 + 1 + 2);
+        assert!(tableau.converged);
+        for (k, row) in tableau.rows.iter().enumerate() {
+            assert_eq!(row.len(), k + 1);
+        }
+    }
+
+    #[test]
+    fn test_level_limit() {
+        let mut integrator = RombergIntegrator::<f64>::new();
+        integrator.max_levels = 3;
+        integrator.abs_tol = 0.0;
+        integrator.rel_tol = 1e-14;
+        let tableau = integrator.tableau(f64::sqrt, 0.0, 1.0);
+        assert!(!tableau.converged);
+        assert_eq!(tableau.rows.len(), 4);
+        assert_eq!(tableau.evaluations, 9);
+    }
+
+    #[test]
+    fn test_data() {
+        let vals: Vec<f64> = (0..17).map(|i| (i as f64 / 16.0).exp()).collect();
+        let tableau = RombergIntegrator::new().integrate_data(&vals, 1.0 / 16.0).unwrap();
+        assert_eq!(tableau.rows.len(), 5);
+        assert!((tableau.value() - (1.0_f64.exp() - 1.0)).abs() < 1e-12);
+        let from_func = RombergIntegrator::new().tableau(f64::exp, 0.0, 1.0);
+        assert!((tableau.rows[4][0] - from_func.rows[4][0]).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_data_errors() {
+        let integrator = RombergIntegrator::<f64>::new();
+        assert_eq!(integrator.integrate_data(&[], 1.0), Err(IntegrationError::EmptyInput));
+        assert_eq!(
+            integrator.integrate_data(&[1.0; 6], 1.0),
+            Err(IntegrationError::InvalidSampleCount { count: 6 })
+        );
+        assert_eq!(integrator.integrate_data(&[1.0, 3.0], 2.0).unwrap().value(), 4.0);
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::math::dual::{self, Dual};
+    use quartz::math::reverse::{self, Var};
+    use quartz::math::scalar::{Float, Real, Scalar};
+    use quartz::spatial::vector_3d::Vector3D;
+
+    // A function in forward mode, the same in reverse mode, and its slope
+    type Case = (fn(Dual<f64>) -> Dual<f64>, fn(Var<f64>) -> Var<f64>, f64);
+
+    // Rotates v about the z axis, written once for every Float
+    fn rotate<S: Float>(v: Vector3D<S>, angle: S) -> Vector3D<S> {
+        let (c, s) = (angle.cos(), angle.sin());
+        Vector3D::new(c * v.x - s * v.y, s * v.x + c * v.y, v.z)
+    }
+
+    // The sinc function, with its removable singularity
+    fn sinc<S: Float>(x: S) -> S {
+        if x.abs() < S::epsilon() {
+            S::one()
+        } else {
+            x.sin() / x
+        }
+    }
+
+    #[test]
+    fn test_generic_functions() {
+        let v = rotate(Vector3D::new(1.0f32, 0.0, 2.0), f32::pi() / 2.0);
+        assert!(v.x.abs() < 1e-7 && (v.y - 1.0).abs() < 1e-7 && v.z == 2.0);
+        let v = rotate(Vector3D::new(1.0f64, 1.0, 0.0), -f64::pi() / 4.0);
+        assert_relative_eq!(v.x, 2f64.sqrt(), epsilon = 1e-15, max_relative = 1e-15);
+        assert!(v.y.abs() < 1e-15);
+        assert_eq!(sinc(0.0f64), 1.0);
+        assert_relative_eq!(sinc(f64::pi() / 2.0), 2.0 / f64::pi(), epsilon = 1e-15, max_relative = 1e-15);
+        assert_eq!(f64::one(), 1.0);
+        assert_eq!(f32::e(), std::f32::consts::E);
+        assert!(f64::nan().is_nan() && !f64::infinity().is_finite());
+        assert_eq!(Float::atan2(1.0f64, -1.0), 3.0 * f64::pi() / 4.0);
+        assert_eq!(Float::powi(2.0f32, -2), 0.25);
+    }
+
+    #[test]
+    fn test_differentiable_functions() {
+        // Every function against its known derivative, in both modes
+        let x = 0.4;
+        let cases: Vec<Case> = vec![
+            (|t| t.exp(), |t| t.exp(), x.exp()),
+            (|t| t.ln(), |t| t.ln(), 1.0 / x),
+            (|t| t.log10(), |t| t.log10(), 1.0 / (x * 10f64.ln())),
+            (|t| t.powf(Dual::constant(2.5)), |t| t.powf(Var::constant(2.5)), 2.5 * x.powf(1.5)),
+            (|t| Float::powi(t, 3), |t| Float::powi(t, 3), 3.0 * x * x),
+            (|t| t.cbrt(), |t| t.cbrt(), x.powf(-2.0 / 3.0) / 3.0),
+            (|t| t.sin(), |t| t.sin(), x.cos()),
+            (|t| t.cos(), |t| t.cos(), -x.sin()),
+            (|t| t.tan(), |t| t.tan(), 1.0 / (x.cos() * x.cos())),
+            (|t| t.asin(), |t| t.asin(), 1.0 / (1.0 - x * x).sqrt()),
+            (|t| t.acos(), |t| t.acos(), -1.0 / (1.0 - x * x).sqrt()),
+            (|t| t.atan(), |t| t.atan(), 1.0 / (1.0 + x * x)),
+            (|t| t.sinh(), |t| t.sinh(), x.cosh()),
+            (|t| t.cosh(), |t| t.cosh(), x.sinh()),
+            (|t| t.tanh(), |t| t.tanh(), 1.0 / (x.cosh() * x.cosh())),
+            (|t| t.floor() + t, |t| t.floor() + t, 1.0),
+            // x^x, through both the base and the exponent
+            (|t| t.powf(t), |t| t.powf(t), x.powf(x) * (x.ln() + 1.0)),
+        ];
+        for (forward, backward, expected) in cases {
+            let (_, slope) = dual::derivative(forward, x);
+            assert_relative_eq!(slope, expected, epsilon = 1e-14, max_relative = 1e-14);
+            let (_, [slope]) = reverse::gradient(|[t]| backward(t), [x]).unwrap();
+            assert_relative_eq!(slope, expected, epsilon = 1e-14, max_relative = 1e-14);
+        }
+        // Functions of two variables
+        let (angle, grad) = dual::gradient(|[y, x]| y.atan2(x), [1.0, 2.0]);
+        assert_eq!(angle, 0.5f64.atan());
+        assert_relative_eq!(grad[0], 2.0 / 5.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad[1], -1.0 / 5.0, epsilon = 1e-15, max_relative = 1e-15);
+        let (length, grad) = reverse::gradient(|[a, b]| a.hypot(b), [3.0, 4.0]).unwrap();
+        assert_eq!(length, 5.0);
+        assert_relative_eq!(grad[0], 0.6, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(grad[1], 0.8, epsilon = 1e-15, max_relative = 1e-15);
+        // A negative base with a constant exponent still has a derivative
+        let (value, slope) = dual::derivative(|t| t.powf(Dual::constant(2.0)), -3.0);
+        assert_relative_eq!(value, 9.0, epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(slope, -6.0, epsilon = 1e-15, max_relative = 1e-15);
+        // Rotating by a dual angle gives the velocity of the rotation
+        let x_axis = Vector3D::new(Dual::constant(1.0), Dual::constant(0.0), Dual::constant(0.0));
+        let v = rotate(x_axis, Dual::variable(0.3, 0));
+        assert_relative_eq!(v.x.derivative(), -(0.3f64.sin()), epsilon = 1e-15, max_relative = 1e-15);
+        assert_relative_eq!(v.y.derivative(), 0.3f64.cos(), epsilon = 1e-15, max_relative = 1e-15);
+        assert!(Dual::<f64>::pi().to_f64() == std::f64::consts::PI);
+    }
+}
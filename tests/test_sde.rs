@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::sde::sde_solver::{OdeError, Rng, SdeCalculus, SdeMethod, SdeSolver};
+    use quartz::spatial::vector_3d::Vector3D;
+
+    const METHODS: [SdeMethod; 3] = [SdeMethod::EulerMaruyama, SdeMethod::Milstein, SdeMethod::StochasticRungeKutta];
+
+    // Geometric Brownian motion dX = mu X dt + sigma X dW
+    const MU: f64 = 1.5;
+    const SIGMA: f64 = 1.0;
+
+    fn drift(_: f64, x: &f64) -> f64 {
+        MU * x
+    }
+
+    fn diffusion(_: f64, x: &f64) -> f64 {
+        SIGMA * x
+    }
+
+    // The mean absolute error at t = 1 over many Brownian paths, each
+    // simulated with 2^10 increments and with those summed in fours
+    fn strong_errors(method: SdeMethod, calculus: SdeCalculus) -> (f64, f64) {
+        let solver = SdeSolver::new(method, calculus, 0);
+        let mut rng = Rng::new(11);
+        let paths = 200;
+        let (mut fine_error, mut coarse_error) = (0.0, 0.0);
+        for _ in 0..paths {
+            let fine: Vec<f64> = (0..1024).map(|_| rng.normal() / 32.0).collect();
+            let coarse: Vec<f64> = fine.chunks(4).map(|c| c.iter().sum()).collect();
+            let w: f64 = fine.iter().sum();
+            let exact = match calculus {
+                SdeCalculus::Ito => ((MU - SIGMA * SIGMA / 2.0) + SIGMA * w).exp(),
+                SdeCalculus::Stratonovich => (MU + SIGMA * w).exp(),
+            };
+            let a = solver.solve_with_increments(drift, diffusion, 0.0, 1.0, 1.0, &fine).unwrap();
+            let b = solver.solve_with_increments(drift, diffusion, 0.0, 1.0, 1.0, &coarse).unwrap();
+            assert!((a.brownian[1024] - w).abs() < 1e-12);
+            fine_error += (a.final_state() - exact).abs() / paths as f64;
+            coarse_error += (b.final_state() - exact).abs() / paths as f64;
+        }
+        (fine_error, coarse_error)
+    }
+
+    #[test]
+    fn test_strong_order() {
+        for calculus in [SdeCalculus::Ito, SdeCalculus::Stratonovich] {
+            for method in METHODS {
+                let (fine, coarse) = strong_errors(method, calculus);
+                // A quarter of the step size cuts the error by 4^order
+                let ratio = coarse / fine;
+                // Euler-Heun is first order for a single noise source
+                match method {
+                    SdeMethod::EulerMaruyama if calculus == SdeCalculus::Ito => assert!(ratio > 1.6 && ratio < 2.5),
+                    _ => assert!(ratio > 3.2 && ratio < 5.0),
+                }
+                assert!(fine < 0.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_brownian_motion_in_space() {
+        // Free diffusion: the mean squared displacement grows as 3 D^2 t
+        let d = 0.5;
+        let solver = SdeSolver::new(SdeMethod::EulerMaruyama, SdeCalculus::Ito, 2024);
+        let drift = |_: f64, _: &Vector3D<f64>| Vector3D::new(0.0, 0.0, 0.0);
+        let diffusion = |_: f64, _: &Vector3D<f64>| Vector3D::new(d, d, d);
+        let origin = Vector3D::new(0.0, 0.0, 0.0);
+        let paths = solver.solve_paths(drift, diffusion, 0.0, 2.0, origin, 50, 4000).unwrap();
+        assert_eq!(paths.len(), 4000);
+        let msd = paths.iter().map(|p| {
+            let x = p.final_state();
+            x.x * x.x + x.y * x.y + x.z * x.z
+        });
+        let msd = msd.sum::<f64>() / 4000.0;
+        assert!((msd - 3.0 * d * d * 2.0).abs() < 0.05 * 3.0 * d * d * 2.0);
+        // The displacement is the Brownian path itself
+        assert_eq!(paths[7].final_state().x, d * paths[7].brownian[50].x);
+        // Each path is reproducible on its own
+        let fewer = solver.solve_paths(drift, diffusion, 0.0, 2.0, origin, 50, 10).unwrap();
+        assert_eq!(fewer[7], paths[7]);
+        assert_ne!(paths[7], paths[8]);
+        assert_eq!(solver.solve(drift, diffusion, 0.0, 2.0, origin, 50).unwrap(), paths[0]);
+    }
+
+    #[test]
+    fn test_ornstein_uhlenbeck() {
+        // dX = -theta X dt + s dW has mean x0 e^(-theta t) and variance
+        // s^2 (1 - e^(-2 theta t)) / (2 theta)
+        let (theta, s, x0, t) = (2.0, 0.8, 1.0, 1.0);
+        for method in METHODS {
+            let solver = SdeSolver::new(method, SdeCalculus::Ito, 5);
+            let paths = solver.solve_paths(|_, x: &f64| -theta * x, |_, _: &f64| s, 0.0, t, x0, 200, 4000).unwrap();
+            let finals: Vec<f64> = paths.iter().map(|p| *p.final_state()).collect();
+            let mean = finals.iter().sum::<f64>() / finals.len() as f64;
+            let variance = finals.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (finals.len() - 1) as f64;
+            let expected_variance = s * s * (1.0 - (-2.0 * theta * t).exp()) / (2.0 * theta);
+            assert!((mean - x0 * (-theta * t).exp()).abs() < 0.02);
+            assert!((variance - expected_variance).abs() < 0.1 * expected_variance);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let solver = SdeSolver::new(SdeMethod::Milstein, SdeCalculus::Ito, 0);
+        assert_eq!(solver.solve(drift, diffusion, 0.0, 1.0, 1.0, 0), Err(OdeError::InvalidStepCount { count: 0 }));
+        assert_eq!(solver.solve_with_increments(drift, diffusion, 0.0, 1.0, 1.0, &[]), Err(OdeError::InvalidStepCount { count: 0 }));
+    }
+}
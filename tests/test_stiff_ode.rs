@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::ode::bdf::BdfSolver;
+    use quartz::integrate::ode::radau::RadauSolver;
+    use quartz::integrate::ode::rosenbrock::RosenbrockSolver;
+    use quartz::integrate::ode::runge_kutta::{RungeKuttaMethod, RungeKuttaSolver};
+    use quartz::math::lin_alg::dense_matrix::DenseMatrix;
+    use quartz::spatial::vector_3d::Vector3D;
+
+    // Robertson's chemical kinetics problem, a classic stiff test
+    fn robertson(_: f64, y: &[f64; 3]) -> [f64; 3] {
+        [
+            -0.04 * y[0] + 1e4 * y[1] * y[2],
+            0.04 * y[0] - 1e4 * y[1] * y[2] - 3e7 * y[1] * y[1],
+            3e7 * y[1] * y[1],
+        ]
+    }
+
+    fn robertson_jacobian(_: f64, y: &[f64; 3]) -> DenseMatrix<f64> {
+        DenseMatrix::from_rows(&[
+            vec![-0.04, 1e4 * y[2], 1e4 * y[1]],
+            vec![0.04, -1e4 * y[2] - 6e7 * y[1], -1e4 * y[1]],
+            vec![0.0, 6e7 * y[1], 0.0],
+        ])
+        .unwrap()
+    }
+
+    // The solution at t = 40
+    const ROBERTSON_40: [f64; 3] = [0.7158270687193, 9.185534764e-6, 0.2841637457043];
+
+    fn check_robertson(y: &[f64; 3]) {
+        for (a, b) in y.iter().zip(ROBERTSON_40.iter()) {
+            assert_relative_eq!(*a, *b, max_relative = 1e-5);
+        }
+        assert_relative_eq!(y.iter().sum(), 1.0, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_zero_atol() {
+        // y0' = -1000 y0 + y1 and y1' = -y1, where y0 starts at zero, whose
+        // column of the Jacobian still needs a difference step with atol = 0.
+        // The first step is given, as estimating it needs atol.
+        let f = |_: f64, y: &[f64; 2]| [-1000.0 * y[0] + y[1], -y[1]];
+        let mut solver = RosenbrockSolver::new();
+        solver.atol = 0.0;
+        solver.rtol = 1e-6;
+        solver.first_step = Some(1e-4);
+        let y = *solver.solve(f, 0.0, 1.0, [0.0, 1.0]).unwrap().final_state();
+        assert_relative_eq!(y[0], ((-1f64).exp() - (-1000f64).exp()) / 999.0, max_relative = 1e-4);
+        assert_relative_eq!(y[1], (-1f64).exp(), max_relative = 1e-4);
+    }
+
+    #[test]
+    fn test_bdf() {
+        let mut solver = BdfSolver::new();
+        solver.rtol = 1e-6;
+        solver.atol = 1e-10;
+        let numeric = solver.solve(robertson, 0.0, 40.0, [1.0, 0.0, 0.0]).unwrap();
+        check_robertson(numeric.final_state());
+        let analytic = solver.solve_with_jacobian(robertson, robertson_jacobian, 0.0, 40.0, [1.0, 0.0, 0.0]).unwrap();
+        check_robertson(analytic.final_state());
+        assert!(analytic.evaluations < numeric.evaluations);
+    }
+
+    #[test]
+    fn test_radau() {
+        let mut solver = RadauSolver::new();
+        solver.rtol = 1e-6;
+        solver.atol = 1e-10;
+        let numeric = solver.solve(robertson, 0.0, 40.0, [1.0, 0.0, 0.0]).unwrap();
+        check_robertson(numeric.final_state());
+        let analytic = solver.solve_with_jacobian(robertson, robertson_jacobian, 0.0, 40.0, [1.0, 0.0, 0.0]).unwrap();
+        check_robertson(analytic.final_state());
+        assert!(analytic.evaluations < numeric.evaluations);
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        let mut solver = RosenbrockSolver::new();
+        solver.rtol = 1e-6;
+        solver.atol = 1e-10;
+        let numeric = solver.solve(robertson, 0.0, 40.0, [1.0, 0.0, 0.0]).unwrap();
+        check_robertson(numeric.final_state());
+        let analytic = solver.solve_with_jacobian(robertson, robertson_jacobian, 0.0, 40.0, [1.0, 0.0, 0.0]).unwrap();
+        check_robertson(analytic.final_state());
+        assert!(analytic.evaluations < numeric.evaluations);
+    }
+
+    #[test]
+    fn test_long_times() {
+        // Explicit methods would need billions of steps to get this far
+        let mut bdf = BdfSolver::new();
+        bdf.rtol = 1e-6;
+        bdf.atol = 1e-10;
+        let mut radau = RadauSolver::new();
+        radau.rtol = 1e-6;
+        radau.atol = 1e-10;
+        let solutions = [
+            bdf.solve(robertson, 0.0, 4e10, [1.0, 0.0, 0.0]).unwrap(),
+            radau.solve(robertson, 0.0, 4e10, [1.0, 0.0, 0.0]).unwrap(),
+        ];
+        for solution in solutions.iter() {
+            assert!(solution.accepted_steps < 1000);
+            let y = solution.final_state();
+            assert_relative_eq!(y[0], 5.2e-8, max_relative = 0.01);
+            assert_relative_eq!(y[2], 1.0, max_relative = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fewer_steps_than_explicit() {
+        // The solution hugs cos(t), but perturbations decay at rate 1000
+        let f = |t: f64, y: &f64| -1000.0 * (y - t.cos()) - t.sin();
+        let explicit = RungeKuttaSolver::new(RungeKuttaMethod::Rk45).solve(f, 0.0, 10.0, 1.0).unwrap();
+        let implicit = [
+            BdfSolver::new().solve(f, 0.0, 10.0, 1.0).unwrap(),
+            RadauSolver::new().solve(f, 0.0, 10.0, 1.0).unwrap(),
+            RosenbrockSolver::new().solve(f, 0.0, 10.0, 1.0).unwrap(),
+        ];
+        for solution in implicit.iter() {
+            assert!(10 * solution.accepted_steps < explicit.accepted_steps);
+            assert!((solution.final_state() - 10f64.cos()).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_vector_3d_and_backwards() {
+        let rates = Vector3D::new(-1.0, -100.0, -10000.0);
+        let f = |_: f64, y: &Vector3D<f64>| Vector3D::new(rates.x * y.x, rates.y * y.y, rates.z * y.z);
+        let mut solver = RadauSolver::new();
+        solver.rtol = 1e-8;
+        solver.atol = 1e-12;
+        let solution = solver.solve(f, 0.0, 1.0, Vector3D::new(1.0, 1.0, 1.0)).unwrap();
+        let y = solution.final_state();
+        assert_relative_eq!(y.x, (-1.0f64).exp(), max_relative = 1e-7);
+        assert!(y.y.abs() < 1e-12 && y.z.abs() < 1e-12);
+        let solution = BdfSolver::new().solve(|_, y: &f64| *y, 1.0, 0.0, 1f64.exp()).unwrap();
+        assert_eq!(solution.final_time(), 0.0);
+        assert_relative_eq!(*solution.final_state(), 1.0, max_relative = 1e-2);
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::one_d::integrator::Integrator;
+    use quartz::integrate::one_d::streaming_integrator::{IntegrationError, StreamingTrapezoidIntegrator};
+    use quartz::integrate::one_d::trapezoid_integrator::TrapezoidIntegrator;
+
+    #[test]
+    fn test_matches_batch() {
+        let times: [f64; 5] = [0.0, 0.5, 1.25, 2.0, 3.5];
+        let vals = [1.0, -2.0, 4.0, 0.5, 3.0];
+        let mut stream = StreamingTrapezoidIntegrator::new();
+        assert_eq!(stream.integral(), 0.0);
+        for (&t, &v) in times.iter().zip(vals.iter()) {
+            stream.push(t, v).unwrap();
+        }
+        let batch = TrapezoidIntegrator::new().integrate_data_time_series(&times, &vals).unwrap();
+        assert!((stream.integral() - batch).abs() < 1e-12);
+        assert_eq!(stream.count(), 5);
+        assert_eq!(stream.last_sample(), Some((3.5, 3.0)));
+    }
+
+    #[test]
+    fn test_rejects_out_of_order() {
+        let mut stream = StreamingTrapezoidIntegrator::new();
+        assert_eq!(stream.push(1.0, 2.0), Ok(0.0));
+        assert_eq!(stream.push(2.0, 2.0), Ok(2.0));
+        assert_eq!(stream.push(2.0, 5.0), Err(IntegrationError::NonMonotonicTimes { index: 2 }));
+        assert_eq!(stream.push(1.5, 5.0), Err(IntegrationError::NonMonotonicTimes { index: 2 }));
+        assert_eq!(stream.push(f64::NAN, 5.0), Err(IntegrationError::NonMonotonicTimes { index: 2 }));
+        assert_eq!(stream.integral(), 2.0);
+        assert_eq!(stream.push(3.0, 4.0), Ok(5.0));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut stream = StreamingTrapezoidIntegrator::new();
+        stream.push(0.0, 1.0).unwrap();
+        stream.push(1.0, 1.0).unwrap();
+        stream.reset();
+        assert_eq!(stream.integral(), 0.0);
+        assert_eq!(stream.count(), 0);
+        assert_eq!(stream.last_sample(), None);
+        // Earlier times are fine after a reset
+        assert_eq!(stream.push(-1.0, 3.0), Ok(0.0));
+        assert_eq!(stream.push(0.0, 3.0), Ok(3.0));
+    }
+
+    #[test]
+    fn test_window() {
+        let mut stream = StreamingTrapezoidIntegrator::<f64>::windowed(2.0).unwrap();
+        for i in 0..10 {
+            let t = i as f64;
+            stream.push(t, t).unwrap();
+        }
+        // The integral of t over [7, 9]
+        assert!((stream.integral() - 16.0).abs() < 1e-12);
+        // The window starts partway between samples
+        stream.push(9.5, 9.5).unwrap();
+        assert!((stream.integral() - (9.5 * 9.5 - 7.5 * 7.5) / 2.0).abs() < 1e-12);
+        assert_eq!(stream.window(), Some(2.0));
+        // Before the window fills up, every sample is integrated
+        let mut stream = StreamingTrapezoidIntegrator::windowed(10.0).unwrap();
+        stream.push(0.0, 1.0).unwrap();
+        assert_eq!(stream.push(4.0, 1.0), Ok(4.0));
+        assert!(StreamingTrapezoidIntegrator::windowed(0.0).is_err());
+        assert!(StreamingTrapezoidIntegrator::windowed(-1.0).is_err());
+        assert!(StreamingTrapezoidIntegrator::windowed(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_long_window_stream() {
+        // Large areas passing through the window would leave their rounding
+        // errors behind in a running total
+        let mut stream = StreamingTrapezoidIntegrator::<f64>::windowed(1.0).unwrap();
+        for i in 0..100_000 {
+            let t = i as f64 * 0.25;
+            let v = if i < 50_000 { 1e12 * (1.0 + (t * 0.7).sin()) } else { 1.0 };
+            stream.push(t, v).unwrap();
+        }
+        assert!((stream.integral() - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use quartz::integrate::ode::symplectic::{
+        Direction, MechanicalState, OdeError, Position, SpatialVector, SymplecticMethod, SymplecticSolver,
+    };
+    use quartz::spatial::vector_3d::Vector3D;
+    use std::f64::consts::PI;
+
+    type State = MechanicalState<f64, Vector3D<f64>>;
+
+    const METHODS: [SymplecticMethod; 4] =
+        [SymplecticMethod::VelocityVerlet, SymplecticMethod::Leapfrog, SymplecticMethod::Yoshida4, SymplecticMethod::ForestRuth];
+
+    // A planet orbiting a unit mass sun at the origin, with G = 1
+    fn gravity(_: f64, x: &[Position<f64, Vector3D<f64>>]) -> Vec<Direction<f64, Vector3D<f64>>> {
+        x.iter().map(|p| Direction::new(p.v * (-1.0 / p.length().powi(3)))).collect()
+    }
+
+    // Starts at perihelion of an orbit with semi-major axis 1, so the period is 2 pi
+    fn orbit(eccentricity: f64) -> State {
+        let r = 1.0 - eccentricity;
+        let v = ((1.0 + eccentricity) / r).sqrt();
+        MechanicalState::new(vec![Position::new(Vector3D::new(r, 0.0, 0.0))], vec![Direction::new(Vector3D::new(0.0, v, 0.0))])
+    }
+
+    fn energy(state: &State) -> f64 {
+        state.kinetic_energy() - 1.0 / state.positions[0].length()
+    }
+
+    fn distance(a: &State, b: &State) -> f64 {
+        (a.positions[0] - b.positions[0]).length()
+    }
+
+    #[test]
+    fn test_circular_orbit() {
+        let start = orbit(0.0);
+        for method in METHODS {
+            let solution = SymplecticSolver::new(method).solve(gravity, 0.0, 2.0 * PI, start.clone(), 1000).unwrap();
+            assert_eq!(solution.final_time(), 2.0 * PI);
+            assert_eq!(solution.states.len(), 1001);
+            let tol = if method.order() == 2 { 1e-3 } else { 1e-8 };
+            assert!(distance(solution.final_state(), &start) < tol);
+        }
+    }
+
+    #[test]
+    fn test_order() {
+        let start = orbit(0.5);
+        for method in METHODS {
+            let solver = SymplecticSolver::new(method);
+            let coarse = solver.solve(gravity, 0.0, 2.0 * PI, start.clone(), 400).unwrap();
+            let fine = solver.solve(gravity, 0.0, 2.0 * PI, start.clone(), 800).unwrap();
+            let ratio = distance(coarse.final_state(), &start) / distance(fine.final_state(), &start);
+            let expected = 2f64.powi(method.order() as i32);
+            assert!(ratio > 0.8 * expected && ratio < 1.25 * expected);
+        }
+    }
+
+    #[test]
+    fn test_energy_stays_bounded() {
+        // A hundred orbits: the energy error oscillates rather than drifting,
+        // so it is no worse at the end than during the first orbit
+        let start = orbit(0.5);
+        let e0 = energy(&start);
+        for method in METHODS {
+            let solution = SymplecticSolver::new(method).solve(gravity, 0.0, 200.0 * PI, start.clone(), 100 * 500).unwrap();
+            let errors: Vec<f64> = solution.states.iter().map(|s| (energy(s) - e0).abs()).collect();
+            let first_orbit = errors[..=500].iter().cloned().fold(0.0, f64::max);
+            let last_orbit = errors[errors.len() - 501..].iter().cloned().fold(0.0, f64::max);
+            assert!(first_orbit < 1e-2);
+            assert!(last_orbit < 1.1 * first_orbit);
+        }
+    }
+
+    #[test]
+    fn test_evaluations() {
+        // Methods that end with a kick share it with the start of the next step
+        let expected = [(11, 2), (10, 1), (31, 4), (30, 3)];
+        for (method, (evaluations, per_step)) in METHODS.into_iter().zip(expected) {
+            let solver = SymplecticSolver::new(method);
+            let solution = solver.solve(gravity, 0.0, 1.0, orbit(0.0), 10).unwrap();
+            assert_eq!(solution.evaluations, evaluations);
+            let mut state = orbit(0.0);
+            let mut a = gravity;
+            assert_eq!(solver.step(&mut a, 0.0, &mut state, 0.1), Ok(per_step));
+            assert_eq!(state, solution.states[1]);
+        }
+    }
+
+    #[test]
+    fn test_two_particles() {
+        // Two unit masses joined by a unit spring oscillate about their centre
+        // of mass with angular frequency sqrt(2)
+        let spring = |_: f64, x: &[Position<f64, Vector3D<f64>>]| {
+            let stretch = Direction::new((x[1] - x[0]).v);
+            vec![stretch * 1.0, stretch * -1.0]
+        };
+        let start = MechanicalState::new(
+            vec![Position::new(Vector3D::new(-1.0, 0.0, 0.0)), Position::new(Vector3D::new(1.0, 0.0, 0.0))],
+            vec![Direction::zero(), Direction::zero()],
+        );
+        let period = 2.0 * PI / 2f64.sqrt();
+        let solution = SymplecticSolver::new(SymplecticMethod::Yoshida4).solve(spring, 0.0, period / 2.0, start, 200).unwrap();
+        let end = solution.final_state();
+        assert!((end.positions[0].v.x - 1.0).abs() < 1e-8);
+        assert!((end.positions[1].v.x + 1.0).abs() < 1e-8);
+        assert!(end.kinetic_energy().abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_errors() {
+        let solver = SymplecticSolver::default();
+        assert_eq!(solver.method, SymplecticMethod::VelocityVerlet);
+        assert_eq!(solver.solve(gravity, 0.0, 1.0, orbit(0.0), 0), Err(OdeError::InvalidStepCount { count: 0 }));
+        // One particle given the accelerations of two
+        let doubled = |t: f64, x: &[Position<f64, Vector3D<f64>>]| gravity(t, x).repeat(2);
+        assert_eq!(solver.solve(doubled, 0.0, 1.0, orbit(0.0), 10), Err(OdeError::AccelerationCount { expected: 1, found: 2 }));
+        // One particle with two velocities
+        let mut state = orbit(0.0);
+        state.velocities.push(state.velocities[0]);
+        let error = OdeError::VelocityCount { positions: 1, velocities: 2 };
+        assert_eq!(solver.solve(gravity, 0.0, 1.0, state.clone(), 10), Err(error));
+        assert_eq!(solver.step(&mut gravity, 0.0, &mut state, 0.1), Err(error));
+    }
+}
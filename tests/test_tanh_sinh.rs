@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use quartz::assert_relative_eq;
+    use quartz::integrate::one_d::tanh_sinh_integrator::TanhSinhIntegrator;
+    use std::f64::consts::PI;
+    
+
+    #[test]
+    fn test_finite() {
+        let result = TanhSinhIntegrator::new().integrate(f64::sin, 0.0, PI);
+        assert!(result.converged);
+        assert_relative_eq!(result.value, 2.0, epsilon = 1e-10, max_relative = 1e-10);
+    }
+
+    #[test]
+    fn test_endpoint_singularities() {
+        let integrator = TanhSinhIntegrator::new();
+        let result = integrator.integrate(|x: f64| 1.0 / x.sqrt(), 0.0, 1.0);
+        assert!(result.converged);
+        assert_relative_eq!(result.value, 2.0, epsilon = 1e-10, max_relative = 1e-10);
+        assert_relative_eq!(integrator.integrate(f64::ln, 0.0, 1.0).value, -1.0, epsilon = 1e-10, max_relative = 1e-10);
+        let result = integrator.integrate(|x: f64| 1.0 / (1.0 - x * x).sqrt(), -1.0, 1.0);
+        // Computing 1 - x^2 near the bounds loses about half the digits
+        assert!((result.value - PI).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_infinite_bounds() {
+        let integrator = TanhSinhIntegrator::new();
+        let result = integrator.integrate(|x: f64| (-x).exp(), 0.0, f64::INFINITY);
+        assert!(result.converged);
+        assert_relative_eq!(result.value, 1.0, epsilon = 1e-10, max_relative = 1e-10);
+        assert_relative_eq!(integrator.integrate(|x: f64| 1.0 / (x * x), 1.0, f64::INFINITY).value, 1.0, epsilon = 1e-10, max_relative = 1e-10);
+        assert_relative_eq!(integrator.integrate(f64::exp, f64::NEG_INFINITY, 0.0).value, 1.0, epsilon = 1e-10, max_relative = 1e-10);
+        let result = integrator.integrate(|x: f64| (-x * x).exp(), f64::NEG_INFINITY, f64::INFINITY);
+        assert!(result.converged);
+        assert_relative_eq!(result.value, PI.sqrt(), epsilon = 1e-10, max_relative = 1e-10);
+        assert_relative_eq!(integrator.integrate(|x: f64| 1.0 / (1.0 + x * x), f64::NEG_INFINITY, f64::INFINITY).value, PI, epsilon = 1e-10, max_relative = 1e-10);
+    }
+
+    #[test]
+    fn test_reversed_and_empty() {
+        let integrator = TanhSinhIntegrator::new();
+        assert_relative_eq!(integrator.integrate(|x: f64| (-x).exp(), f64::INFINITY, 0.0).value, -1.0, epsilon = 1e-10, max_relative = 1e-10);
+        assert_relative_eq!(integrator.integrate(|x: f64| x, 1.0, 0.0).value, -0.5, epsilon = 1e-10, max_relative = 1e-10);
+        let result = integrator.integrate(|x: f64| x, 2.0, 2.0);
+        assert_eq!(result.value, 0.0);
+        assert_eq!(result.evaluations, 0);
+    }
+
+    #[test]
+    fn test_f32() {
+        let result = TanhSinhIntegrator::<f32>::new().integrate(|x: f32| 1.0 / x.sqrt(), 0.0, 4.0);
+        assert!((result.value - 4.0).abs() < 1e-4);
+    }
+}
@@ -1,7 +1,7 @@
 
 #[cfg(test)]
 mod tests {
-    use quartz::integrate::one_d::trapezoid_integrator::{Integrator, TrapezoidIntegrator};
+    use quartz::integrate::one_d::trapezoid_integrator::{IntegrationError, Integrator, TrapezoidIntegrator};
     
     fn linear(x: f64) -> f64 {
         2_f64*x + 3_f64
@@ -9,8 +9,53 @@ mod tests {
     
     #[test]
     fn test_linear() {
-        assert_eq!( TrapezoidIntegrator::<f64>::integrate_func(linear, 2.0_f64, 5.0_f64, 20), (5*5 + 5*3 - 2*2 - 2*3) as f64 );
+        assert_eq!( TrapezoidIntegrator::<f64>::new().integrate_func(linear, 2.0_f64, 5.0_f64, 20), Ok((5*5 + 5*3 - 2*2 - 2*3) as f64) );
 
     }
 
+    #[test]
+    fn test_closure() {
+        let slope = 2.0_f64;
+        let mut calls = 0;
+        let value = TrapezoidIntegrator::new().integrate_func(|x| { calls += 1; slope*x }, 0.0, 1.0, 4).unwrap();
+        assert_eq!( value, 1.0 );
+        assert_eq!( calls, 5 );
+        assert_eq!( TrapezoidIntegrator::new().integrate_func(linear, 0.0, 1.0, 0), Err(IntegrationError::InvalidSampleCount { count: 0 }) );
+    }
+
+    #[test]
+    fn test_data() {
+        let integrator = TrapezoidIntegrator::<f64>::new();
+        assert_eq!( integrator.integrate_data(&[1.0, 2.0, 3.0], 0.5), Ok(2.0) );
+        assert_eq!( integrator.integrate_data(&[4.0], 0.5), Ok(0.0) );
+        assert_eq!( integrator.integrate_data(&[], 0.5), Err(IntegrationError::EmptyInput) );
+    }
+
+    #[test]
+    fn test_time_series() {
+        let integrator = TrapezoidIntegrator::<f64>::new();
+        let times = [0.0, 1.0, 3.0];
+        assert_eq!( integrator.integrate_data_time_series(&times, &[1.0, 1.0, 2.0]), Ok(4.0) );
+        assert_eq!( integrator.integrate_func_time_series(linear, &times), Ok(9.0 + 9.0) );
+    }
+
+    #[test]
+    fn test_time_series_errors() {
+        let integrator = TrapezoidIntegrator::<f64>::new();
+        assert_eq!(
+            integrator.integrate_data_time_series(&[0.0, 1.0], &[1.0, 2.0, 3.0]),
+            Err(IntegrationError::LengthMismatch { times: 2, vals: 3 })
+        );
+        assert_eq!(
+            integrator.integrate_data_time_series(&[0.0, 2.0, 1.0], &[1.0, 2.0, 3.0]),
+            Err(IntegrationError::NonMonotonicTimes { index: 2 })
+        );
+        assert_eq!( integrator.integrate_func_time_series(linear, &[]), Err(IntegrationError::EmptyInput) );
+        // A NaN time stamp is out of order with its neighbours
+        assert_eq!(
+            integrator.integrate_data_time_series(&[0.0, f64::NAN, 1.0], &[1.0, 2.0, 3.0]),
+            Err(IntegrationError::NonMonotonicTimes { index: 1 })
+        );
+    }
+
 }